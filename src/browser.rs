@@ -0,0 +1,816 @@
+//! Browser and WebDriver setup module.
+//!
+//! Drives Chrome (chromedriver) or Firefox (geckodriver), selected with `BROWSER`.
+//! Builds the browser capabilities (headless mode, window size, user agent, proxy,
+//! profile directory) from the environment, and either spawns the local driver on
+//! a free port or connects to an existing remote WebDriver such as a Selenium Grid
+//! (`WEBDRIVER_URL`).
+//!
+//! The WebDriver is ready once its `/status` endpoint reports so; the driver's
+//! output is written to `CHROMEDRIVER_LOG` (or `GECKODRIVER_LOG`) so that startup
+//! failures can be read back.
+//!
+//! Behaviour that differs between the browsers, such as the console log used by
+//! diagnostics and how the Slate.js description editor is typed into, is described
+//! by `BrowserKind`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use fantoccini::wd::Capabilities;
+use fantoccini::{Client, ClientBuilder};
+use serde_json::{json, Value};
+use std::env;
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info};
+
+/// How long to wait for the WebDriver to become ready when `WEBDRIVER_STARTUP_TIMEOUT` is not set
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between two `/status` requests
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of driver log lines included in startup errors
+const LOG_TAIL_LINES: usize = 20;
+
+/// Browser driven by the automation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BrowserKind {
+    /// Google Chrome or Chromium, through chromedriver
+    #[default]
+    Chrome,
+    /// Mozilla Firefox, through geckodriver
+    Firefox,
+}
+
+/// How text is typed into Spotify's Slate.js description editor when pasting it
+/// does not work (see `editor.rs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextInput {
+    /// One key event per character; Slate drops characters when chromedriver sends
+    /// a whole string at once
+    PerCharacter,
+    /// The whole text in a single send, which geckodriver dispatches as separate
+    /// key events that Slate handles
+    Whole,
+}
+
+impl FromStr for BrowserKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "chrome" | "chromium" => Ok(BrowserKind::Chrome),
+            "firefox" | "gecko" => Ok(BrowserKind::Firefox),
+            other => Err(anyhow!(
+                "Invalid BROWSER: {other} (expected chrome or firefox)"
+            )),
+        }
+    }
+}
+
+impl BrowserKind {
+    /// Returns the WebDriver `browserName`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrowserKind::Chrome => "chrome",
+            BrowserKind::Firefox => "firefox",
+        }
+    }
+
+    /// Returns the default driver binary.
+    pub fn driver(&self) -> &'static str {
+        match self {
+            BrowserKind::Chrome => "chromedriver",
+            BrowserKind::Firefox => "geckodriver",
+        }
+    }
+
+    /// Returns the prefix of the driver's environment variables, e.g. `CHROMEDRIVER`.
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            BrowserKind::Chrome => "CHROMEDRIVER",
+            BrowserKind::Firefox => "GECKODRIVER",
+        }
+    }
+
+    /// Returns whether the driver exposes the browser console log (used by
+    /// diagnostics); geckodriver does not implement the log endpoint.
+    pub fn has_console_log(&self) -> bool {
+        matches!(self, BrowserKind::Chrome)
+    }
+
+    /// Returns how the episode description is typed into the Slate.js editor when
+    /// pasting it does not work.
+    pub fn description_input(&self) -> TextInput {
+        match self {
+            BrowserKind::Chrome => TextInput::PerCharacter,
+            BrowserKind::Firefox => TextInput::Whole,
+        }
+    }
+}
+
+/// Browser settings read from the environment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BrowserConfig {
+    /// Browser to drive
+    pub kind: BrowserKind,
+    /// Browser binary, when not installed in the default location
+    pub binary: Option<String>,
+    /// Run the browser without a visible window
+    pub headless: bool,
+    /// Window width and height in pixels
+    pub window_size: Option<(u32, u32)>,
+    pub user_agent: Option<String>,
+    /// Proxy server, e.g. `http://proxy.example.org:3128`
+    pub proxy: Option<String>,
+    /// Browser profile directory, kept between runs
+    pub profile_dir: Option<String>,
+    /// Extra browser command line arguments
+    pub args: Vec<String>,
+    /// Address Chrome accepts remote debugging (DevTools) connections on
+    pub debug_address: Option<SocketAddr>,
+    /// Existing WebDriver to connect to instead of spawning the driver
+    pub remote_url: Option<String>,
+    /// Path of the driver binary (chromedriver or geckodriver)
+    pub driver: String,
+    /// File the driver's stdout and stderr are written to
+    pub driver_log: PathBuf,
+    /// How long to wait for the WebDriver to report ready
+    pub startup_timeout: Duration,
+}
+
+impl BrowserConfig {
+    /// Reads the browser settings from the environment.
+    ///
+    /// - `BROWSER`: `chrome` (default) or `firefox`
+    /// - `BROWSER_BINARY`: browser binary, when not installed in the default location
+    /// - `BROWSER_HEADLESS`: `true` to run the browser headless
+    /// - `BROWSER_WINDOW_SIZE`: window size as `<width>x<height>`, e.g. `1920x1080`
+    /// - `BROWSER_USER_AGENT`: user agent override
+    /// - `BROWSER_PROXY`: proxy server for all browser traffic
+    /// - `BROWSER_PROFILE_DIR`: browser profile directory
+    /// - `BROWSER_ARGS`: extra space-separated browser arguments
+    /// - `BROWSER_DEBUG_ADDRESS`: Chrome remote debugging port, or address and port
+    ///   (e.g. `9222` for `127.0.0.1:9222`, or `0.0.0.0:9222` to reach it from other
+    ///   machines), used to solve bot challenges (see `challenge.rs`)
+    /// - `WEBDRIVER_URL`: remote WebDriver/Selenium Grid URL; the driver is not
+    ///   spawned when set
+    /// - `CHROMEDRIVER_PATH` / `GECKODRIVER_PATH`: driver binary (default
+    ///   `chromedriver` / `geckodriver`)
+    /// - `CHROMEDRIVER_LOG` / `GECKODRIVER_LOG`: file the driver's output is written
+    ///   to (default `chromedriver.log` / `geckodriver.log`)
+    /// - `WEBDRIVER_STARTUP_TIMEOUT`: seconds to wait for the WebDriver to become
+    ///   ready (default 30)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the browser, window size, debug address or startup timeout
+    /// is malformed.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let kind: BrowserKind = var("BROWSER")
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or_default();
+        let prefix = kind.env_prefix();
+
+        Ok(Self {
+            kind,
+            binary: var("BROWSER_BINARY"),
+            headless: var("BROWSER_HEADLESS")
+                .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes")),
+            window_size: var("BROWSER_WINDOW_SIZE")
+                .map(|v| parse_window_size(&v))
+                .transpose()?,
+            user_agent: var("BROWSER_USER_AGENT"),
+            proxy: var("BROWSER_PROXY"),
+            profile_dir: var("BROWSER_PROFILE_DIR"),
+            args: var("BROWSER_ARGS")
+                .map(|v| v.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            debug_address: var("BROWSER_DEBUG_ADDRESS")
+                .map(|v| parse_debug_address(&v))
+                .transpose()?,
+            remote_url: var("WEBDRIVER_URL").map(|v| v.trim_end_matches('/').to_string()),
+            driver: var(&format!("{prefix}_PATH")).unwrap_or_else(|| kind.driver().to_string()),
+            driver_log: var(&format!("{prefix}_LOG"))
+                .unwrap_or_else(|| format!("{}.log", kind.driver()))
+                .into(),
+            startup_timeout: var("WEBDRIVER_STARTUP_TIMEOUT")
+                .map(|v| {
+                    v.trim()
+                        .parse()
+                        .map(Duration::from_secs)
+                        .with_context(|| format!("Invalid WEBDRIVER_STARTUP_TIMEOUT: {v}"))
+                })
+                .transpose()?
+                .unwrap_or(DEFAULT_STARTUP_TIMEOUT),
+        })
+    }
+
+    /// Returns the Chrome command line arguments for these settings.
+    pub fn chrome_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.headless {
+            args.push("--headless=new".to_string());
+            // /dev/shm is too small for Chrome in most containers
+            args.push("--disable-dev-shm-usage".to_string());
+        }
+        if let Some((width, height)) = self.window_size {
+            args.push(format!("--window-size={width},{height}"));
+        }
+        if let Some(user_agent) = &self.user_agent {
+            args.push(format!("--user-agent={user_agent}"));
+        }
+        if let Some(proxy) = &self.proxy {
+            args.push(format!("--proxy-server={proxy}"));
+        }
+        if let Some(profile_dir) = &self.profile_dir {
+            args.push(format!("--user-data-dir={profile_dir}"));
+        }
+        if let Some(address) = self.debug_address {
+            args.push(format!("--remote-debugging-port={}", address.port()));
+            if !address.ip().is_loopback() {
+                args.push(format!("--remote-debugging-address={}", address.ip()));
+            }
+        }
+        args.extend(self.args.iter().cloned());
+        args
+    }
+
+    /// Returns the Firefox command line arguments for these settings.
+    ///
+    /// The user agent and proxy are not arguments in Firefox; they are set as
+    /// preferences and the `proxy` capability by `capabilities`.
+    pub fn firefox_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.headless {
+            args.push("-headless".to_string());
+        }
+        if let Some((width, height)) = self.window_size {
+            args.push(format!("--width={width}"));
+            args.push(format!("--height={height}"));
+        }
+        if let Some(profile_dir) = &self.profile_dir {
+            args.push("-profile".to_string());
+            args.push(profile_dir.clone());
+        }
+        args.extend(self.args.iter().cloned());
+        args
+    }
+
+    /// Returns the WebDriver capabilities for these settings.
+    ///
+    /// With Chrome the browser console log is enabled for diagnostics.
+    pub fn capabilities(&self) -> Capabilities {
+        let mut capabilities = Capabilities::new();
+        capabilities.insert("browserName".to_string(), json!(self.kind.name()));
+
+        match self.kind {
+            BrowserKind::Chrome => {
+                let mut options = json!({ "args": self.chrome_args() });
+                if let Some(binary) = &self.binary {
+                    options["binary"] = json!(binary);
+                }
+                capabilities.insert("goog:chromeOptions".to_string(), options);
+                capabilities.insert("goog:loggingPrefs".to_string(), json!({ "browser": "ALL" }));
+            }
+            BrowserKind::Firefox => {
+                let mut options = json!({ "args": self.firefox_args(), "prefs": {} });
+                if let Some(binary) = &self.binary {
+                    options["binary"] = json!(binary);
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    options["prefs"]["general.useragent.override"] = json!(user_agent);
+                }
+                capabilities.insert("moz:firefoxOptions".to_string(), options);
+                if let Some(proxy) = &self.proxy {
+                    capabilities.insert("proxy".to_string(), proxy_capability(proxy));
+                }
+            }
+        }
+        capabilities
+    }
+
+    /// Spawns the driver on a free port, or uses the remote WebDriver if configured,
+    /// and waits until it is ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no port is free, the driver is missing or exits during
+    /// startup (with the end of its log), or the WebDriver is not ready within
+    /// `startup_timeout`.
+    pub async fn start(&self) -> Result<WebDriver> {
+        if let Some(url) = &self.remote_url {
+            info!(url = %url, "Using remote WebDriver");
+            wait_until_ready(url, self.startup_timeout, None).await?;
+            return Ok(WebDriver {
+                url: url.clone(),
+                kind: self.kind,
+                _guard: None,
+            });
+        }
+
+        let port = free_port()?;
+        let mut args = vec![format!("--port={port}")];
+        if self.kind == BrowserKind::Firefox {
+            // geckodriver's WebDriver BiDi port defaults to 9222, which overlapping runs share
+            args.push(format!("--websocket-port={}", free_port()?));
+        }
+        let mut guard = DriverGuard::new(self.kind, &self.driver, &args, &self.driver_log)?;
+        let url = format!("http://localhost:{port}");
+        wait_until_ready(&url, self.startup_timeout, Some(&mut guard)).await?;
+        info!(driver = %self.driver, port, log = %self.driver_log.display(), "Started WebDriver");
+
+        Ok(WebDriver {
+            url,
+            kind: self.kind,
+            _guard: Some(guard),
+        })
+    }
+
+    /// Opens a browser session on `webdriver`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the WebDriver cannot be reached or refuses the session,
+    /// explaining the likely cause when chromedriver does not match the installed
+    /// Chrome, or the browser is missing.
+    pub async fn connect(&self, webdriver: &WebDriver) -> Result<Client> {
+        ClientBuilder::native()
+            .capabilities(self.capabilities())
+            .connect(&webdriver.url)
+            .await
+            .map_err(|e| {
+                let hint = session_error_hint(&e.to_string());
+                let err = anyhow::Error::new(e).context(format!(
+                    "Failed to connect to WebDriver at {}",
+                    webdriver.url
+                ));
+                match hint {
+                    Some(hint) => err.context(hint),
+                    None => err,
+                }
+            })
+    }
+}
+
+/// A WebDriver endpoint; a driver spawned for it is killed when this is dropped.
+#[derive(Debug)]
+pub struct WebDriver {
+    pub url: String,
+    /// Browser the endpoint drives
+    pub kind: BrowserKind,
+    _guard: Option<DriverGuard>,
+}
+
+impl WebDriver {
+    /// Returns the URL diagnostics read the browser console log from, if the
+    /// driver has one.
+    pub fn console_log_url(&self) -> Option<&str> {
+        self.kind.has_console_log().then_some(self.url.as_str())
+    }
+}
+
+/// Guard struct that ensures the driver process (chromedriver or geckodriver) is
+/// properly cleaned up even if the upload fails partway through
+#[derive(Debug)]
+struct DriverGuard {
+    process: Child,
+    program: String,
+    log: PathBuf,
+}
+
+impl DriverGuard {
+    /// Start the driver process with `args`, writing its output to `log`
+    fn new(kind: BrowserKind, program: &str, args: &[String], log: &Path) -> Result<Self> {
+        if let Some(parent) = log.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let stdout = File::create(log)
+            .with_context(|| format!("Failed to create {program} log {}", log.display()))?;
+        let stderr = stdout.try_clone()?;
+
+        let process = Command::new(program)
+            .args(args)
+            .stdout(Stdio::from(stdout))
+            .stderr(Stdio::from(stderr))
+            .spawn()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => anyhow!(
+                    "{program} not found: install {} (matching your {} version), or set {}_PATH",
+                    kind.driver(),
+                    kind.name(),
+                    kind.env_prefix()
+                ),
+                _ => anyhow::Error::new(e).context(format!("Failed to start {program}")),
+            })?;
+
+        Ok(DriverGuard {
+            process,
+            program: program.to_string(),
+            log: log.to_path_buf(),
+        })
+    }
+
+    /// Returns the exit status if the driver has already exited.
+    fn exited(&mut self) -> Option<ExitStatus> {
+        self.process.try_wait().ok().flatten()
+    }
+
+    /// Returns the last lines the driver wrote to its log.
+    fn log_tail(&self) -> String {
+        let text = fs::read_to_string(&self.log).unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        let tail = lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n");
+        if tail.trim().is_empty() {
+            "(no output)".to_string()
+        } else {
+            tail
+        }
+    }
+}
+
+impl Drop for DriverGuard {
+    fn drop(&mut self) {
+        // Attempt to kill the process
+        let _ = self.process.kill();
+        // CRITICAL: wait() to reap the process and avoid zombies
+        let _ = self.process.wait();
+    }
+}
+
+/// Polls the WebDriver `/status` endpoint until it reports ready.
+///
+/// # Arguments
+///
+/// * `url` - Base URL of the WebDriver
+/// * `timeout` - How long to keep polling
+/// * `guard` - The spawned driver, checked for an early exit between polls
+///
+/// # Errors
+///
+/// Returns an error if the driver exits (with the end of its log) or the
+/// WebDriver is not ready within `timeout` (with the last status or request error).
+async fn wait_until_ready(
+    url: &str,
+    timeout: Duration,
+    mut guard: Option<&mut DriverGuard>,
+) -> Result<()> {
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+    let status_url = format!("{url}/status");
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Some(guard) = guard.as_deref_mut() {
+            if let Some(status) = guard.exited() {
+                bail!(
+                    "{} exited during startup ({status}), see {}:\n{}",
+                    guard.program,
+                    guard.log.display(),
+                    guard.log_tail()
+                );
+            }
+        }
+
+        let last = match http.get(&status_url).send().await {
+            Ok(response) => match response.json::<Value>().await {
+                Ok(status) if is_ready(&status) => return Ok(()),
+                Ok(status) => status["value"]["message"]
+                    .as_str()
+                    .unwrap_or("not ready")
+                    .to_string(),
+                Err(e) => e.to_string(),
+            },
+            Err(e) => e.to_string(),
+        };
+        debug!(url = %status_url, status = %last, "WebDriver not ready yet");
+
+        if Instant::now() >= deadline {
+            bail!(
+                "WebDriver at {url} not ready after {}s: {last}",
+                timeout.as_secs_f32()
+            );
+        }
+        sleep(STATUS_POLL_INTERVAL).await;
+    }
+}
+
+/// Returns whether a WebDriver `/status` response reports ready.
+fn is_ready(status: &Value) -> bool {
+    status["value"]["ready"].as_bool().unwrap_or(false)
+}
+
+/// Explains the common causes of a refused session, from its error message.
+fn session_error_hint(message: &str) -> Option<&'static str> {
+    let message = message.to_lowercase();
+    if message.contains("only supports chrome version") {
+        Some(
+            "chromedriver does not match the installed Chrome version: compare \
+             `chrome --version` with `chromedriver --version` and install the matching \
+             ChromeDriver, or set CHROMEDRIVER_PATH",
+        )
+    } else if message.contains("cannot find chrome binary") || message.contains("no chrome binary")
+    {
+        Some("Chrome is not installed or not on PATH; set BROWSER_BINARY to its location")
+    } else if message.contains("expected browser binary location") {
+        Some("Firefox is not installed or not on PATH; set BROWSER_BINARY to its location")
+    } else if message.contains("unable to find a matching set of capabilities") {
+        Some(
+            "The WebDriver cannot start the requested browser: check that BROWSER matches \
+             the installed browser and driver (or the Selenium Grid nodes)",
+        )
+    } else {
+        None
+    }
+}
+
+/// Returns the WebDriver `proxy` capability for a proxy URL such as
+/// `http://proxy:3128` or `socks5://proxy:1080`.
+fn proxy_capability(proxy: &str) -> Value {
+    let (scheme, host) = proxy.split_once("://").unwrap_or(("http", proxy));
+    let host = host.trim_end_matches('/');
+    match scheme.to_lowercase().as_str() {
+        "socks" | "socks5" | "socks5h" => {
+            json!({ "proxyType": "manual", "socksProxy": host, "socksVersion": 5 })
+        }
+        "socks4" => json!({ "proxyType": "manual", "socksProxy": host, "socksVersion": 4 }),
+        _ => json!({ "proxyType": "manual", "httpProxy": host, "sslProxy": host }),
+    }
+}
+
+/// Returns a local TCP port that is free, so that overlapping runs do not collide.
+pub fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").context("No free local port")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Parses a remote debugging address such as `9222` (local only) or `0.0.0.0:9222`.
+fn parse_debug_address(text: &str) -> Result<SocketAddr> {
+    let text = text.trim();
+    if let Ok(port) = text.parse::<u16>() {
+        return Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port));
+    }
+    text.parse().map_err(|_| {
+        anyhow!("Invalid BROWSER_DEBUG_ADDRESS: {text} (expected e.g. 9222 or 0.0.0.0:9222)")
+    })
+}
+
+/// Parses a window size such as `1920x1080` or `1920,1080`.
+fn parse_window_size(text: &str) -> Result<(u32, u32)> {
+    let (width, height) = text
+        .trim()
+        .split_once(['x', 'X', ','])
+        .ok_or_else(|| anyhow!("Invalid BROWSER_WINDOW_SIZE: {text} (expected e.g. 1920x1080)"))?;
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|v| *v > 0)
+            .ok_or_else(|| anyhow!("Invalid BROWSER_WINDOW_SIZE: {text}"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::MockHttp;
+
+    /// Starts a mock WebDriver that answers every request with `status`.
+    async fn mock_webdriver(status: Value) -> MockHttp {
+        MockHttp::start(move |_, _| (200, status.clone())).await
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "audio_epistles_chromedriver_{}_{name}.log",
+            std::process::id()
+        ))
+    }
+
+    /// Returns the Chrome arguments of a capabilities object.
+    fn capability_args(capabilities: &Capabilities) -> Vec<&str> {
+        let options = capabilities
+            .get("goog:chromeOptions")
+            .or_else(|| capabilities.get("moz:firefoxOptions"))
+            .unwrap();
+        options["args"]
+            .as_array()
+            .map(|args| args.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_parse_window_size() {
+        assert_eq!(parse_window_size("1920x1080").unwrap(), (1920, 1080));
+        assert_eq!(parse_window_size(" 1280 , 800 ").unwrap(), (1280, 800));
+        assert!(parse_window_size("1920").is_err());
+        assert!(parse_window_size("0x800").is_err());
+    }
+
+    #[test]
+    fn test_debug_address() {
+        assert_eq!(
+            parse_debug_address("9222").unwrap(),
+            "127.0.0.1:9222".parse().unwrap()
+        );
+        let address = parse_debug_address(" 0.0.0.0:9333 ").unwrap();
+        assert!(parse_debug_address("localhost").is_err());
+
+        let config = BrowserConfig {
+            debug_address: Some(address),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.chrome_args(),
+            [
+                "--remote-debugging-port=9333",
+                "--remote-debugging-address=0.0.0.0"
+            ]
+        );
+        let config = BrowserConfig {
+            debug_address: Some("127.0.0.1:9222".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(config.chrome_args(), ["--remote-debugging-port=9222"]);
+    }
+
+    #[test]
+    fn test_default_capabilities() {
+        let capabilities = BrowserConfig::default().capabilities();
+        assert!(capability_args(&capabilities).is_empty());
+        assert_eq!(capabilities["browserName"], "chrome");
+        assert_eq!(capabilities["goog:loggingPrefs"]["browser"], "ALL");
+    }
+
+    #[test]
+    fn test_headless_capabilities() {
+        let config = BrowserConfig {
+            headless: true,
+            window_size: Some((1920, 1080)),
+            user_agent: Some("Mozilla/5.0 Test".to_string()),
+            proxy: Some("http://proxy:3128".to_string()),
+            profile_dir: Some("/data/profile".to_string()),
+            args: vec!["--no-sandbox".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            capability_args(&config.capabilities()),
+            [
+                "--headless=new",
+                "--disable-dev-shm-usage",
+                "--window-size=1920,1080",
+                "--user-agent=Mozilla/5.0 Test",
+                "--proxy-server=http://proxy:3128",
+                "--user-data-dir=/data/profile",
+                "--no-sandbox",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_browser_kind() {
+        assert_eq!(
+            "Firefox".parse::<BrowserKind>().unwrap(),
+            BrowserKind::Firefox
+        );
+        assert_eq!(
+            "chromium".parse::<BrowserKind>().unwrap(),
+            BrowserKind::Chrome
+        );
+        assert!("safari".parse::<BrowserKind>().is_err());
+        assert_eq!(BrowserKind::Firefox.description_input(), TextInput::Whole);
+        assert!(!BrowserKind::Firefox.has_console_log());
+    }
+
+    #[test]
+    fn test_firefox_capabilities() {
+        let config = BrowserConfig {
+            kind: BrowserKind::Firefox,
+            binary: Some("/usr/bin/firefox-esr".to_string()),
+            headless: true,
+            window_size: Some((1920, 1080)),
+            user_agent: Some("Mozilla/5.0 Test".to_string()),
+            proxy: Some("http://proxy:3128".to_string()),
+            profile_dir: Some("/data/profile".to_string()),
+            ..Default::default()
+        };
+        let capabilities = config.capabilities();
+        assert_eq!(capabilities["browserName"], "firefox");
+        assert_eq!(
+            capability_args(&capabilities),
+            [
+                "-headless",
+                "--width=1920",
+                "--height=1080",
+                "-profile",
+                "/data/profile"
+            ]
+        );
+        let options = &capabilities["moz:firefoxOptions"];
+        assert_eq!(options["binary"], "/usr/bin/firefox-esr");
+        assert_eq!(
+            options["prefs"]["general.useragent.override"],
+            "Mozilla/5.0 Test"
+        );
+        assert_eq!(capabilities["proxy"]["httpProxy"], "proxy:3128");
+        assert!(!capabilities.contains_key("goog:loggingPrefs"));
+    }
+
+    #[test]
+    fn test_proxy_capability() {
+        assert_eq!(
+            proxy_capability("socks5://proxy:1080"),
+            json!({ "proxyType": "manual", "socksProxy": "proxy:1080", "socksVersion": 5 })
+        );
+        assert_eq!(proxy_capability("proxy:3128")["sslProxy"], "proxy:3128");
+    }
+
+    #[test]
+    fn test_free_port() {
+        let port = free_port().unwrap();
+        assert_ne!(port, 0);
+        // The port is released and can be bound again
+        TcpListener::bind(("127.0.0.1", port)).unwrap();
+    }
+
+    #[test]
+    fn test_session_error_hint() {
+        let mismatch = "session not created: This version of ChromeDriver only supports \
+                        Chrome version 114\nCurrent browser version is 130.0.6723.58";
+        assert!(session_error_hint(mismatch)
+            .unwrap()
+            .contains("does not match the installed Chrome version"));
+        assert!(session_error_hint("unknown error: cannot find Chrome binary").is_some());
+        assert!(session_error_hint(
+            "Expected browser binary location, but unable to find binary in default location"
+        )
+        .unwrap()
+        .contains("Firefox"));
+        assert_eq!(session_error_hint("connection refused"), None);
+    }
+
+    #[tokio::test]
+    async fn test_start_remote() {
+        let mock = mock_webdriver(json!({"value": {"ready": true, "message": "ok"}})).await;
+        let config = BrowserConfig {
+            remote_url: Some(mock.url.clone()),
+            startup_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let webdriver = config.start().await.unwrap();
+        assert_eq!(webdriver.url, mock.url);
+        assert!(webdriver._guard.is_none());
+        assert_eq!(mock.requests()[0].path, "/status");
+    }
+
+    #[tokio::test]
+    async fn test_start_remote_not_ready() {
+        let mock = mock_webdriver(json!({"value": {"ready": false, "message": "No nodes"}})).await;
+        let config = BrowserConfig {
+            remote_url: Some(mock.url),
+            startup_timeout: Duration::from_millis(600),
+            ..Default::default()
+        };
+        let err = config.start().await.unwrap_err().to_string();
+        assert!(err.contains("not ready after"), "{err}");
+        assert!(err.ends_with("No nodes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_start_missing_chromedriver() {
+        let config = BrowserConfig {
+            driver: "audio-epistles-missing-chromedriver".to_string(),
+            driver_log: temp_log("missing"),
+            startup_timeout: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let err = config.start().await.unwrap_err().to_string();
+        assert!(err.contains("not found"), "{err}");
+        fs::remove_file(temp_log("missing")).ok();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_start_chromedriver_exits() {
+        // `echo` prints its arguments to the log and exits like a crashing chromedriver
+        let config = BrowserConfig {
+            driver: "echo".to_string(),
+            driver_log: temp_log("exits"),
+            startup_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let err = config.start().await.unwrap_err().to_string();
+        assert!(err.contains("exited during startup"), "{err}");
+        assert!(err.contains("--port="), "{err}");
+        fs::remove_file(temp_log("exits")).ok();
+    }
+}
@@ -0,0 +1,384 @@
+//! Bot challenge module.
+//!
+//! Spotify sometimes answers the automation with a challenge instead of the
+//! expected page: a captcha (on `challenge.spotify.com` or embedded in the page), a
+//! "verify you are human" check, or an access block. Without detection the run
+//! fails much later, on the next element that does not appear.
+//!
+//! The automation checks for a challenge after each login step and when a step
+//! fails. A challenge is recognised by its address, by the captcha elements named
+//! `challenge.captcha` in `pages.rs`, or by the page title and headings. When one
+//! is found, the operator is notified (see `notify.rs`) and:
+//!
+//! - without `SPOTIFY_CHALLENGE_WAIT`, the run stops with a `ChallengeError`
+//! - with `SPOTIFY_CHALLENGE_WAIT` seconds, the browser is kept open while the
+//!   operator solves the challenge, through the browser window or Chrome remote
+//!   debugging (`BROWSER_DEBUG_ADDRESS`), and the run continues once the challenge
+//!   is gone; if it is still shown when the time is up, the run stops with a
+//!   `ChallengeError`
+
+use anyhow::{Context, Result};
+use fantoccini::Client;
+use serde_json::Value;
+use std::env;
+use std::net::SocketAddr;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info};
+
+use crate::browser::BrowserConfig;
+use crate::notify::Notifier;
+use crate::pages::Pages;
+
+/// Interval between two checks of whether the operator solved a challenge
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Returns the page title and the text of its headings and alerts, one per line.
+const HEADINGS_SCRIPT: &str = r#"
+const parts = [document.title];
+for (const e of document.querySelectorAll('h1, h2, h3, [role=alert], [role=heading]')) {
+  parts.push(e.innerText);
+}
+return parts.join('\n');
+"#;
+
+/// Phrases of challenge pages, matched in lowercase against the title and headings
+const PHRASES: &[(&str, ChallengeKind)] = &[
+    ("captcha", ChallengeKind::Captcha),
+    ("not a robot", ChallengeKind::Captcha),
+    ("are you a robot", ChallengeKind::Captcha),
+    ("verify you are human", ChallengeKind::Verification),
+    ("verify you're human", ChallengeKind::Verification),
+    ("verify that you're human", ChallengeKind::Verification),
+    ("unusual activity", ChallengeKind::Verification),
+    ("unusual traffic", ChallengeKind::Verification),
+    ("access denied", ChallengeKind::Blocked),
+    ("you have been blocked", ChallengeKind::Blocked),
+    ("too many requests", ChallengeKind::Blocked),
+];
+
+/// Kind of challenge shown instead of the expected page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeKind {
+    /// A captcha to solve
+    Captcha,
+    /// A "verify you are human" or unusual-activity check
+    Verification,
+    /// Access refused, e.g. after too many requests
+    Blocked,
+}
+
+impl ChallengeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Captcha => "captcha",
+            Self::Verification => "human verification",
+            Self::Blocked => "access block",
+        }
+    }
+}
+
+impl std::fmt::Display for ChallengeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The run stopped at a bot challenge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeError {
+    pub kind: ChallengeKind,
+    /// Address of the page showing the challenge
+    pub url: String,
+    /// How long the operator was given to solve it, if the run waited
+    pub waited: Option<Duration>,
+}
+
+impl std::fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Bot challenge: Spotify showed a {} at {}",
+            self.kind, self.url
+        )?;
+        if let Some(waited) = self.waited {
+            write!(f, " (not solved within {}s)", waited.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ChallengeError {}
+
+/// Recognises a challenge page by its address, title and headings.
+///
+/// # Arguments
+///
+/// * `url` - Address of the page
+/// * `headings` - Title, headings and alerts of the page
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::challenge::{classify, ChallengeKind};
+///
+/// assert_eq!(
+///     classify("https://challenge.spotify.com/c/abc", ""),
+///     Some(ChallengeKind::Captcha)
+/// );
+/// assert_eq!(classify("https://podcasters.spotify.com/pod/dashboard", "Episodes"), None);
+/// ```
+pub fn classify(url: &str, headings: &str) -> Option<ChallengeKind> {
+    let address = url.split(['?', '#']).next().unwrap_or_default();
+    let address = address.split_once("://").map_or(address, |(_, rest)| rest);
+    let mut parts = address.split('/');
+    let host = parts.next().unwrap_or_default();
+    if host.starts_with("challenge.") || parts.any(|part| part == "challenge") {
+        return Some(ChallengeKind::Captcha);
+    }
+    let headings = headings.to_lowercase();
+    PHRASES
+        .iter()
+        .find(|(phrase, _)| headings.contains(phrase))
+        .map(|(_, kind)| *kind)
+}
+
+/// Checks whether the current page is a challenge.
+///
+/// # Errors
+///
+/// Returns an error if the page cannot be read, e.g. because the browser closed.
+pub async fn detect(client: &Client, pages: &Pages) -> Result<Option<ChallengeKind>> {
+    let url = client.current_url().await?.to_string();
+    if pages.try_find(client, "challenge.captcha").await.is_some() {
+        return Ok(Some(ChallengeKind::Captcha));
+    }
+    let headings = match client.execute(HEADINGS_SCRIPT, vec![]).await? {
+        Value::String(text) => text,
+        _ => String::new(),
+    };
+    Ok(classify(&url, &headings))
+}
+
+/// How the automation reacts to challenges: notify, then stop or wait for an
+/// operator.
+#[derive(Debug, Clone, Default)]
+pub struct Challenges {
+    /// How long an operator has to solve a challenge; zero stops the run at once
+    pub wait: Duration,
+    /// Chrome remote debugging address the operator can reach the browser through
+    pub debug_address: Option<SocketAddr>,
+    /// Whether the browser has no window the operator could use
+    pub headless: bool,
+    notifier: Notifier,
+}
+
+impl Challenges {
+    /// Reads `SPOTIFY_CHALLENGE_WAIT` (seconds, default 0) and `NOTIFY_WEBHOOK_URL`;
+    /// the remote debugging address and headless mode come from `browser`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `SPOTIFY_CHALLENGE_WAIT` is not a number of seconds.
+    pub fn from_env(browser: &BrowserConfig) -> Result<Self> {
+        let wait = match env::var("SPOTIFY_CHALLENGE_WAIT")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            Some(v) => Duration::from_secs(
+                v.trim()
+                    .parse()
+                    .with_context(|| format!("Invalid SPOTIFY_CHALLENGE_WAIT: {v}"))?,
+            ),
+            None => Duration::ZERO,
+        };
+
+        Ok(Self {
+            wait,
+            debug_address: browser.debug_address,
+            headless: browser.headless,
+            notifier: Notifier::from_env(),
+        })
+    }
+
+    /// Checks for a challenge and, if one is shown, notifies the operator and waits
+    /// for them to solve it (see the module documentation).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ChallengeError` if a challenge is shown and not solved in time,
+    /// or an error if the page cannot be read.
+    pub async fn check(&self, client: &Client, pages: &Pages) -> Result<()> {
+        let Some(kind) = detect(client, pages).await? else {
+            return Ok(());
+        };
+        let url = client.current_url().await?.to_string();
+
+        if self.wait.is_zero() {
+            let err = ChallengeError {
+                kind,
+                url,
+                waited: None,
+            };
+            self.notifier
+                .send("challenge", &format!("{err}; the run was stopped"))
+                .await;
+            return Err(err.into());
+        }
+
+        self.notifier
+            .send(
+                "challenge",
+                &format!(
+                    "Spotify shows a {kind} at {url}. Solve it within {}s {}, and the \
+                     run will continue",
+                    self.wait.as_secs(),
+                    self.how_to_reach()
+                ),
+            )
+            .await;
+
+        let deadline = Instant::now() + self.wait;
+        while Instant::now() < deadline {
+            sleep(POLL_INTERVAL.min(deadline - Instant::now())).await;
+            if detect(client, pages).await?.is_none() {
+                info!(kind = %kind, "Challenge solved, continuing");
+                self.notifier
+                    .send(
+                        "challenge_solved",
+                        "Spotify challenge solved, the run continues",
+                    )
+                    .await;
+                return Ok(());
+            }
+            debug!(kind = %kind, "Challenge still shown");
+        }
+
+        let err = ChallengeError {
+            kind,
+            url,
+            waited: Some(self.wait),
+        };
+        self.notifier
+            .send("challenge", &format!("{err}; the run was stopped"))
+            .await;
+        Err(err.into())
+    }
+
+    /// Marks a failed step's error as a `ChallengeError` if the page shows a
+    /// challenge, and notifies the operator.
+    ///
+    /// The error is returned unchanged if it already is a challenge, or if no
+    /// challenge is shown or the page cannot be read.
+    pub async fn explain(
+        &self,
+        client: &Client,
+        pages: &Pages,
+        err: anyhow::Error,
+    ) -> anyhow::Error {
+        if err.downcast_ref::<ChallengeError>().is_some() {
+            return err;
+        }
+        let (Ok(Some(kind)), Ok(url)) = (detect(client, pages).await, client.current_url().await)
+        else {
+            return err;
+        };
+
+        let challenge = ChallengeError {
+            kind,
+            url: url.to_string(),
+            waited: None,
+        };
+        self.notifier
+            .send("challenge", &format!("{challenge}; the run was stopped"))
+            .await;
+        err.context(challenge)
+    }
+
+    /// Describes how the operator can reach the browser.
+    fn how_to_reach(&self) -> String {
+        match (self.debug_address, self.headless) {
+            (Some(address), _) => format!(
+                "through Chrome remote debugging at {address} (in Chrome, open \
+                 chrome://inspect, add the address under \"Configure\" and inspect the page)"
+            ),
+            (None, false) => "in the browser window".to_string(),
+            (None, true) => {
+                "in the browser (headless: set BROWSER_DEBUG_ADDRESS to reach it)".to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_url() {
+        assert_eq!(
+            classify("https://challenge.spotify.com/c/abc/def", ""),
+            Some(ChallengeKind::Captcha)
+        );
+        assert_eq!(
+            classify("http://127.0.0.1:4000/challenge?next=/pod", "Spotify"),
+            Some(ChallengeKind::Captcha)
+        );
+        // Only the address itself counts, not a query parameter naming a challenge
+        assert_eq!(
+            classify(
+                "https://podcasters.spotify.com/pod/login?from=/challenge",
+                "Log in"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_classify_headings() {
+        let url = "https://podcasters.spotify.com/pod/dashboard/episodes";
+        assert_eq!(
+            classify(url, "Spotify\nVerify you are human"),
+            Some(ChallengeKind::Verification)
+        );
+        assert_eq!(classify(url, "Access Denied"), Some(ChallengeKind::Blocked));
+        assert_eq!(
+            classify(url, "Please complete the CAPTCHA"),
+            Some(ChallengeKind::Captcha)
+        );
+        assert_eq!(classify(url, "Episodes\nWalking by Faith"), None);
+    }
+
+    #[test]
+    fn test_challenge_error() {
+        let mut err = ChallengeError {
+            kind: ChallengeKind::Captcha,
+            url: "https://challenge.spotify.com/c/abc".to_string(),
+            waited: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Bot challenge: Spotify showed a captcha at https://challenge.spotify.com/c/abc"
+        );
+        err.waited = Some(Duration::from_secs(600));
+        assert!(err.to_string().ends_with(" (not solved within 600s)"));
+
+        // Found by callers through the context chain of the failed step
+        let wrapped =
+            anyhow::anyhow!("Page element not found: wizard.title_input").context(err.clone());
+        assert_eq!(wrapped.downcast_ref::<ChallengeError>(), Some(&err));
+    }
+
+    #[test]
+    fn test_how_to_reach() {
+        let mut challenges = Challenges {
+            headless: true,
+            ..Default::default()
+        };
+        assert!(challenges.how_to_reach().contains("BROWSER_DEBUG_ADDRESS"));
+        challenges.debug_address = Some("0.0.0.0:9222".parse().unwrap());
+        assert!(challenges.how_to_reach().contains("0.0.0.0:9222"));
+        challenges.debug_address = None;
+        challenges.headless = false;
+        assert_eq!(challenges.how_to_reach(), "in the browser window");
+    }
+}
@@ -0,0 +1,246 @@
+//! Episode chapters and show notes module.
+//!
+//! Timestamps in a video description are relative to the full video. This module
+//! finds the chapters (including indented or bulleted sub-chapters) that fall inside
+//! the trimmed sermon range, rebases them to episode-relative time, and renders them
+//! as show notes, an ffmpeg metadata file (for ID3 `CHAP` frames) and Podcasting 2.0
+//! chapters JSON.
+
+use regex::Regex;
+use serde_json::json;
+use std::fmt::Write as _;
+
+use crate::episode::{parse_timestamp, Chapter};
+
+/// Parses every timestamped line of a description into chapters, sorted by start.
+///
+/// Besides top-level lines ("15:00 Sermon"), lines that are indented or bulleted
+/// ("  - 20:15 Faith defined", "↳ 31:40 Faith in action") are included, so that
+/// sub-chapters listed under the sermon are kept. Each chapter ends where the next
+/// one starts; the last one has no end.
+///
+/// # Example
+///
+/// ```
+/// let chapters = audio_epistles::chapters::parse_chapters("15:00 Sermon\n  - 20:15 Point one");
+/// assert_eq!(chapters[1].start, 1215);
+/// assert_eq!(chapters[1].title, "Point one");
+/// ```
+pub fn parse_chapters(description: &str) -> Vec<Chapter> {
+    // Pattern is a compile-time constant and will never fail to compile
+    let re = Regex::new(
+        r"(?m)^[ \t]*(?:[-–•*>↳|]+[ \t]*)?(\d{1,2}:\d{2}(?::\d{2})?)(?:[ \t]*[-–:][ \t]*|[ \t]+)(.+?)[ \t]*$",
+    )
+    .unwrap();
+
+    let mut chapters: Vec<Chapter> = re
+        .captures_iter(description)
+        .map(|cap| Chapter {
+            start: parse_timestamp(&cap[1]),
+            end: None,
+            title: cap[2].to_string(),
+        })
+        .collect();
+    chapters.sort_by_key(|c| c.start);
+
+    let starts: Vec<u32> = chapters.iter().map(|c| c.start).collect();
+    for (chapter, next) in chapters.iter_mut().zip(starts.iter().skip(1)) {
+        chapter.end = Some(*next);
+    }
+
+    chapters
+}
+
+/// Keeps the chapters that start inside `[start, end)` and rebases them to start at 0.
+///
+/// Chapter ends are clamped to the trimmed range. If fewer than two chapters fall
+/// inside the range there are no sections to navigate, so none are returned.
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::chapters::{parse_chapters, rebase};
+/// let chapters = parse_chapters("15:00 Sermon\n  20:00 Point one\n45:00 Closing");
+/// let rebased = rebase(&chapters, 900, 2700);
+/// assert_eq!((rebased[1].start, rebased[1].end), (300, Some(1800)));
+/// ```
+pub fn rebase(chapters: &[Chapter], start: u32, end: u32) -> Vec<Chapter> {
+    let rebased: Vec<Chapter> = chapters
+        .iter()
+        .filter(|c| c.start >= start && c.start < end)
+        .map(|c| Chapter {
+            start: c.start - start,
+            end: Some(c.end.unwrap_or(end).min(end) - start),
+            title: c.title.clone(),
+        })
+        .collect();
+
+    if rebased.len() < 2 {
+        Vec::new()
+    } else {
+        rebased
+    }
+}
+
+/// Formats seconds as `M:SS` or `H:MM:SS`, the form YouTube and podcast apps link.
+fn format_timestamp(secs: u32) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Renders chapters as show notes, one "timestamp title" line per chapter.
+pub fn show_notes(chapters: &[Chapter]) -> String {
+    chapters
+        .iter()
+        .map(|c| format!("{} {}", format_timestamp(c.start), c.title))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders chapters as an ffmpeg metadata file, which ffmpeg writes as ID3 `CHAP` frames.
+pub fn to_ffmetadata(chapters: &[Chapter]) -> String {
+    let mut meta = String::from(";FFMETADATA1\n");
+    for c in chapters {
+        let end = c.end.unwrap_or(c.start);
+        // '=', ';', '#' and '\' must be escaped in ffmetadata values
+        let title: String = c
+            .title
+            .chars()
+            .flat_map(|ch| match ch {
+                '=' | ';' | '#' | '\\' => vec!['\\', ch],
+                '\n' => vec![' '],
+                _ => vec![ch],
+            })
+            .collect();
+        let _ = write!(
+            meta,
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={title}\n",
+            u64::from(c.start) * 1000,
+            u64::from(end) * 1000
+        );
+    }
+    meta
+}
+
+/// Renders chapters in the Podcasting 2.0 JSON chapters format.
+pub fn to_json(chapters: &[Chapter]) -> String {
+    let chapters: Vec<_> = chapters
+        .iter()
+        .map(|c| {
+            let mut chapter = json!({ "startTime": c.start, "title": c.title });
+            if let Some(end) = c.end {
+                chapter["endTime"] = json!(end);
+            }
+            chapter
+        })
+        .collect();
+    json!({ "version": "1.2.0", "chapters": chapters }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"
+0:00 Welcome
+5:30 Worship
+15:00 Sermon
+   - 20:15 Faith defined
+   ↳ 31:40 Faith in action
+45:00 Closing Prayer
+"#;
+
+    #[test]
+    fn test_parse_chapters_includes_sub_chapters() {
+        let chapters = parse_chapters(DESCRIPTION);
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Welcome",
+                "Worship",
+                "Sermon",
+                "Faith defined",
+                "Faith in action",
+                "Closing Prayer"
+            ]
+        );
+        assert_eq!(chapters[3].start, 1215);
+        assert_eq!(chapters[3].end, Some(1900));
+        assert_eq!(chapters[5].end, None);
+    }
+
+    #[test]
+    fn test_rebase_keeps_chapters_inside_range() {
+        let rebased = rebase(&parse_chapters(DESCRIPTION), 900, 2700);
+        assert_eq!(
+            rebased,
+            [
+                Chapter {
+                    start: 0,
+                    end: Some(315),
+                    title: "Sermon".to_string()
+                },
+                Chapter {
+                    start: 315,
+                    end: Some(1000),
+                    title: "Faith defined".to_string()
+                },
+                Chapter {
+                    start: 1000,
+                    end: Some(1800),
+                    title: "Faith in action".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rebase_clamps_last_chapter_to_range() {
+        let rebased = rebase(&parse_chapters("10:00 Sermon\n  20:00 Point"), 600, 1500);
+        assert_eq!(rebased[1].end, Some(900));
+    }
+
+    #[test]
+    fn test_rebase_without_sections_is_empty() {
+        assert!(rebase(&parse_chapters("15:00 Sermon\n45:00 Closing"), 900, 2700).is_empty());
+        assert!(rebase(&[], 0, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_show_notes() {
+        let rebased = rebase(&parse_chapters(DESCRIPTION), 900, 2700);
+        assert_eq!(
+            show_notes(&rebased),
+            "0:00 Sermon\n5:15 Faith defined\n16:40 Faith in action"
+        );
+        assert_eq!(format_timestamp(3723), "1:02:03");
+    }
+
+    #[test]
+    fn test_to_ffmetadata() {
+        let chapters = [Chapter {
+            start: 0,
+            end: Some(90),
+            title: "Faith; hope = love".to_string(),
+        }];
+        assert_eq!(
+            to_ffmetadata(&chapters),
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=90000\ntitle=Faith\\; hope \\= love\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let rebased = rebase(&parse_chapters(DESCRIPTION), 900, 2700);
+        let json: serde_json::Value = serde_json::from_str(&to_json(&rebased)).unwrap();
+        assert_eq!(json["version"], "1.2.0");
+        assert_eq!(json["chapters"][1]["startTime"], 315);
+        assert_eq!(json["chapters"][1]["endTime"], 1000);
+        assert_eq!(json["chapters"][1]["title"], "Faith defined");
+    }
+}
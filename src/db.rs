@@ -5,10 +5,14 @@
 //! schema versioning and automatic migration from older database schemas.
 
 use anyhow::Result;
+use sqlx::sqlite::SqliteRow;
 use sqlx::{Row, Sqlite, SqlitePool, Transaction};
 
+use crate::episode::{Chapter, Episode};
+use crate::publish::EpisodeStatus;
+
 /// Current schema version
-const SCHEMA_VERSION: i32 = 1;
+const SCHEMA_VERSION: i32 = 12;
 
 /// Represents an upload record in the database
 #[derive(Debug)]
@@ -16,6 +20,73 @@ pub struct UploadRecord {
     pub id: i64,
     pub video_id: String,
     pub uploaded_at: String,
+    /// Episode title (not recorded before schema v2)
+    pub title: Option<String>,
+    /// Who preached, if detected (not recorded before schema v3)
+    pub speaker: Option<String>,
+    /// Sermon series, if detected (not recorded before schema v3)
+    pub series: Option<String>,
+    /// Segment rule that produced the episode (not recorded before schema v6)
+    pub segment: Option<String>,
+    /// Final state on the host: `draft`, `published` or `scheduled` (not recorded before schema v7)
+    pub status: Option<String>,
+    /// Local time a scheduled episode goes live, e.g. "2026-10-18 18:00:00"
+    pub publish_at: Option<String>,
+    /// Stable episode identifier derived from the video ID (not recorded before schema v9)
+    pub guid: Option<String>,
+    /// Episode description (not recorded before schema v9)
+    pub description: Option<String>,
+    /// Episode length in seconds (not recorded before schema v9)
+    pub duration_secs: Option<u32>,
+    /// Path to the episode cover art (not recorded before schema v9)
+    pub artwork: Option<String>,
+    /// Season number (not recorded before schema v11)
+    pub season: Option<u32>,
+    /// Episode number (not recorded before schema v11)
+    pub episode_number: Option<u32>,
+    /// `full`, `trailer` or `bonus` (not recorded before schema v11)
+    pub episode_type: Option<String>,
+    /// Whether the episode is explicit (not recorded before schema v11)
+    pub explicit: Option<bool>,
+}
+
+/// Columns selected into an `UploadRecord`, prefixed with the `uploads` table alias `u`
+const UPLOAD_COLUMNS: &str = "u.id, u.video_id, u.uploaded_at, u.title, u.speaker, u.series,
+    u.segment, u.status, u.publish_at, u.guid, u.description, u.duration_secs, u.artwork,
+    u.season, u.episode_number, u.episode_type, u.explicit";
+
+/// Represents the outcome of publishing an upload to one target
+#[derive(Debug)]
+pub struct PublicationRecord {
+    /// Publisher name (e.g. "spotify")
+    pub target: String,
+    /// `draft`, `published` or `scheduled`, or `None` if publishing failed
+    pub status: Option<String>,
+    pub url: Option<String>,
+    /// Why publishing failed
+    pub error: Option<String>,
+    /// Folder of the screenshot, HTML and logs captured when browser automation failed
+    pub diagnostics: Option<String>,
+}
+
+/// Represents one audited change to an uploaded episode on a target
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeRecord {
+    pub upload_id: i64,
+    /// Publisher name (e.g. "spotify")
+    pub target: String,
+    /// `edit`, `replace_audio` or `delete`
+    pub action: String,
+    /// Changed field (`title`, `description` or `audio`), or `None` for a deletion
+    pub field: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// Why the change failed, or `None` if it was applied
+    pub error: Option<String>,
+    /// Folder of the screenshot, HTML and logs captured when browser automation failed
+    pub diagnostics: Option<String>,
+    /// Set by the database when the change is recorded
+    pub created_at: String,
 }
 
 /// Initializes the database with schema migrations.
@@ -25,9 +96,19 @@ pub struct UploadRecord {
 /// migrations as needed. If an old `uploaded` table exists from a previous version,
 /// it migrates the data to the new `uploads` table structure.
 ///
-/// The current schema (v1) includes:
-/// - `uploads` table with auto-incrementing ID, video_id, and timestamp
-/// - Index on video_id for faster lookups
+/// The current schema (v12) includes:
+/// - `uploads` table with auto-incrementing ID, video_id, timestamp, title, speaker, series,
+///   segment, publish status, scheduled publish time, GUID, description, duration, artwork,
+///   season and episode number, episode type and explicit flag
+/// - Indexes on video_id and speaker for faster lookups
+/// - `scripture_refs` table with the scripture references of each upload
+/// - Index on scripture book for passage searches
+/// - `transcripts` table with the text, SRT, WebVTT and JSON transcript of each upload
+/// - `chapters` table with the episode-relative chapters of each upload
+/// - `publications` table with the outcome of publishing each upload to each target and
+///   the diagnostics folder of failed browser automation
+/// - `episode_changes` table auditing every edit, audio replacement and deletion of
+///   an uploaded episode
 /// - `schema_version` table to track migrations
 ///
 /// # Arguments
@@ -42,7 +123,7 @@ pub struct UploadRecord {
 ///
 /// Returns an error if:
 /// - The schema_version table cannot be created
-/// - The migration to v1 fails
+/// - Any migration fails
 /// - Any database query fails during initialization
 ///
 /// # Example
@@ -67,26 +148,67 @@ pub async fn init(pool: &SqlitePool) -> Result<()> {
     // Get current schema version
     let current_version = get_schema_version(pool).await?;
 
-    if current_version == 0 {
+    if current_version < 1 {
         // Fresh install or old schema - run migration
         migrate_to_v1(pool).await?;
     }
 
+    if current_version < 2 {
+        migrate_to_v2(pool).await?;
+    }
+
+    if current_version < 3 {
+        migrate_to_v3(pool).await?;
+    }
+
+    if current_version < 4 {
+        migrate_to_v4(pool).await?;
+    }
+
+    if current_version < 5 {
+        migrate_to_v5(pool).await?;
+    }
+
+    if current_version < 6 {
+        migrate_to_v6(pool).await?;
+    }
+
+    if current_version < 7 {
+        migrate_to_v7(pool).await?;
+    }
+
+    if current_version < 8 {
+        migrate_to_v8(pool).await?;
+    }
+
+    if current_version < 9 {
+        migrate_to_v9(pool).await?;
+    }
+
+    if current_version < 10 {
+        migrate_to_v10(pool).await?;
+    }
+
+    if current_version < 11 {
+        migrate_to_v11(pool).await?;
+    }
+
+    if current_version < 12 {
+        migrate_to_v12(pool).await?;
+    }
+
+    debug_assert_eq!(get_schema_version(pool).await?, SCHEMA_VERSION);
+
     Ok(())
 }
 
 /// Get the current schema version
 async fn get_schema_version(pool: &SqlitePool) -> Result<i32> {
-    let row = sqlx::query("SELECT version FROM schema_version LIMIT 1")
-        .fetch_optional(pool)
+    let version: Option<i32> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
         .await?;
 
-    if let Some(row) = row {
-        let version: i32 = row.try_get("version")?;
-        Ok(version)
-    } else {
-        Ok(0) // No version = fresh install or old schema
-    }
+    Ok(version.unwrap_or(0)) // No version = fresh install or old schema
 }
 
 /// Migrate to schema version 1
@@ -138,7 +260,292 @@ async fn migrate_to_v1(pool: &SqlitePool) -> Result<()> {
 
     // Set schema version
     sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
-        .bind(SCHEMA_VERSION)
+        .bind(1)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 2 (episode titles and scripture references)
+async fn migrate_to_v2(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN title TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS scripture_refs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            upload_id INTEGER NOT NULL REFERENCES uploads(id),
+            reference TEXT NOT NULL,
+            book TEXT NOT NULL,
+            chapter INTEGER NOT NULL,
+            verse INTEGER,
+            end_chapter INTEGER,
+            end_verse INTEGER,
+            translation TEXT
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Listeners search by passage, so index on book
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_scripture_refs_book ON scripture_refs(book)")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(2)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 3 (speaker and series)
+async fn migrate_to_v3(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN speaker TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN series TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_uploads_speaker ON uploads(speaker)")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(3)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 4 (transcripts)
+async fn migrate_to_v4(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS transcripts (
+            upload_id INTEGER PRIMARY KEY REFERENCES uploads(id),
+            text TEXT NOT NULL,
+            srt TEXT NOT NULL,
+            vtt TEXT NOT NULL,
+            json TEXT NOT NULL
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(4)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 5 (episode chapters)
+async fn migrate_to_v5(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS chapters (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            upload_id INTEGER NOT NULL REFERENCES uploads(id),
+            start_secs INTEGER NOT NULL,
+            end_secs INTEGER,
+            title TEXT NOT NULL
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(5)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 6 (segments)
+async fn migrate_to_v6(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN segment TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(6)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 7 (publish status)
+async fn migrate_to_v7(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN status TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("ALTER TABLE uploads ADD COLUMN publish_at DATETIME")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(7)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 8 (publications per target)
+async fn migrate_to_v8(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS publications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            upload_id INTEGER NOT NULL REFERENCES uploads(id),
+            target TEXT NOT NULL,
+            status TEXT,
+            publish_at DATETIME,
+            remote_id TEXT,
+            url TEXT,
+            error TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_publications_upload_id ON publications(upload_id)")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(8)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 9 (feed details)
+async fn migrate_to_v9(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    for column in [
+        "guid TEXT",
+        "description TEXT",
+        "duration_secs INTEGER",
+        "artwork TEXT",
+    ] {
+        sqlx::query(&format!("ALTER TABLE uploads ADD COLUMN {column}"))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(9)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 10 (failure diagnostics per publication)
+async fn migrate_to_v10(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE publications ADD COLUMN diagnostics TEXT")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(10)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 11 (season, episode number, episode type and explicit flag)
+async fn migrate_to_v11(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    for column in [
+        "season INTEGER",
+        "episode_number INTEGER",
+        "episode_type TEXT",
+        "explicit BOOLEAN",
+    ] {
+        sqlx::query(&format!("ALTER TABLE uploads ADD COLUMN {column}"))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(11)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Migrate to schema version 12 (audit of changes to uploaded episodes)
+async fn migrate_to_v12(pool: &SqlitePool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS episode_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            upload_id INTEGER NOT NULL REFERENCES uploads(id),
+            target TEXT NOT NULL,
+            action TEXT NOT NULL,
+            field TEXT,
+            old_value TEXT,
+            new_value TEXT,
+            error TEXT,
+            diagnostics TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_episode_changes_upload_id ON episode_changes(upload_id)",
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT OR REPLACE INTO schema_version (version) VALUES (?)")
+        .bind(12)
         .execute(&mut *tx)
         .await?;
 
@@ -190,97 +597,709 @@ pub async fn get_last_id(pool: &SqlitePool) -> Result<String> {
     }
 }
 
-/// Saves a video ID to the database within a transaction.
+/// Saves a processed episode, its scripture references, transcript, chapters and publications within a transaction.
+///
+/// This function records the episode's video ID and upload time together with its
+/// GUID, title, description, duration, artwork, speaker, series and publish status,
+/// and stores each scripture reference in canonical form
+/// alongside its book, chapter and verse numbers so that uploads can be searched by
+/// passage. The transcript, if any, is stored in every supported format,
+/// the episode chapters are stored in order, and the outcome of each publication
+/// (successful or not) is stored per target.
+///
+/// # Arguments
+///
+/// * `tx` - A mutable reference to the database transaction
+/// * `episode` - The processed episode to save
+///
+/// # Returns
+///
+/// Returns the database ID of the new upload record.
+///
+/// # Errors
+///
+/// Returns an error if any INSERT query fails to execute.
+///
+/// # Example
+///
+/// ```no_run
+/// # use sqlx::SqlitePool;
+/// # use audio_epistles::episode::Episode;
+/// # tokio_test::block_on(async {
+/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// let episode = Episode {
+///     video_id: "dQw4w9WgXcQ".to_string(),
+///     guid: "dQw4w9WgXcQ".to_string(),
+///     segment: "sermon".to_string(),
+///     audio_path: "assets/audio.mp3".to_string(),
+///     artwork: None,
+///     season: None,
+///     episode_number: None,
+///     episode_type: audio_epistles::episode::EpisodeType::Full,
+///     explicit: false,
+///     duration: 1800,
+///     title: "Faith".to_string(),
+///     description: String::new(),
+///     scripture: audio_epistles::scripture::parse_references("Hebrews 11:1"),
+///     speaker: Some("Pastor Kofi Mensah".to_string()),
+///     series: None,
+///     transcript: None,
+///     chapters: Vec::new(),
+///     publications: Vec::new(),
+/// };
+/// let mut tx = pool.begin().await.unwrap();
+/// audio_epistles::db::save_episode(&mut tx, &episode).await.unwrap();
+/// tx.commit().await.unwrap();
+/// # })
+/// ```
+pub async fn save_episode(tx: &mut Transaction<'_, Sqlite>, episode: &Episode) -> Result<i64> {
+    let upload_id = sqlx::query(
+        "INSERT INTO uploads (video_id, title, speaker, series, segment, status, publish_at,
+                              guid, description, duration_secs, artwork,
+                              season, episode_number, episode_type, explicit)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&episode.video_id)
+    .bind(&episode.title)
+    .bind(&episode.speaker)
+    .bind(&episode.series)
+    .bind(&episode.segment)
+    .bind(episode.status().map(|status| status.as_str()))
+    .bind(
+        episode
+            .status()
+            .and_then(|status| format_publish_at(&status)),
+    )
+    .bind(&episode.guid)
+    .bind(&episode.description)
+    .bind(episode.duration)
+    .bind(&episode.artwork)
+    .bind(episode.season)
+    .bind(episode.episode_number)
+    .bind(episode.episode_type.as_str())
+    .bind(episode.explicit)
+    .execute(&mut **tx)
+    .await?
+    .last_insert_rowid();
+
+    for reference in &episode.scripture {
+        sqlx::query(
+            "INSERT INTO scripture_refs
+             (upload_id, reference, book, chapter, verse, end_chapter, end_verse, translation)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(upload_id)
+        .bind(reference.to_string())
+        .bind(reference.book)
+        .bind(reference.chapter)
+        .bind(reference.verse)
+        .bind(reference.end_chapter)
+        .bind(reference.end_verse)
+        .bind(&reference.translation)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    if let Some(transcript) = &episode.transcript {
+        sqlx::query(
+            "INSERT INTO transcripts (upload_id, text, srt, vtt, json) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(upload_id)
+        .bind(transcript.to_text())
+        .bind(transcript.to_srt())
+        .bind(transcript.to_vtt())
+        .bind(transcript.to_json())
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    for chapter in &episode.chapters {
+        sqlx::query(
+            "INSERT INTO chapters (upload_id, start_secs, end_secs, title) VALUES (?, ?, ?, ?)",
+        )
+        .bind(upload_id)
+        .bind(chapter.start)
+        .bind(chapter.end)
+        .bind(&chapter.title)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    for publication in &episode.publications {
+        let status = publication.published.as_ref().map(|p| p.status);
+        sqlx::query(
+            "INSERT INTO publications
+                (upload_id, target, status, publish_at, remote_id, url, error, diagnostics)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(upload_id)
+        .bind(&publication.target)
+        .bind(status.map(|status| status.as_str()))
+        .bind(status.and_then(|status| format_publish_at(&status)))
+        .bind(
+            publication
+                .published
+                .as_ref()
+                .and_then(|p| p.remote_id.as_ref()),
+        )
+        .bind(publication.published.as_ref().and_then(|p| p.url.as_ref()))
+        .bind(&publication.error)
+        .bind(&publication.diagnostics)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(upload_id)
+}
+
+/// Formats the scheduled publish time of a status as stored in the database.
+fn format_publish_at(status: &EpisodeStatus) -> Option<String> {
+    status
+        .publish_at()
+        .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Retrieves the outcome of publishing an upload to each target.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `upload_id` - The database ID of the upload record
+///
+/// # Returns
+///
+/// Returns one record per target, in the order they were published.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any column cannot be extracted.
+pub async fn get_publications(pool: &SqlitePool, upload_id: i64) -> Result<Vec<PublicationRecord>> {
+    let rows = sqlx::query(
+        "SELECT target, status, url, error, diagnostics
+         FROM publications WHERE upload_id = ? ORDER BY id",
+    )
+    .bind(upload_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut publications = Vec::new();
+    for row in rows {
+        publications.push(PublicationRecord {
+            target: row.try_get("target")?,
+            status: row.try_get("status")?,
+            url: row.try_get("url")?,
+            error: row.try_get("error")?,
+            diagnostics: row.try_get("diagnostics")?,
+        });
+    }
+
+    Ok(publications)
+}
+
+/// Retrieves the episode-relative chapters stored for an upload.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `upload_id` - The database ID of the upload record
+///
+/// # Returns
+///
+/// Returns the chapters ordered by start time.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any column cannot be extracted.
+pub async fn get_chapters(pool: &SqlitePool, upload_id: i64) -> Result<Vec<Chapter>> {
+    let rows = sqlx::query(
+        "SELECT start_secs, end_secs, title FROM chapters
+         WHERE upload_id = ? ORDER BY start_secs, id",
+    )
+    .bind(upload_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut chapters = Vec::new();
+    for row in rows {
+        chapters.push(Chapter {
+            start: row.try_get("start_secs")?,
+            end: row.try_get("end_secs")?,
+            title: row.try_get("title")?,
+        });
+    }
+
+    Ok(chapters)
+}
+
+/// Retrieves a stored transcript of an upload in the given format.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `upload_id` - The database ID of the upload record
+/// * `format` - One of `text`, `srt`, `vtt` or `json`
+///
+/// # Returns
+///
+/// Returns the transcript, or `None` if the upload has no transcript.
+///
+/// # Errors
+///
+/// Returns an error if the format is unknown or the database query fails.
+pub async fn get_transcript(
+    pool: &SqlitePool,
+    upload_id: i64,
+    format: &str,
+) -> Result<Option<String>> {
+    let column = match format {
+        "text" | "srt" | "vtt" | "json" => format,
+        _ => anyhow::bail!("Unknown transcript format: {format}"),
+    };
+
+    let transcript = sqlx::query_scalar(&format!(
+        "SELECT {column} FROM transcripts WHERE upload_id = ?"
+    ))
+    .bind(upload_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(transcript)
+}
+
+/// Retrieves the canonical scripture references stored for an upload.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `upload_id` - The database ID of the upload record
+///
+/// # Returns
+///
+/// Returns the references (e.g. "John 3:16-18 (NIV)") in the order they were found.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails to execute.
+pub async fn get_scripture_refs(pool: &SqlitePool, upload_id: i64) -> Result<Vec<String>> {
+    let refs =
+        sqlx::query_scalar("SELECT reference FROM scripture_refs WHERE upload_id = ? ORDER BY id")
+            .bind(upload_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(refs)
+}
+
+/// Finds uploads that reference a book of the Bible, or one of its chapters, most
+/// recent first.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `book` - The canonical book name (e.g. "1 Corinthians")
+/// * `chapter` - Only uploads with a reference that includes this chapter (e.g. 8 for
+///   "Romans 7:24-8:2")
+///
+/// # Returns
+///
+/// Returns the matching upload records, each listed once.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails to execute.
+///
+/// # Example
+///
+/// ```no_run
+/// # use sqlx::SqlitePool;
+/// # tokio_test::block_on(async {
+/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// for record in audio_epistles::db::find_uploads_by_book(&pool, "Romans", Some(8)).await.unwrap() {
+///     println!("{}: {:?}", record.video_id, record.title);
+/// }
+/// # })
+/// ```
+pub async fn find_uploads_by_book(
+    pool: &SqlitePool,
+    book: &str,
+    chapter: Option<u32>,
+) -> Result<Vec<UploadRecord>> {
+    let rows = sqlx::query(&format!(
+        "SELECT DISTINCT {UPLOAD_COLUMNS}
+             FROM uploads u
+             JOIN scripture_refs s ON s.upload_id = u.id
+             WHERE s.book = ?
+               AND (?2 IS NULL OR ?2 BETWEEN s.chapter AND COALESCE(s.end_chapter, s.chapter))
+             ORDER BY u.id DESC"
+    ))
+    .bind(book)
+    .bind(chapter)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(upload_record).collect()
+}
+
+/// Retrieves upload history with a specified limit.
+///
+/// This function fetches the most recent upload records from the database,
+/// ordered by ID in descending order (most recent first). Each record includes
+/// the database ID, video ID, and upload timestamp.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `limit` - The maximum number of records to retrieve
+///
+/// # Returns
+///
+/// Returns a vector of `UploadRecord` structs containing upload history.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The database query fails to execute
+/// - Any column cannot be extracted from the result rows
+///
+/// # Example
+///
+/// ```no_run
+/// # use sqlx::SqlitePool;
+/// # tokio_test::block_on(async {
+/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// let history = audio_epistles::db::get_upload_history(&pool, 10).await.unwrap();
+/// for record in history {
+///     println!("{}: {} at {}", record.id, record.video_id, record.uploaded_at);
+/// }
+/// # })
+/// ```
+pub async fn get_upload_history(pool: &SqlitePool, limit: u32) -> Result<Vec<UploadRecord>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {UPLOAD_COLUMNS} FROM uploads u ORDER BY u.id DESC LIMIT ?"
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(upload_record).collect()
+}
+
+/// Retrieves upload history for a single speaker with a specified limit.
+///
+/// This function works like `get_upload_history` but only returns uploads whose
+/// detected speaker matches `speaker` (case-insensitive).
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `speaker` - The canonical speaker name
+/// * `limit` - The maximum number of records to retrieve
+///
+/// # Returns
+///
+/// Returns a vector of `UploadRecord` structs, most recent first.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any column cannot be extracted.
+///
+/// # Example
+///
+/// ```no_run
+/// # use sqlx::SqlitePool;
+/// # tokio_test::block_on(async {
+/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+/// let history =
+///     audio_epistles::db::get_upload_history_by_speaker(&pool, "Pastor Kofi Mensah", 10)
+///         .await
+///         .unwrap();
+/// # })
+/// ```
+pub async fn get_upload_history_by_speaker(
+    pool: &SqlitePool,
+    speaker: &str,
+    limit: u32,
+) -> Result<Vec<UploadRecord>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {UPLOAD_COLUMNS} FROM uploads u
+             WHERE u.speaker = ? COLLATE NOCASE
+             ORDER BY u.id DESC LIMIT ?"
+    ))
+    .bind(speaker)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(upload_record).collect()
+}
+
+/// Retrieves the uploads successfully published to a target, with their publication.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `target` - The publisher name (e.g. "rss")
+/// * `limit` - The maximum number of uploads to retrieve
+///
+/// # Returns
+///
+/// Returns `(upload, publication)` pairs, most recent first.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any column cannot be extracted.
+pub async fn get_published_uploads(
+    pool: &SqlitePool,
+    target: &str,
+    limit: u32,
+) -> Result<Vec<(UploadRecord, PublicationRecord)>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {UPLOAD_COLUMNS}, p.target, p.status AS p_status, p.url, p.error, p.diagnostics
+         FROM uploads u
+         JOIN publications p ON p.upload_id = u.id
+         WHERE p.target = ? AND p.status IS NOT NULL
+         ORDER BY u.id DESC LIMIT ?"
+    ))
+    .bind(target)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    let mut uploads = Vec::new();
+    for row in &rows {
+        let publication = PublicationRecord {
+            target: row.try_get("target")?,
+            status: row.try_get("p_status")?,
+            url: row.try_get("url")?,
+            error: row.try_get("error")?,
+            diagnostics: row.try_get("diagnostics")?,
+        };
+        uploads.push((upload_record(row)?, publication));
+    }
+
+    Ok(uploads)
+}
+
+/// Builds an `UploadRecord` from a row selected with `UPLOAD_COLUMNS`.
+fn upload_record(row: &SqliteRow) -> Result<UploadRecord> {
+    Ok(UploadRecord {
+        id: row.try_get("id")?,
+        video_id: row.try_get("video_id")?,
+        uploaded_at: row.try_get("uploaded_at")?,
+        title: row.try_get("title")?,
+        speaker: row.try_get("speaker")?,
+        series: row.try_get("series")?,
+        segment: row.try_get("segment")?,
+        status: row.try_get("status")?,
+        publish_at: row.try_get("publish_at")?,
+        guid: row.try_get("guid")?,
+        description: row.try_get("description")?,
+        duration_secs: row.try_get("duration_secs")?,
+        artwork: row.try_get("artwork")?,
+        season: row.try_get("season")?,
+        episode_number: row.try_get("episode_number")?,
+        episode_type: row.try_get("episode_type")?,
+        explicit: row.try_get("explicit")?,
+    })
+}
+
+/// Condition on an upload `u` that is true if every target failed to publish it
+const FAILED_UPLOAD: &str = "(u.status IS NULL
+         AND EXISTS (SELECT 1 FROM publications p WHERE p.upload_id = u.id))";
+
+/// Counts the recorded uploads, optionally only those of a series or a year.
 ///
-/// This function inserts a new video ID into the uploads table along with
-/// an automatic timestamp. Unlike the old schema which used INSERT OR REPLACE
-/// to maintain a single record, this version keeps a full history of all uploads.
-/// The operation is performed within a transaction to ensure atomicity.
+/// Uploads that every target failed to publish are not counted.
 ///
 /// # Arguments
 ///
-/// * `tx` - A mutable reference to the database transaction
-/// * `id` - The video ID string to save
+/// * `pool` - A reference to the SQLite connection pool
+/// * `series` - Only count uploads of this series
+/// * `year` - Only count uploads recorded in this year, in local time like
+///   `chrono::Local` (upload times are stored in UTC)
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success.
+/// Returns the number of matching uploads.
 ///
 /// # Errors
 ///
-/// Returns an error if the INSERT query fails to execute.
+/// Returns an error if the database query fails to execute.
+pub async fn count_uploads(
+    pool: &SqlitePool,
+    series: Option<&str>,
+    year: Option<i32>,
+) -> Result<u32> {
+    let count: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM uploads u
+         WHERE NOT {FAILED_UPLOAD}
+           AND (?1 IS NULL OR series = ?1 COLLATE NOCASE)
+           AND (?2 IS NULL OR strftime('%Y', uploaded_at, 'localtime') = ?2)"
+    ))
+    .bind(series)
+    .bind(year.map(|year| year.to_string()))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count as u32)
+}
+
+/// Returns the number of a series, counting series in order of their first upload.
 ///
-/// # Example
+/// A series without uploads gets the number after the last recorded series. Uploads
+/// that every target failed to publish are ignored.
 ///
-/// ```no_run
-/// # use sqlx::SqlitePool;
-/// # tokio_test::block_on(async {
-/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-/// let mut tx = pool.begin().await.unwrap();
-/// audio_epistles::db::save_id(&mut tx, "dQw4w9WgXcQ").await.unwrap();
-/// tx.commit().await.unwrap();
-/// # })
-/// ```
-pub async fn save_id(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<()> {
-    sqlx::query("INSERT INTO uploads (video_id) VALUES (?)")
-        .bind(id)
-        .execute(&mut **tx)
-        .await?;
-    Ok(())
+/// # Errors
+///
+/// Returns an error if the database query fails to execute.
+pub async fn get_series_number(pool: &SqlitePool, series: &str) -> Result<u32> {
+    let names: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT series FROM uploads u
+         WHERE series IS NOT NULL AND NOT {FAILED_UPLOAD}
+         GROUP BY series COLLATE NOCASE
+         ORDER BY MIN(id)"
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let position = names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(series))
+        .unwrap_or(names.len());
+    Ok(position as u32 + 1)
 }
 
-/// Retrieves upload history with a specified limit.
-///
-/// This function fetches the most recent upload records from the database,
-/// ordered by ID in descending order (most recent first). Each record includes
-/// the database ID, video ID, and upload timestamp.
+/// Retrieves the uploads of a video, one per published segment.
 ///
 /// # Arguments
 ///
 /// * `pool` - A reference to the SQLite connection pool
-/// * `limit` - The maximum number of records to retrieve
+/// * `video_id` - The YouTube video ID the episodes were made from
 ///
 /// # Returns
 ///
-/// Returns a vector of `UploadRecord` structs containing upload history.
+/// Returns the upload records in the order they were saved.
 ///
 /// # Errors
 ///
-/// Returns an error if:
-/// - The database query fails to execute
-/// - Any column cannot be extracted from the result rows
+/// Returns an error if the database query fails or any column cannot be extracted.
+pub async fn get_uploads_by_video(pool: &SqlitePool, video_id: &str) -> Result<Vec<UploadRecord>> {
+    let rows = sqlx::query(&format!(
+        "SELECT {UPLOAD_COLUMNS} FROM uploads u WHERE u.video_id = ? ORDER BY u.id"
+    ))
+    .bind(video_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(upload_record).collect()
+}
+
+/// Updates the title and description recorded for an upload after it was edited.
 ///
-/// # Example
+/// Fields passed as `None` are left unchanged.
 ///
-/// ```no_run
-/// # use sqlx::SqlitePool;
-/// # tokio_test::block_on(async {
-/// let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-/// let history = audio_epistles::db::get_upload_history(&pool, 10).await.unwrap();
-/// for record in history {
-///     println!("{}: {} at {}", record.id, record.video_id, record.uploaded_at);
-/// }
-/// # })
-/// ```
-pub async fn get_upload_history(pool: &SqlitePool, limit: u32) -> Result<Vec<UploadRecord>> {
+/// # Errors
+///
+/// Returns an error if the UPDATE query fails to execute.
+pub async fn update_upload_details(
+    tx: &mut Transaction<'_, Sqlite>,
+    upload_id: i64,
+    title: Option<&str>,
+    description: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE uploads SET title = COALESCE(?, title), description = COALESCE(?, description)
+         WHERE id = ?",
+    )
+    .bind(title)
+    .bind(description)
+    .bind(upload_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks the publications of an upload to `target` as `deleted`.
+///
+/// # Errors
+///
+/// Returns an error if the UPDATE query fails to execute.
+pub async fn mark_publication_deleted(
+    tx: &mut Transaction<'_, Sqlite>,
+    upload_id: i64,
+    target: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE publications SET status = 'deleted'
+         WHERE upload_id = ? AND target = ? AND status IS NOT NULL",
+    )
+    .bind(upload_id)
+    .bind(target)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Records a change to an uploaded episode in the `episode_changes` audit table.
+///
+/// The `created_at` of `change` is ignored; the database sets the current time.
+///
+/// # Errors
+///
+/// Returns an error if the INSERT query fails to execute.
+pub async fn save_change(tx: &mut Transaction<'_, Sqlite>, change: &ChangeRecord) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO episode_changes
+            (upload_id, target, action, field, old_value, new_value, error, diagnostics)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(change.upload_id)
+    .bind(&change.target)
+    .bind(&change.action)
+    .bind(&change.field)
+    .bind(&change.old_value)
+    .bind(&change.new_value)
+    .bind(&change.error)
+    .bind(&change.diagnostics)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Retrieves the audited changes to an upload, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any column cannot be extracted.
+pub async fn get_changes(pool: &SqlitePool, upload_id: i64) -> Result<Vec<ChangeRecord>> {
     let rows = sqlx::query(
-        "SELECT id, video_id, uploaded_at FROM uploads
-         ORDER BY id DESC LIMIT ?",
+        "SELECT upload_id, target, action, field, old_value, new_value, error, diagnostics,
+                created_at
+         FROM episode_changes WHERE upload_id = ? ORDER BY id",
     )
-    .bind(limit)
+    .bind(upload_id)
     .fetch_all(pool)
     .await?;
 
-    let mut records = Vec::new();
+    let mut changes = Vec::new();
     for row in rows {
-        records.push(UploadRecord {
-            id: row.try_get("id")?,
-            video_id: row.try_get("video_id")?,
-            uploaded_at: row.try_get("uploaded_at")?,
+        changes.push(ChangeRecord {
+            upload_id: row.try_get("upload_id")?,
+            target: row.try_get("target")?,
+            action: row.try_get("action")?,
+            field: row.try_get("field")?,
+            old_value: row.try_get("old_value")?,
+            new_value: row.try_get("new_value")?,
+            error: row.try_get("error")?,
+            diagnostics: row.try_get("diagnostics")?,
+            created_at: row.try_get("created_at")?,
         });
     }
 
-    Ok(records)
+    Ok(changes)
 }
 
 /// Checks if a video ID has already been uploaded.
@@ -326,6 +1345,9 @@ pub async fn is_video_uploaded(pool: &SqlitePool, video_id: &str) -> Result<bool
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::episode::EpisodeType;
+    use crate::publish::{Publication, Published};
+    use crate::transcript::Transcript;
     use sqlx::sqlite::SqlitePoolOptions;
 
     async fn setup_test_db() -> SqlitePool {
@@ -337,6 +1359,15 @@ mod tests {
             .expect("Failed to create test database")
     }
 
+    /// Builds a minimal episode for the given video ID
+    fn episode(video_id: &str) -> Episode {
+        Episode {
+            video_id: video_id.to_string(),
+            guid: video_id.to_string(),
+            ..Default::default()
+        }
+    }
+
     #[tokio::test]
     async fn test_init_creates_tables() {
         let pool = setup_test_db().await;
@@ -394,7 +1425,7 @@ mod tests {
 
         // Save an ID
         let test_id = "test_video_123";
-        let save_result = save_id(&mut tx, test_id).await;
+        let save_result = save_episode(&mut tx, &episode(test_id)).await;
         assert!(save_result.is_ok());
 
         // Commit the transaction
@@ -415,7 +1446,9 @@ mod tests {
 
         for video_id in &video_ids {
             let mut tx = pool.begin().await.expect("Failed to begin transaction");
-            save_id(&mut tx, video_id).await.expect("Failed to save ID");
+            save_episode(&mut tx, &episode(video_id))
+                .await
+                .expect("Failed to save episode");
             tx.commit().await.expect("Failed to commit transaction");
         }
 
@@ -431,9 +1464,9 @@ mod tests {
 
         // Start a transaction and save an ID but don't commit
         let mut tx = pool.begin().await.expect("Failed to begin transaction");
-        save_id(&mut tx, "uncommitted_id")
+        save_episode(&mut tx, &episode("uncommitted_id"))
             .await
-            .expect("Failed to save ID");
+            .expect("Failed to save episode");
         // Explicitly drop the transaction without committing (simulates rollback)
         drop(tx);
 
@@ -457,7 +1490,9 @@ mod tests {
 
         // Save the ID
         let mut tx = pool.begin().await.expect("Failed to begin transaction");
-        save_id(&mut tx, test_id).await.expect("Failed to save ID");
+        save_episode(&mut tx, &episode(test_id))
+            .await
+            .expect("Failed to save episode");
         tx.commit().await.expect("Failed to commit transaction");
 
         // Now should be uploaded
@@ -476,7 +1511,9 @@ mod tests {
         let video_ids = vec!["video1", "video2", "video3", "video4", "video5"];
         for video_id in &video_ids {
             let mut tx = pool.begin().await.expect("Failed to begin transaction");
-            save_id(&mut tx, video_id).await.expect("Failed to save ID");
+            save_episode(&mut tx, &episode(video_id))
+                .await
+                .expect("Failed to save episode");
             tx.commit().await.expect("Failed to commit transaction");
         }
 
@@ -564,7 +1601,9 @@ mod tests {
         // Save the same ID twice
         for _ in 0..2 {
             let mut tx = pool.begin().await.expect("Failed to begin transaction");
-            save_id(&mut tx, test_id).await.expect("Failed to save ID");
+            save_episode(&mut tx, &episode(test_id))
+                .await
+                .expect("Failed to save episode");
             tx.commit().await.expect("Failed to commit transaction");
         }
 
@@ -585,9 +1624,9 @@ mod tests {
 
         // Save an ID
         let mut tx = pool.begin().await.expect("Failed to begin transaction");
-        save_id(&mut tx, "timestamped_video")
+        save_episode(&mut tx, &episode("timestamped_video"))
             .await
-            .expect("Failed to save ID");
+            .expect("Failed to save episode");
         tx.commit().await.expect("Failed to commit transaction");
 
         // Get the record
@@ -601,6 +1640,533 @@ mod tests {
         assert!(!history[0].uploaded_at.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_migration_from_v1_schema() {
+        let pool = setup_test_db().await;
+
+        // Create a v1 database with an existing upload
+        sqlx::query("CREATE TABLE schema_version (version INTEGER PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .expect("Failed to create schema_version table");
+        migrate_to_v1(&pool).await.expect("Failed to migrate to v1");
+        sqlx::query("INSERT INTO uploads (video_id) VALUES (?)")
+            .bind("v1_video")
+            .execute(&pool)
+            .await
+            .expect("Failed to insert v1 upload");
+
+        init(&pool).await.expect("Failed to init database");
+
+        let version = get_schema_version(&pool)
+            .await
+            .expect("Failed to get schema version");
+        assert_eq!(version, SCHEMA_VERSION);
+
+        let history = get_upload_history(&pool, 1)
+            .await
+            .expect("Failed to get upload history");
+        assert_eq!(history[0].video_id, "v1_video");
+        assert_eq!(history[0].title, None);
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_with_scripture_refs() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let episode = Episode {
+            video_id: "scripture_video".to_string(),
+            guid: "scripture_video".to_string(),
+            segment: "sermon".to_string(),
+            audio_path: "assets/audio.mp3".to_string(),
+            artwork: None,
+            season: None,
+            episode_number: None,
+            episode_type: EpisodeType::Full,
+            explicit: false,
+            duration: 1800,
+            title: "Walking by Faith".to_string(),
+            description: String::new(),
+            scripture: crate::scripture::parse_references("Heb 11:1-6; Rom 10:17 (KJV)"),
+            speaker: None,
+            series: None,
+            transcript: None,
+            chapters: Vec::new(),
+            publications: Vec::new(),
+        };
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        let upload_id = save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        assert_eq!(get_last_id(&pool).await.unwrap(), "scripture_video");
+
+        let refs = get_scripture_refs(&pool, upload_id)
+            .await
+            .expect("Failed to get scripture refs");
+        assert_eq!(refs, ["Hebrews 11:1-6", "Romans 10:17 (KJV)"]);
+
+        let found = find_uploads_by_book(&pool, "Romans", None)
+            .await
+            .expect("Failed to find uploads");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title.as_deref(), Some("Walking by Faith"));
+
+        let not_found = find_uploads_by_book(&pool, "Genesis", None)
+            .await
+            .expect("Failed to find uploads");
+        assert!(not_found.is_empty());
+
+        let in_chapter = find_uploads_by_book(&pool, "Hebrews", Some(11))
+            .await
+            .expect("Failed to find uploads");
+        assert_eq!(in_chapter.len(), 1);
+        let other_chapter = find_uploads_by_book(&pool, "Hebrews", Some(12))
+            .await
+            .expect("Failed to find uploads");
+        assert!(other_chapter.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_details() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let episode = Episode {
+            video_id: "details_video".to_string(),
+            guid: "details_video-2".to_string(),
+            description: "Join us online.".to_string(),
+            duration: 1800,
+            artwork: Some("assets/artwork.jpg".to_string()),
+            season: Some(2),
+            episode_number: Some(14),
+            episode_type: EpisodeType::Bonus,
+            explicit: true,
+            ..Default::default()
+        };
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let history = get_upload_history(&pool, 1)
+            .await
+            .expect("Failed to get history");
+        let record = &history[0];
+        assert_eq!(record.guid.as_deref(), Some("details_video-2"));
+        assert_eq!(record.description.as_deref(), Some("Join us online."));
+        assert_eq!(record.duration_secs, Some(1800));
+        assert_eq!(record.artwork.as_deref(), Some("assets/artwork.jpg"));
+        assert_eq!(record.season, Some(2));
+        assert_eq!(record.episode_number, Some(14));
+        assert_eq!(record.episode_type.as_deref(), Some("bonus"));
+        assert_eq!(record.explicit, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_get_upload_history_by_speaker() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let speakers = [Some("Pastor Kofi Mensah"), Some("Rev. Jane Doe"), None];
+        for (i, speaker) in speakers.iter().enumerate() {
+            let episode = Episode {
+                video_id: format!("video{i}"),
+                guid: format!("video{i}"),
+                segment: "sermon".to_string(),
+                audio_path: "assets/audio.mp3".to_string(),
+                artwork: None,
+                season: None,
+                episode_number: None,
+                episode_type: EpisodeType::Full,
+                explicit: false,
+                duration: 1800,
+                title: format!("Episode {i}"),
+                description: String::new(),
+                scripture: Vec::new(),
+                speaker: speaker.map(String::from),
+                series: Some("Walking by Faith".to_string()),
+                transcript: None,
+                chapters: Vec::new(),
+                publications: Vec::new(),
+            };
+            let mut tx = pool.begin().await.expect("Failed to begin transaction");
+            save_episode(&mut tx, &episode)
+                .await
+                .expect("Failed to save episode");
+            tx.commit().await.expect("Failed to commit transaction");
+        }
+
+        let history = get_upload_history_by_speaker(&pool, "pastor kofi mensah", 10)
+            .await
+            .expect("Failed to get upload history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].video_id, "video0");
+        assert_eq!(history[0].speaker.as_deref(), Some("Pastor Kofi Mensah"));
+        assert_eq!(history[0].series.as_deref(), Some("Walking by Faith"));
+        assert_eq!(history[0].segment.as_deref(), Some("sermon"));
+
+        let all = get_upload_history(&pool, 10)
+            .await
+            .expect("Failed to get upload history");
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].speaker, None);
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_with_transcript() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let transcript =
+            Transcript::parse("1\n00:00:00,000 --> 00:00:01,500\nGood morning\n").unwrap();
+        let episode = Episode {
+            video_id: "transcribed_video".to_string(),
+            guid: "transcribed_video".to_string(),
+            segment: "sermon".to_string(),
+            audio_path: "assets/audio.mp3".to_string(),
+            artwork: None,
+            season: None,
+            episode_number: None,
+            episode_type: EpisodeType::Full,
+            explicit: false,
+            duration: 1800,
+            title: "Episode".to_string(),
+            description: String::new(),
+            scripture: Vec::new(),
+            speaker: None,
+            series: None,
+            transcript: Some(transcript.clone()),
+            chapters: Vec::new(),
+            publications: Vec::new(),
+        };
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        let upload_id = save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let srt = get_transcript(&pool, upload_id, "srt")
+            .await
+            .expect("Failed to get transcript");
+        assert_eq!(srt, Some(transcript.to_srt()));
+
+        let text = get_transcript(&pool, upload_id, "text")
+            .await
+            .expect("Failed to get transcript");
+        assert_eq!(text.as_deref(), Some("Good morning\n"));
+
+        let missing = get_transcript(&pool, upload_id + 1, "vtt")
+            .await
+            .expect("Failed to get transcript");
+        assert_eq!(missing, None);
+
+        assert!(get_transcript(&pool, upload_id, "id; DROP TABLE uploads")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_with_chapters() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let chapters = vec![
+            Chapter {
+                start: 0,
+                end: Some(315),
+                title: "Sermon".to_string(),
+            },
+            Chapter {
+                start: 315,
+                end: Some(1800),
+                title: "Faith defined".to_string(),
+            },
+        ];
+        let episode = Episode {
+            video_id: "chaptered_video".to_string(),
+            guid: "chaptered_video".to_string(),
+            segment: "sermon".to_string(),
+            audio_path: "assets/audio.mp3".to_string(),
+            artwork: None,
+            season: None,
+            episode_number: None,
+            episode_type: EpisodeType::Full,
+            explicit: false,
+            duration: 1800,
+            title: "Episode".to_string(),
+            description: String::new(),
+            scripture: Vec::new(),
+            speaker: None,
+            series: None,
+            transcript: None,
+            chapters: chapters.clone(),
+            publications: Vec::new(),
+        };
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        let upload_id = save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let stored = get_chapters(&pool, upload_id)
+            .await
+            .expect("Failed to get chapters");
+        assert_eq!(stored, chapters);
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_with_publish_status() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let publish_at = chrono::NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap();
+        let statuses = [
+            EpisodeStatus::Published,
+            EpisodeStatus::Scheduled(publish_at),
+        ];
+        for status in statuses {
+            let episode = Episode {
+                video_id: "published_video".to_string(),
+                guid: "published_video".to_string(),
+                segment: "sermon".to_string(),
+                audio_path: "assets/audio.mp3".to_string(),
+                artwork: None,
+                season: None,
+                episode_number: None,
+                episode_type: EpisodeType::Full,
+                explicit: false,
+                duration: 1800,
+                title: "Episode".to_string(),
+                description: String::new(),
+                scripture: Vec::new(),
+                speaker: None,
+                series: None,
+                transcript: None,
+                chapters: Vec::new(),
+                publications: vec![Publication {
+                    target: "spotify".to_string(),
+                    published: Some(Published {
+                        status,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+            };
+            let mut tx = pool.begin().await.expect("Failed to begin transaction");
+            save_episode(&mut tx, &episode)
+                .await
+                .expect("Failed to save episode");
+            tx.commit().await.expect("Failed to commit transaction");
+        }
+
+        let history = get_upload_history(&pool, 2)
+            .await
+            .expect("Failed to get upload history");
+        assert_eq!(history[0].status.as_deref(), Some("scheduled"));
+        assert_eq!(
+            history[0].publish_at.as_deref(),
+            Some("2026-10-18 18:00:00")
+        );
+        assert_eq!(history[1].status.as_deref(), Some("published"));
+        assert_eq!(history[1].publish_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_save_episode_with_publications() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let episode = Episode {
+            video_id: "multi_target_video".to_string(),
+            title: "Episode".to_string(),
+            publications: vec![
+                Publication {
+                    target: "spotify".to_string(),
+                    error: Some("Login failed".to_string()),
+                    diagnostics: Some("diagnostics/20261018-180000-login".to_string()),
+                    ..Default::default()
+                },
+                Publication {
+                    target: "rss".to_string(),
+                    published: Some(Published {
+                        status: EpisodeStatus::Published,
+                        remote_id: Some("multi_target_video".to_string()),
+                        url: Some("https://example.org/feed.xml".to_string()),
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        let upload_id = save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let publications = get_publications(&pool, upload_id)
+            .await
+            .expect("Failed to get publications");
+        assert_eq!(publications.len(), 2);
+        assert_eq!(publications[0].target, "spotify");
+        assert_eq!(publications[0].status, None);
+        assert_eq!(publications[0].error.as_deref(), Some("Login failed"));
+        assert_eq!(
+            publications[0].diagnostics.as_deref(),
+            Some("diagnostics/20261018-180000-login")
+        );
+        assert_eq!(publications[1].diagnostics, None);
+        assert_eq!(publications[1].status.as_deref(), Some("published"));
+        assert_eq!(
+            publications[1].url.as_deref(),
+            Some("https://example.org/feed.xml")
+        );
+        let remote_id: Option<String> =
+            sqlx::query_scalar("SELECT remote_id FROM publications WHERE target = 'rss'")
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to get remote ID");
+        assert_eq!(remote_id.as_deref(), Some("multi_target_video"));
+
+        // The upload takes the status of the first target that succeeded
+        let history = get_upload_history(&pool, 1)
+            .await
+            .expect("Failed to get upload history");
+        assert_eq!(history[0].status.as_deref(), Some("published"));
+    }
+
+    #[tokio::test]
+    async fn test_episode_changes() {
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        let episode = Episode {
+            video_id: "edited_video".to_string(),
+            title: "Faith".to_string(),
+            description: "Join us online.".to_string(),
+            publications: vec![Publication {
+                target: "spotify".to_string(),
+                published: Some(Published {
+                    remote_id: Some("e2f4a9b".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        let upload_id = save_episode(&mut tx, &episode)
+            .await
+            .expect("Failed to save episode");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+        update_upload_details(&mut tx, upload_id, Some("Faith Works"), None)
+            .await
+            .expect("Failed to update upload");
+        save_change(
+            &mut tx,
+            &ChangeRecord {
+                upload_id,
+                target: "spotify".to_string(),
+                action: "edit".to_string(),
+                field: Some("title".to_string()),
+                old_value: Some("Faith".to_string()),
+                new_value: Some("Faith Works".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to save change");
+        mark_publication_deleted(&mut tx, upload_id, "spotify")
+            .await
+            .expect("Failed to mark publication deleted");
+        save_change(
+            &mut tx,
+            &ChangeRecord {
+                upload_id,
+                target: "spotify".to_string(),
+                action: "delete".to_string(),
+                error: Some("Delete button not found".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to save change");
+        tx.commit().await.expect("Failed to commit transaction");
+
+        let uploads = get_uploads_by_video(&pool, "edited_video")
+            .await
+            .expect("Failed to get uploads");
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].title.as_deref(), Some("Faith Works"));
+        assert_eq!(uploads[0].description.as_deref(), Some("Join us online."));
+
+        let publications = get_publications(&pool, upload_id)
+            .await
+            .expect("Failed to get publications");
+        assert_eq!(publications[0].status.as_deref(), Some("deleted"));
+
+        let changes = get_changes(&pool, upload_id)
+            .await
+            .expect("Failed to get changes");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].action, "edit");
+        assert_eq!(changes[0].field.as_deref(), Some("title"));
+        assert_eq!(changes[0].old_value.as_deref(), Some("Faith"));
+        assert_eq!(changes[0].new_value.as_deref(), Some("Faith Works"));
+        assert_eq!(changes[0].error, None);
+        assert!(!changes[0].created_at.is_empty());
+        assert_eq!(changes[1].action, "delete");
+        assert_eq!(changes[1].field, None);
+        assert_eq!(changes[1].error.as_deref(), Some("Delete button not found"));
+        assert!(get_uploads_by_video(&pool, "other_video")
+            .await
+            .expect("Failed to get uploads")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_uploads_by_local_year() {
+        use chrono::{Datelike, Local, TimeZone, Utc};
+
+        let pool = setup_test_db().await;
+        init(&pool).await.expect("Failed to init database");
+
+        // Upload times are stored in UTC, but years follow the local clock
+        let year = Local::now().year();
+        let new_year = Local
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        for (video_id, at) in [
+            ("before", new_year - chrono::Duration::minutes(1)),
+            ("after", new_year + chrono::Duration::minutes(1)),
+        ] {
+            sqlx::query("INSERT INTO uploads (video_id, uploaded_at) VALUES (?, ?)")
+                .bind(video_id)
+                .bind(at.format("%Y-%m-%d %H:%M:%S").to_string())
+                .execute(&pool)
+                .await
+                .expect("Failed to insert upload");
+        }
+
+        assert_eq!(count_uploads(&pool, None, Some(year)).await.unwrap(), 1);
+        assert_eq!(count_uploads(&pool, None, Some(year - 1)).await.unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_index_exists() {
         let pool = setup_test_db().await;
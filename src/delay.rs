@@ -0,0 +1,288 @@
+//! Human-like pauses of the Spotify browser automation.
+//!
+//! Every pause of the automation is one of a few named kinds (`Pause`), each with
+//! the range of a typical human pause for that kind of step: a keystroke, a click,
+//! a page load, and so on. A delay profile (`SPOTIFY_DELAY_PROFILE`) scales all of
+//! them:
+//!
+//! - `cautious`: 1.5× the normal pauses, for accounts that were flagged before
+//! - `normal` (default): pauses as a person would take them
+//! - `fast`: a tenth of the normal pauses, for development and end-to-end tests
+//! - `none`: no pauses at all
+//!
+//! Pauses are drawn from a log-normal distribution centred on the middle of their
+//! range and clipped to it, so that most pauses are close to typical with
+//! occasional longer ones, rather than spread evenly as with a uniform draw. With
+//! `SPOTIFY_DELAY_SEED` the random sequence is seeded, so that a run (or a test)
+//! pauses the same way every time.
+
+use anyhow::{anyhow, Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::env;
+use std::f64::consts::PI;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration};
+use tracing::debug;
+
+/// How long the automation pauses between steps, relative to a person.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DelayProfile {
+    Cautious,
+    #[default]
+    Normal,
+    Fast,
+    None,
+}
+
+impl DelayProfile {
+    /// Returns the profile name as used in `SPOTIFY_DELAY_PROFILE`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cautious => "cautious",
+            Self::Normal => "normal",
+            Self::Fast => "fast",
+            Self::None => "none",
+        }
+    }
+
+    /// Returns the factor applied to the normal pauses.
+    pub fn scale(&self) -> f64 {
+        match self {
+            Self::Cautious => 1.5,
+            Self::Normal => 1.0,
+            Self::Fast => 0.1,
+            Self::None => 0.0,
+        }
+    }
+}
+
+impl FromStr for DelayProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "cautious" | "slow" => Ok(Self::Cautious),
+            "" | "normal" => Ok(Self::Normal),
+            "fast" => Ok(Self::Fast),
+            "none" | "off" => Ok(Self::None),
+            other => Err(anyhow!(
+                "Unknown delay profile: {other} (expected cautious, normal, fast or none)"
+            )),
+        }
+    }
+}
+
+/// A kind of pause between two automation steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pause {
+    /// Between two typed characters
+    Keystroke,
+    /// After focusing a field or selecting its text
+    Focus,
+    /// After deleting the text of a field
+    Erase,
+    /// After choosing an option or entering a short number
+    Choice,
+    /// After confirming a small dialog, such as the artwork crop
+    Confirm,
+    /// After filling in a field or opening a menu
+    Field,
+    /// After a click that reveals the next part of a page
+    Click,
+    /// While a dialog or file picker opens
+    Dialog,
+    /// While the page settles after saving
+    Settle,
+    /// Between the steps of the login flow
+    Step,
+    /// While a page loads
+    Page,
+    /// While a heavy page loads, or before starting a new part of the flow
+    SlowPage,
+    /// While the review step of the wizard loads
+    Review,
+}
+
+impl Pause {
+    /// Returns the range of the pause in the normal profile, in milliseconds.
+    pub fn range(&self) -> (u64, u64) {
+        match self {
+            Self::Keystroke => (50, 120),
+            Self::Focus => (200, 400),
+            Self::Erase => (400, 800),
+            Self::Choice => (500, 1000),
+            Self::Confirm => (800, 1500),
+            Self::Field => (1000, 2000),
+            Self::Click => (1200, 2500),
+            Self::Dialog => (2200, 3100),
+            Self::Settle => (3000, 5000),
+            Self::Step => (3500, 4500),
+            Self::Page => (4000, 7000),
+            Self::SlowPage => (6000, 9300),
+            Self::Review => (19000, 22000),
+        }
+    }
+}
+
+/// Draws the pauses of one automation run.
+#[derive(Debug)]
+pub struct Delays {
+    pub profile: DelayProfile,
+    rng: Mutex<StdRng>,
+}
+
+impl Default for Delays {
+    fn default() -> Self {
+        Self::new(DelayProfile::Normal, None)
+    }
+}
+
+impl Delays {
+    /// Creates the pauses of `profile`, drawn from a sequence seeded with `seed`,
+    /// or from the operating system's randomness without one.
+    pub fn new(profile: DelayProfile, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self {
+            profile,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// Reads `SPOTIFY_DELAY_PROFILE` (`cautious`, `normal`, `fast` or `none`) and
+    /// `SPOTIFY_DELAY_SEED` (a number, for a repeatable sequence of pauses).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile is unknown or the seed is not a number.
+    pub fn from_env() -> Result<Self> {
+        let profile = env::var("SPOTIFY_DELAY_PROFILE")
+            .unwrap_or_default()
+            .parse::<DelayProfile>()?;
+        let seed = match env::var("SPOTIFY_DELAY_SEED")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            Some(v) => Some(
+                v.trim()
+                    .parse::<u64>()
+                    .with_context(|| format!("Invalid SPOTIFY_DELAY_SEED: {v}"))?,
+            ),
+            None => None,
+        };
+        debug!(profile = profile.as_str(), seed = ?seed, "Delay profile");
+
+        Ok(Self::new(profile, seed))
+    }
+
+    /// Draws the length of a pause.
+    pub fn duration(&self, pause: Pause) -> Duration {
+        let scale = self.profile.scale();
+        if scale == 0.0 {
+            return Duration::ZERO;
+        }
+
+        let (min, max) = pause.range();
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        let ms = log_normal(&mut *rng, min as f64, max as f64);
+        Duration::from_secs_f64(ms * scale / 1000.0)
+    }
+
+    /// Waits for a pause of the given kind.
+    pub async fn pause(&self, pause: Pause) {
+        // The random generator is not held across the await
+        let duration = self.duration(pause);
+        if !duration.is_zero() {
+            sleep(duration).await;
+        }
+    }
+}
+
+/// Draws a value between `min` and `max` from a log-normal distribution whose
+/// median is their geometric mean and whose range covers ±2 standard deviations.
+fn log_normal(rng: &mut impl Rng, min: f64, max: f64) -> f64 {
+    let median = (min * max).sqrt();
+    let sigma = (max / min).ln() / 4.0;
+
+    // Box-Muller transform of two uniform draws into a standard normal one
+    let u1: f64 = rng.random_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.random();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+    (median * (sigma * z).exp()).clamp(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        assert_eq!("".parse::<DelayProfile>().unwrap(), DelayProfile::Normal);
+        assert_eq!(
+            " Cautious ".parse::<DelayProfile>().unwrap(),
+            DelayProfile::Cautious
+        );
+        assert_eq!("fast".parse::<DelayProfile>().unwrap(), DelayProfile::Fast);
+        assert_eq!("none".parse::<DelayProfile>().unwrap(), DelayProfile::None);
+        assert!("instant".parse::<DelayProfile>().is_err());
+    }
+
+    #[test]
+    fn test_pauses_stay_in_range() {
+        let delays = Delays::new(DelayProfile::Normal, Some(7));
+        for pause in [Pause::Keystroke, Pause::Page, Pause::Review] {
+            let (min, max) = pause.range();
+            for _ in 0..1000 {
+                let ms = delays.duration(pause).as_secs_f64() * 1000.0;
+                assert!(ms >= min as f64 - 0.001 && ms <= max as f64 + 0.001, "{ms}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_pauses_repeat() {
+        let draw = |seed| {
+            let delays = Delays::new(DelayProfile::Normal, Some(seed));
+            (0..20)
+                .map(|_| delays.duration(Pause::Click))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn test_profiles_scale_pauses() {
+        let normal = Delays::new(DelayProfile::Normal, Some(1)).duration(Pause::Page);
+        let cautious = Delays::new(DelayProfile::Cautious, Some(1)).duration(Pause::Page);
+        let fast = Delays::new(DelayProfile::Fast, Some(1)).duration(Pause::Page);
+        assert!((cautious.as_secs_f64() - normal.as_secs_f64() * 1.5).abs() < 1e-6);
+        assert!((fast.as_secs_f64() - normal.as_secs_f64() * 0.1).abs() < 1e-6);
+        assert_eq!(
+            Delays::new(DelayProfile::None, Some(1)).duration(Pause::Review),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_pauses_cluster_around_median() {
+        // Most pauses of a log-normal draw are close to the median, unlike a uniform one
+        let delays = Delays::new(DelayProfile::Normal, Some(3));
+        let (min, max) = Pause::Page.range();
+        let median = ((min * max) as f64).sqrt();
+        let mut draws: Vec<f64> = (0..2000)
+            .map(|_| delays.duration(Pause::Page).as_secs_f64() * 1000.0)
+            .collect();
+        draws.sort_by(f64::total_cmp);
+
+        let sample_median = draws[draws.len() / 2];
+        assert!((sample_median - median).abs() < 100.0, "{sample_median}");
+        let middle_half = draws[draws.len() * 3 / 4] - draws[draws.len() / 4];
+        assert!(middle_half < (max - min) as f64 / 2.0, "{middle_half}");
+    }
+}
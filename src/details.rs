@@ -0,0 +1,418 @@
+//! Episode details module.
+//!
+//! Decides the details published alongside each episode's audio:
+//!
+//! - Cover art: the image of the episode's series (`SERIES_ARTWORK`), else the
+//!   YouTube thumbnail cropped to a square (`EPISODE_ARTWORK_FROM_THUMBNAIL`), else
+//!   `EPISODE_ARTWORK`
+//! - Season and episode numbers, counted from the episode history in the database
+//!   (`EPISODE_NUMBERING`)
+//! - Episode type (`full`, `trailer` or `bonus`) and the explicit-content flag, with
+//!   per-segment overrides (`SEGMENT_<NAME>_EPISODE_TYPE`, `SEGMENT_<NAME>_EXPLICIT`)
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Local};
+use sqlx::SqlitePool;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use tokio::task;
+use tracing::{info, warn};
+
+use crate::db;
+use crate::episode::EpisodeType;
+
+/// Side of the square cover art made from a thumbnail, in pixels
+const ARTWORK_SIZE: u32 = 1400;
+
+/// How season and episode numbers are derived from the episode history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Numbering {
+    /// Episodes are not numbered
+    #[default]
+    None,
+    /// Episodes are numbered across the whole podcast, without seasons
+    Overall,
+    /// Each series is a season, numbered in order of its first episode, and
+    /// episodes are numbered within their series
+    Series,
+    /// Each year is a season and episodes are numbered within the year
+    Year,
+}
+
+impl FromStr for Numbering {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "none" => Ok(Numbering::None),
+            "overall" => Ok(Numbering::Overall),
+            "series" => Ok(Numbering::Series),
+            "year" => Ok(Numbering::Year),
+            other => Err(anyhow!(
+                "Invalid EPISODE_NUMBERING: {other} (expected none, overall, series or year)"
+            )),
+        }
+    }
+}
+
+/// Season and episode number of the next episode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Numbers {
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+}
+
+impl Numbers {
+    /// Returns the numbers of the episode `offset` places after this one, for later
+    /// segments of the same video.
+    pub fn offset(&self, offset: u32) -> Self {
+        Self {
+            season: self.season,
+            episode: self.episode.map(|episode| episode + offset),
+        }
+    }
+}
+
+/// Episode details configuration read from the environment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetailsConfig {
+    /// Cover art used when no other image applies
+    pub artwork: Option<String>,
+    /// `(series, image path)` pairs
+    pub series_artwork: Vec<(String, String)>,
+    /// Whether the YouTube thumbnail becomes the cover art
+    pub thumbnail_artwork: bool,
+    pub numbering: Numbering,
+    /// Episode type of segments without an override
+    pub episode_type: EpisodeType,
+    /// Explicit flag of segments without an override
+    pub explicit: bool,
+}
+
+impl DetailsConfig {
+    /// Reads the episode details configuration.
+    ///
+    /// - `EPISODE_ARTWORK`: path to the default cover art
+    /// - `SERIES_ARTWORK`: `Series name=path;Series name=path`
+    /// - `EPISODE_ARTWORK_FROM_THUMBNAIL`: `true` to use the YouTube thumbnail
+    /// - `EPISODE_NUMBERING`: `none` (default), `overall`, `series` or `year`
+    /// - `EPISODE_TYPE`: `full` (default), `trailer` or `bonus`
+    /// - `EPISODE_EXPLICIT`: `true` if episodes contain explicit content
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the numbering or episode type is invalid.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        Ok(Self {
+            artwork: var("EPISODE_ARTWORK"),
+            series_artwork: parse_series_artwork(&var("SERIES_ARTWORK").unwrap_or_default()),
+            thumbnail_artwork: var("EPISODE_ARTWORK_FROM_THUMBNAIL").is_some_and(|v| is_true(&v)),
+            numbering: var("EPISODE_NUMBERING")
+                .map(|v| v.parse())
+                .transpose()?
+                .unwrap_or_default(),
+            episode_type: var("EPISODE_TYPE")
+                .map(|v| v.parse().context("Invalid EPISODE_TYPE"))
+                .transpose()?
+                .unwrap_or_default(),
+            explicit: var("EPISODE_EXPLICIT").is_some_and(|v| is_true(&v)),
+        })
+    }
+
+    /// Returns the episode type of a segment: `SEGMENT_<NAME>_EPISODE_TYPE`, or the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the segment's episode type is invalid.
+    pub fn episode_type(&self, segment: &str) -> Result<EpisodeType> {
+        let name = format!("SEGMENT_{}_EPISODE_TYPE", segment.to_uppercase());
+        match env::var(&name).ok().filter(|v| !v.trim().is_empty()) {
+            Some(value) => value.parse().with_context(|| format!("Invalid {name}")),
+            None => Ok(self.episode_type),
+        }
+    }
+
+    /// Returns whether a segment is explicit: `SEGMENT_<NAME>_EXPLICIT`, or the default.
+    pub fn explicit(&self, segment: &str) -> bool {
+        env::var(format!("SEGMENT_{}_EXPLICIT", segment.to_uppercase()))
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map_or(self.explicit, |v| is_true(&v))
+    }
+
+    /// Returns the image of `series` from `SERIES_ARTWORK`, matched case-insensitively.
+    pub fn series_image(&self, series: &str) -> Option<&str> {
+        self.series_artwork
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(series.trim()))
+            .map(|(_, path)| path.as_str())
+    }
+
+    /// Chooses the cover art of a video's episodes.
+    ///
+    /// The series image is preferred, then the thumbnail (downloaded and cropped to
+    /// a square in `dir`), then the default artwork. A thumbnail that cannot be
+    /// fetched or converted is logged and skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `series` - The detected sermon series, if any
+    /// * `thumbnail` - URL of the video thumbnail, if any
+    /// * `dir` - Directory the cropped thumbnail is written to
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the cover art, or `None` if there is none.
+    pub async fn artwork(
+        &self,
+        series: Option<&str>,
+        thumbnail: Option<&str>,
+        dir: &Path,
+    ) -> Option<String> {
+        if let Some(image) = series.and_then(|series| self.series_image(series)) {
+            return Some(image.to_string());
+        }
+        if self.thumbnail_artwork {
+            if let Some(url) = thumbnail {
+                match thumbnail_artwork(url, dir).await {
+                    Ok(path) => {
+                        info!(path = %path.display(), "Cover art made from thumbnail");
+                        return Some(path.to_string_lossy().into_owned());
+                    }
+                    Err(e) => warn!(error = %e, "Failed to make cover art from thumbnail"),
+                }
+            }
+        }
+        self.artwork.clone()
+    }
+
+    /// Returns the season and episode number of the next episode of `series`.
+    ///
+    /// Numbers count the episodes already recorded in the database, so that a
+    /// podcast that starts numbering later continues from its history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn numbers(&self, pool: &SqlitePool, series: Option<&str>) -> Result<Numbers> {
+        let numbers = match (self.numbering, series) {
+            (Numbering::None, _) => Numbers::default(),
+            (Numbering::Series, Some(series)) => Numbers {
+                season: Some(db::get_series_number(pool, series).await?),
+                episode: Some(db::count_uploads(pool, Some(series), None).await? + 1),
+            },
+            (Numbering::Year, _) => {
+                // Local time, as `count_uploads` reads upload times
+                let year = Local::now().year();
+                Numbers {
+                    season: Some(year as u32),
+                    episode: Some(db::count_uploads(pool, None, Some(year)).await? + 1),
+                }
+            }
+            // Episodes without a series are numbered overall
+            (Numbering::Overall | Numbering::Series, _) => Numbers {
+                season: None,
+                episode: Some(db::count_uploads(pool, None, None).await? + 1),
+            },
+        };
+        Ok(numbers)
+    }
+}
+
+/// Returns true for `true`, `yes` and `1`.
+fn is_true(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+/// Parses `Series=path;Series=path` into `(series, path)` pairs.
+fn parse_series_artwork(value: &str) -> Vec<(String, String)> {
+    value
+        .split(';')
+        .filter_map(|entry| entry.rsplit_once('='))
+        .map(|(series, path)| (series.trim().to_string(), path.trim().to_string()))
+        .filter(|(series, path)| !series.is_empty() && !path.is_empty())
+        .collect()
+}
+
+/// Downloads the thumbnail and crops it to a square JPEG with ffmpeg.
+async fn thumbnail_artwork(url: &str, dir: &Path) -> Result<PathBuf> {
+    let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+    fs::create_dir_all(dir)?;
+    let download = dir.join("thumbnail");
+    fs::write(&download, &bytes)?;
+
+    let output = dir.join("artwork.jpg");
+    let (input, square) = (download.clone(), output.clone());
+    // Spawn blocking because ffmpeg runs synchronously
+    let status = task::spawn_blocking(move || {
+        Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-i"])
+            .arg(&input)
+            .args(["-vf", &square_filter(ARTWORK_SIZE), "-frames:v", "1"])
+            .arg(&square)
+            .status()
+    })
+    .await;
+    let _ = fs::remove_file(&download);
+
+    let status = status??;
+    if status.success() {
+        Ok(output)
+    } else {
+        Err(anyhow!("ffmpeg thumbnail conversion failed: {status}"))
+    }
+}
+
+/// Returns the ffmpeg filter that crops the centre square of an image and scales it.
+fn square_filter(size: u32) -> String {
+    format!("crop='min(iw,ih)':'min(iw,ih)',scale={size}:{size}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::Episode;
+    use crate::publish::Publication;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        db::init(&pool).await.unwrap();
+        pool
+    }
+
+    async fn save(pool: &SqlitePool, video_id: &str, series: Option<&str>) {
+        let episode = Episode {
+            video_id: video_id.to_string(),
+            series: series.map(String::from),
+            ..Default::default()
+        };
+        let mut tx = pool.begin().await.unwrap();
+        db::save_episode(&mut tx, &episode).await.unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    #[test]
+    fn test_parse_numbering() {
+        assert_eq!("Series".parse::<Numbering>().unwrap(), Numbering::Series);
+        assert_eq!("".parse::<Numbering>().unwrap(), Numbering::None);
+        assert!("weekly".parse::<Numbering>().is_err());
+    }
+
+    #[test]
+    fn test_parse_series_artwork() {
+        let config = DetailsConfig {
+            series_artwork: parse_series_artwork(
+                "Faith Foundations=assets/faith.jpg; Kingdom Living = assets/kingdom.png;broken",
+            ),
+            ..Default::default()
+        };
+        assert_eq!(config.series_artwork.len(), 2);
+        assert_eq!(
+            config.series_image("kingdom living"),
+            Some("assets/kingdom.png")
+        );
+        assert_eq!(config.series_image("Other"), None);
+    }
+
+    #[tokio::test]
+    async fn test_artwork_precedence() {
+        let config = DetailsConfig {
+            artwork: Some("assets/podcast.jpg".to_string()),
+            series_artwork: vec![("Faith".to_string(), "assets/faith.jpg".to_string())],
+            ..Default::default()
+        };
+        let dir = env::temp_dir();
+        assert_eq!(
+            config
+                .artwork(Some("Faith"), Some("http://x/t.jpg"), &dir)
+                .await
+                .as_deref(),
+            Some("assets/faith.jpg")
+        );
+        // Thumbnails are only used when enabled
+        assert_eq!(
+            config
+                .artwork(None, Some("http://x/t.jpg"), &dir)
+                .await
+                .as_deref(),
+            Some("assets/podcast.jpg")
+        );
+    }
+
+    #[test]
+    fn test_square_filter() {
+        assert_eq!(
+            square_filter(1400),
+            "crop='min(iw,ih)':'min(iw,ih)',scale=1400:1400"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_numbers() {
+        let pool = setup_db().await;
+        save(&pool, "a", Some("Faith")).await;
+        save(&pool, "b", Some("Hope")).await;
+        save(&pool, "c", Some("Faith")).await;
+        save(&pool, "d", None).await;
+        // Every target failed to publish this one, so it takes no number
+        let failed = Episode {
+            video_id: "e".to_string(),
+            series: Some("Charity".to_string()),
+            publications: vec![Publication {
+                target: "spotify".to_string(),
+                error: Some("Login failed".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut tx = pool.begin().await.unwrap();
+        db::save_episode(&mut tx, &failed).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let config = |numbering| DetailsConfig {
+            numbering,
+            ..Default::default()
+        };
+        assert_eq!(
+            config(Numbering::None)
+                .numbers(&pool, Some("Faith"))
+                .await
+                .unwrap(),
+            Numbers::default()
+        );
+        assert_eq!(
+            config(Numbering::Overall)
+                .numbers(&pool, Some("Faith"))
+                .await
+                .unwrap(),
+            Numbers {
+                season: None,
+                episode: Some(5)
+            }
+        );
+        let series = config(Numbering::Series);
+        assert_eq!(
+            series.numbers(&pool, Some("Faith")).await.unwrap(),
+            Numbers {
+                season: Some(1),
+                episode: Some(3)
+            }
+        );
+        assert_eq!(
+            series.numbers(&pool, Some("Love")).await.unwrap(),
+            Numbers {
+                season: Some(3),
+                episode: Some(1)
+            }
+        );
+        let year = config(Numbering::Year).numbers(&pool, None).await.unwrap();
+        assert_eq!(year.season, Some(Local::now().year() as u32));
+        assert_eq!(year.episode, Some(5));
+        assert_eq!(year.offset(2).episode, Some(7));
+    }
+}
@@ -0,0 +1,277 @@
+//! Failure diagnostics module.
+//!
+//! When a step of the Spotify browser automation fails, the state of the browser is
+//! captured into a timestamped folder under `DIAGNOSTICS_DIR` (default
+//! `diagnostics/`):
+//!
+//! - `screenshot.png`: full-page screenshot
+//! - `page.html`: the DOM of the current page
+//! - `url.txt`: the current URL
+//! - `console.log`: the browser console log (Chrome only)
+//! - `error.txt`: the error chain
+//!
+//! The folder is attached to the error as `FailureDiagnostics` and recorded with the
+//! failed publication in the database.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use fantoccini::Client;
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{error, warn};
+
+/// Folder diagnostics are written to when `DIAGNOSTICS_DIR` is not set
+pub const DEFAULT_DIAGNOSTICS_DIR: &str = "diagnostics";
+
+/// Tallest page captured by the full-page screenshot, in pixels
+const MAX_SCREENSHOT_HEIGHT: u64 = 16_384;
+
+/// Error context naming the failed step and where its diagnostics were saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureDiagnostics {
+    pub step: String,
+    pub dir: PathBuf,
+}
+
+impl std::fmt::Display for FailureDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Step '{}' failed, diagnostics saved to {}",
+            self.step,
+            self.dir.display()
+        )
+    }
+}
+
+/// Tracks the current automation step and captures the browser state on failure.
+#[derive(Debug)]
+pub struct Diagnostics {
+    pub dir: PathBuf,
+    step: Mutex<&'static str>,
+}
+
+impl Diagnostics {
+    /// Creates diagnostics written under `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            step: Mutex::new("start"),
+        }
+    }
+
+    /// Reads the diagnostics folder from `DIAGNOSTICS_DIR`.
+    pub fn from_env() -> Self {
+        Self::new(
+            env::var("DIAGNOSTICS_DIR")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_DIAGNOSTICS_DIR.to_string()),
+        )
+    }
+
+    /// Records the step that is about to run.
+    pub fn enter(&self, step: &'static str) {
+        *self.step.lock().unwrap_or_else(|e| e.into_inner()) = step;
+    }
+
+    /// Returns the step that is running (or failed).
+    pub fn step(&self) -> &'static str {
+        *self.step.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Captures the browser state for a failed step and attaches the folder to the error.
+    ///
+    /// Capturing is best effort: parts that cannot be captured (e.g. because the
+    /// browser crashed) are skipped, and the error is returned unchanged if nothing
+    /// could be written.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The WebDriver client of the failed session
+    /// * `console_log_url` - URL of the WebDriver server to read the console log from,
+    ///   or `None` if the driver has no log endpoint
+    /// * `err` - The error the step failed with
+    ///
+    /// # Returns
+    ///
+    /// Returns `err` with `FailureDiagnostics` as context.
+    pub async fn fail(
+        &self,
+        client: &Client,
+        console_log_url: Option<&str>,
+        err: anyhow::Error,
+    ) -> anyhow::Error {
+        let step = self.step();
+        let dir = self.dir.join(folder_name(&Local::now(), step));
+
+        match capture(client, console_log_url, &dir, &err).await {
+            Ok(()) => {
+                error!(step = %step, dir = %dir.display(), "Automation step failed, diagnostics captured");
+                err.context(FailureDiagnostics {
+                    step: step.to_string(),
+                    dir,
+                })
+            }
+            Err(e) => {
+                warn!(step = %step, error = %e, "Failed to capture diagnostics");
+                err
+            }
+        }
+    }
+}
+
+/// Returns the folder name of a failure, e.g. `20261018-180501-login`.
+fn folder_name(time: &DateTime<Local>, step: &str) -> String {
+    format!("{}-{step}", time.format("%Y%m%d-%H%M%S"))
+}
+
+/// Writes every part of the diagnostics that can be captured into `dir`.
+async fn capture(
+    client: &Client,
+    console_log_url: Option<&str>,
+    dir: &Path,
+    err: &anyhow::Error,
+) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("error.txt"), format!("{err:?}\n"))?;
+
+    match client.current_url().await {
+        Ok(url) => fs::write(dir.join("url.txt"), format!("{url}\n"))?,
+        Err(e) => warn!(error = %e, "Failed to read current URL"),
+    }
+    match client.source().await {
+        Ok(html) => fs::write(dir.join("page.html"), html)?,
+        Err(e) => warn!(error = %e, "Failed to read page HTML"),
+    }
+    match full_page_screenshot(client).await {
+        Ok(png) => fs::write(dir.join("screenshot.png"), png)?,
+        Err(e) => warn!(error = %e, "Failed to take screenshot"),
+    }
+    if let Some(url) = console_log_url {
+        match console_log(client, url).await {
+            Ok(log) => fs::write(dir.join("console.log"), log)?,
+            Err(e) => warn!(error = %e, "Failed to read browser console log"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes a screenshot of the whole page by growing the window to the page height.
+async fn full_page_screenshot(client: &Client) -> Result<Vec<u8>> {
+    let (width, height) = client.get_window_size().await?;
+    let size = client
+        .execute(
+            "return [document.documentElement.scrollWidth, document.documentElement.scrollHeight];",
+            Vec::new(),
+        )
+        .await?;
+    let page_height = size.get(1).and_then(Value::as_u64).unwrap_or(height);
+    let page_width = size.get(0).and_then(Value::as_u64).unwrap_or(width);
+
+    let resized = page_height > height || page_width > width;
+    if resized {
+        let full_width = page_width.max(width) as u32;
+        let full_height = page_height.min(MAX_SCREENSHOT_HEIGHT).max(height) as u32;
+        client.set_window_size(full_width, full_height).await?;
+    }
+    let png = client.screenshot().await;
+    if resized {
+        client.set_window_size(width as u32, height as u32).await?;
+    }
+
+    Ok(png?)
+}
+
+/// Reads the browser console log through chromedriver's log endpoint.
+///
+/// Requires the session to be created with the `goog:loggingPrefs` capability.
+async fn console_log(client: &Client, webdriver_url: &str) -> Result<String> {
+    let session = client
+        .session_id()
+        .await?
+        .ok_or_else(|| anyhow!("No WebDriver session"))?;
+    let response: Value = reqwest::Client::new()
+        .post(format!(
+            "{}/session/{session}/se/log",
+            webdriver_url.trim_end_matches('/')
+        ))
+        .json(&json!({ "type": "browser" }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(format_console(&response["value"]))
+}
+
+/// Formats WebDriver log entries as `<timestamp> <LEVEL> <message>` lines.
+fn format_console(entries: &Value) -> String {
+    let Some(entries) = entries.as_array() else {
+        return String::new();
+    };
+
+    entries
+        .iter()
+        .map(|entry| {
+            let time = entry["timestamp"]
+                .as_i64()
+                .and_then(DateTime::from_timestamp_millis)
+                .map(|t| t.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+                .unwrap_or_default();
+            format!(
+                "{time} {} {}\n",
+                entry["level"].as_str().unwrap_or("INFO"),
+                entry["message"].as_str().unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_folder_name() {
+        let time = Local.with_ymd_and_hms(2026, 10, 18, 18, 5, 1).unwrap();
+        assert_eq!(folder_name(&time, "login"), "20261018-180501-login");
+    }
+
+    #[test]
+    fn test_format_console() {
+        let entries = json!([
+            {"level": "SEVERE", "message": "Failed to load resource", "timestamp": 1792346701000i64},
+            {"level": "WARNING", "message": "Deprecated API"}
+        ]);
+        assert_eq!(
+            format_console(&entries),
+            "2026-10-18T18:05:01.000Z SEVERE Failed to load resource\n WARNING Deprecated API\n"
+        );
+        assert_eq!(format_console(&Value::Null), "");
+    }
+
+    #[test]
+    fn test_failure_diagnostics_context() {
+        let err =
+            anyhow!("Page element not found: login.password_input").context(FailureDiagnostics {
+                step: "login".to_string(),
+                dir: PathBuf::from("diagnostics/20261018-180501-login"),
+            });
+        assert_eq!(
+            err.to_string(),
+            "Step 'login' failed, diagnostics saved to diagnostics/20261018-180501-login"
+        );
+        assert_eq!(
+            err.downcast_ref::<FailureDiagnostics>().unwrap().step,
+            "login"
+        );
+        assert!(format!("{err:#}").contains("login.password_input"));
+    }
+}
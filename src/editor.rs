@@ -0,0 +1,282 @@
+//! Slate.js editor module.
+//!
+//! Spotify's episode description is a Slate.js rich text editor, which drops
+//! characters when a whole string is sent as key events and takes minutes when the
+//! text is typed one character at a time. Instead, the description is pasted:
+//!
+//! - The text is converted to HTML (`to_html`): one paragraph per line, with web
+//!   addresses turned into links
+//! - A paste event carrying the HTML and plain text is dispatched on the editor
+//!   through WebDriver script execution (`paste`), followed by the `beforeinput`
+//!   event Slate listens to in browsers that support it
+//! - The editor's text is read back (`read_text`) and compared with the description
+//!   (`same_text`); the caller falls back to typing when they differ
+
+use anyhow::{Context, Result};
+use fantoccini::elements::Element;
+use fantoccini::Client;
+use serde_json::{json, Value};
+
+/// Pastes `arguments[1]` (plain text) and `arguments[2]` (HTML) into the editor
+/// `arguments[0]`, at the current selection or at the end of the editor.
+///
+/// Slate handles the paste event itself where `beforeinput` is not supported, and
+/// the `insertFromPaste` input event otherwise; the second is only dispatched when
+/// nothing handled the first.
+const PASTE_SCRIPT: &str = r#"
+const [editor, text, html] = arguments;
+editor.focus();
+const selection = window.getSelection();
+if (!selection.rangeCount || !editor.contains(selection.anchorNode)) {
+  const range = document.createRange();
+  range.selectNodeContents(editor);
+  range.collapse(false);
+  selection.removeAllRanges();
+  selection.addRange(range);
+}
+const data = new DataTransfer();
+data.setData('text/plain', text);
+data.setData('text/html', html);
+const paste = new ClipboardEvent('paste', {
+  clipboardData: data, bubbles: true, cancelable: true,
+});
+if (editor.dispatchEvent(paste)) {
+  editor.dispatchEvent(new InputEvent('beforeinput', {
+    inputType: 'insertFromPaste', dataTransfer: data, bubbles: true, cancelable: true,
+  }));
+}
+"#;
+
+/// Returns the text of the editor `arguments[0]` as the browser renders it.
+const READ_SCRIPT: &str = "return arguments[0].innerText;";
+
+/// Converts a plain-text description to the HTML pasted into the editor.
+///
+/// Each line becomes a paragraph (an empty line an empty paragraph, so blank lines
+/// between paragraphs are kept) and `http://` and `https://` addresses become links.
+///
+/// # Arguments
+///
+/// * `text` - The description, with `\n` line breaks
+///
+/// # Returns
+///
+/// Returns the HTML, with `<`, `>`, `&` and `"` escaped.
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::editor::to_html;
+///
+/// assert_eq!(
+///     to_html("Join us online.\nhttps://example.com"),
+///     "<p>Join us online.</p><p><a href=\"https://example.com\">https://example.com</a></p>"
+/// );
+/// ```
+pub fn to_html(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                "<p><br></p>".to_string()
+            } else {
+                format!("<p>{}</p>", linkify(line))
+            }
+        })
+        .collect()
+}
+
+/// Escapes a line and turns the web addresses in it into links.
+fn linkify(line: &str) -> String {
+    let mut html = String::new();
+    let mut rest = line;
+    while let Some(start) = find_url(rest) {
+        html.push_str(&escape(&rest[..start]));
+        let url = &rest[start..];
+        let end = url.find(char::is_whitespace).unwrap_or(url.len());
+        // Punctuation ending a sentence is not part of the address
+        let url = url[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\'']);
+        html.push_str(&format!("<a href=\"{0}\">{0}</a>", escape(url)));
+        rest = &rest[start + url.len()..];
+    }
+    html.push_str(&escape(rest));
+    html
+}
+
+/// Returns the start of the first web address in `text`.
+fn find_url(text: &str) -> Option<usize> {
+    [text.find("https://"), text.find("http://")]
+        .into_iter()
+        .flatten()
+        .min()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns whether the text read back from the editor is the description.
+///
+/// The comparison ignores what the editor adds or changes in rendering: the
+/// zero-width characters Slate puts in empty paragraphs, non-breaking spaces,
+/// blank lines (paragraphs are separated by one or two line breaks depending on
+/// the browser) and whitespace at the ends of lines. Dropped, doubled or reordered
+/// characters are not ignored.
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::editor::same_text;
+///
+/// assert!(same_text("Join us.\n\nOnline", "Join us.\n\u{feff}\n\nOnline\n"));
+/// assert!(!same_text("Join us online.", "Jin us online."));
+/// ```
+pub fn same_text(expected: &str, actual: &str) -> bool {
+    lines(expected) == lines(actual)
+}
+
+/// Describes the first line where the text read back from the editor differs from
+/// the description, compared as in `same_text`, or returns `None` if they match.
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::editor::first_difference;
+///
+/// assert_eq!(
+///     first_difference("Join us.\nOnline", "Join us.\nOnlne").as_deref(),
+///     Some("line 2: expected \"Online\", found \"Onlne\"")
+/// );
+/// ```
+pub fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let (expected, actual) = (lines(expected), lines(actual));
+    let index =
+        (0..expected.len().max(actual.len())).find(|&i| expected.get(i) != actual.get(i))?;
+    let line = |line: Option<&String>| line.map_or("nothing".to_string(), |l| format!("{l:?}"));
+    Some(format!(
+        "line {}: expected {}, found {}",
+        index + 1,
+        line(expected.get(index)),
+        line(actual.get(index))
+    ))
+}
+
+/// Returns the non-empty lines of `text`, trimmed and with rendering characters
+/// normalised.
+fn lines(text: &str) -> Vec<String> {
+    text.replace(['\u{feff}', '\u{200b}'], "")
+        .replace('\u{a0}', " ")
+        .replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Pastes `text` into a Slate.js editor through a script-dispatched paste event.
+///
+/// # Arguments
+///
+/// * `client` - The WebDriver client showing the editor
+/// * `editor` - The editor element, focused and emptied by the caller
+/// * `text` - The text to paste, with `\n` line breaks
+///
+/// # Errors
+///
+/// Returns an error if the script cannot be run, e.g. because the editor is no
+/// longer on the page. A paste the editor ignores is not an error; check the
+/// result with `read_text`.
+pub async fn paste(client: &Client, editor: &Element, text: &str) -> Result<()> {
+    let editor = serde_json::to_value(editor)?;
+    client
+        .execute(
+            PASTE_SCRIPT,
+            vec![editor, json!(text), json!(to_html(text))],
+        )
+        .await
+        .context("Failed to paste into the editor")?;
+    Ok(())
+}
+
+/// Reads the text of a Slate.js editor as the browser renders it.
+///
+/// # Errors
+///
+/// Returns an error if the script cannot be run.
+pub async fn read_text(client: &Client, editor: &Element) -> Result<String> {
+    let editor = serde_json::to_value(editor)?;
+    let text = client
+        .execute(READ_SCRIPT, vec![editor])
+        .await
+        .context("Failed to read the editor text")?;
+    Ok(match text {
+        Value::String(text) => text,
+        _ => String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(to_html("Join us online."), "<p>Join us online.</p>");
+        assert_eq!(
+            to_html("Faith & Works\r\n\r\n<Part 2>"),
+            "<p>Faith &amp; Works</p><p><br></p><p>&lt;Part 2&gt;</p>"
+        );
+    }
+
+    #[test]
+    fn test_to_html_links() {
+        assert_eq!(
+            to_html("Watch at https://youtu.be/abc?t=1&x=2. Or http://example.com"),
+            "<p>Watch at <a href=\"https://youtu.be/abc?t=1&amp;x=2\">\
+             https://youtu.be/abc?t=1&amp;x=2</a>. Or \
+             <a href=\"http://example.com\">http://example.com</a></p>"
+        );
+        assert_eq!(
+            to_html("(see https://example.com/notes)"),
+            "<p>(see <a href=\"https://example.com/notes\">https://example.com/notes</a>)</p>"
+        );
+    }
+
+    #[test]
+    fn test_same_text() {
+        let description = "Join us online.\n\nhttps://example.com\n00:00 Welcome";
+        assert!(same_text(
+            description,
+            "Join us online.\n\n\u{feff}\n\nhttps://example.com\n\n00:00\u{a0}Welcome\n"
+        ));
+        assert!(same_text(description, description));
+        assert!(!same_text(
+            description,
+            "Join us online.\n\nhttps://example.com"
+        ));
+        assert!(!same_text(
+            description,
+            "Join us onine.\n\nhttps://example.com\n00:00 Welcome"
+        ));
+        assert!(!same_text("Join us", ""));
+    }
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(
+            first_difference("Join us.\n\nOnline", "Join us.\nOnline "),
+            None
+        );
+        assert_eq!(
+            first_difference("Join us online.", "Jin us online.").as_deref(),
+            Some("line 1: expected \"Join us online.\", found \"Jin us online.\"")
+        );
+        assert_eq!(
+            first_difference("Join us.\nOnline", "Join us.").as_deref(),
+            Some("line 2: expected \"Online\", found nothing")
+        );
+    }
+}
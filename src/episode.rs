@@ -5,16 +5,175 @@
 
 use anyhow::{anyhow, Result};
 use regex::Regex;
+use std::path::Path;
 use std::process::Command;
+use std::str::FromStr;
 use std::{env, fs};
 use tokio::task;
 use tracing::info;
 use youtube_dl::{YoutubeDl, YoutubeDlOutput};
 
+use crate::chapters;
+use crate::publish::{EpisodeStatus, Publication};
+use crate::scripture::ScriptureRef;
+use crate::transcript::Transcript;
+
+/// A chapter of a YouTube video or of an episode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    /// Start time in seconds
+    pub start: u32,
+    /// End time in seconds, if known
+    pub end: Option<u32>,
+    pub title: String,
+}
+
+/// Metadata of a downloaded YouTube video.
+#[derive(Debug, Clone)]
+pub struct VideoMetadata {
+    /// The sanitized video title
+    pub title: String,
+    pub description: String,
+    /// The absolute path to the downloaded video
+    pub video_path: String,
+    /// The video duration in seconds
+    pub duration: u32,
+    pub chapters: Vec<Chapter>,
+    /// URL of the video thumbnail
+    pub thumbnail: Option<String>,
+}
+
+/// A rule that turns matching chapters of a video into episodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentRule {
+    /// Rule name (e.g. "testimony"), used to look up its templates
+    pub name: String,
+    /// Lowercase text a chapter title must contain to match
+    pub keyword: String,
+}
+
+/// A part of a video that becomes its own episode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Name of the rule that produced the segment
+    pub rule: String,
+    /// Title of the matching chapter
+    pub title: String,
+    /// Start time in seconds
+    pub start: u32,
+    /// End time in seconds
+    pub end: u32,
+}
+
+/// Kind of episode, as shown by podcast apps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EpisodeType {
+    /// A regular episode
+    #[default]
+    Full,
+    /// A short promotion of the show
+    Trailer,
+    /// Extra content, such as a testimony or a guest message
+    Bonus,
+}
+
+impl EpisodeType {
+    /// Returns the name used in feeds and the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EpisodeType::Full => "full",
+            EpisodeType::Trailer => "trailer",
+            EpisodeType::Bonus => "bonus",
+        }
+    }
+}
+
+impl FromStr for EpisodeType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "full" => Ok(EpisodeType::Full),
+            "trailer" => Ok(EpisodeType::Trailer),
+            "bonus" => Ok(EpisodeType::Bonus),
+            other => Err(anyhow!(
+                "Invalid episode type: {other} (expected full, trailer or bonus)"
+            )),
+        }
+    }
+}
+
+/// An episode produced from a processed video, ready to be recorded in the database.
+#[derive(Debug, Clone, Default)]
+pub struct Episode {
+    pub video_id: String,
+    /// Stable identifier of the episode: the video ID, with a numbered suffix for
+    /// every segment after the first (e.g. "dQw4w9WgXcQ-2")
+    pub guid: String,
+    /// Name of the segment rule that produced this episode
+    pub segment: String,
+    /// Path to the episode audio file
+    pub audio_path: String,
+    /// Path to the episode cover art, if any
+    pub artwork: Option<String>,
+    /// Season number, if episodes are numbered by series or year
+    pub season: Option<u32>,
+    /// Episode number, if episodes are numbered
+    pub episode_number: Option<u32>,
+    pub episode_type: EpisodeType,
+    /// Whether the episode contains explicit content
+    pub explicit: bool,
+    /// Length of the episode audio in seconds
+    pub duration: u32,
+    /// The rendered episode title
+    pub title: String,
+    /// The rendered episode description
+    pub description: String,
+    /// Scripture references found in the video title, description and chapters
+    pub scripture: Vec<ScriptureRef>,
+    /// Who preached, if detected
+    pub speaker: Option<String>,
+    /// The sermon series, if detected
+    pub series: Option<String>,
+    /// Speech-to-text transcript, if transcription is enabled
+    pub transcript: Option<Transcript>,
+    /// Sections of the segment, relative to the start of the episode audio
+    pub chapters: Vec<Chapter>,
+    /// The outcome of publishing the episode to each target
+    pub publications: Vec<Publication>,
+}
+
+impl Episode {
+    /// Returns the status reported by the first target that published the episode.
+    pub fn status(&self) -> Option<EpisodeStatus> {
+        self.publications
+            .iter()
+            .filter(|p| !p.dry_run)
+            .find_map(|p| p.published.as_ref().map(|published| published.status))
+    }
+
+    /// Returns true if the episode was published to `target` (not in a dry run).
+    pub fn is_published_to(&self, target: &str) -> bool {
+        self.publications
+            .iter()
+            .any(|p| p.target == target && p.succeeded() && !p.dry_run)
+    }
+
+    /// Returns true if no target published the episode (or would have, in a dry run).
+    pub fn failed(&self) -> bool {
+        !self.publications.iter().any(|p| p.succeeded())
+    }
+
+    /// Returns true if the episode was only checked by a dry run.
+    pub fn is_dry_run(&self) -> bool {
+        !self.publications.is_empty() && self.publications.iter().all(|p| p.dry_run)
+    }
+}
+
 /// Downloads a YouTube video and extracts its metadata.
 ///
 /// This function uses yt-dlp to download a video in MP4 format and extract
-/// metadata including title, description, and duration. The video is saved
+/// metadata including title, description, duration, and chapters. The video is saved
 /// to the `assets/` directory and renamed to `video.mp4`. The title is
 /// sanitized to keep at most 2 segments if separated by `|`.
 ///
@@ -24,11 +183,9 @@ use youtube_dl::{YoutubeDl, YoutubeDlOutput};
 ///
 /// # Returns
 ///
-/// Returns a tuple containing:
-/// - `title` - The sanitized video title (String)
-/// - `description` - The video description (String)
-/// - `video_path` - The absolute path to the downloaded video (String)
-/// - `duration` - The video duration in seconds (u32)
+/// Returns a `VideoMetadata` containing the sanitized title, description,
+/// absolute path to the downloaded video, duration in seconds, chapters and
+/// thumbnail URL.
 ///
 /// # Errors
 ///
@@ -44,12 +201,11 @@ use youtube_dl::{YoutubeDl, YoutubeDlOutput};
 ///
 /// ```no_run
 /// # tokio_test::block_on(async {
-/// let (title, desc, path, duration) =
-///     audio_epistles::episode::fetch_metadata("dQw4w9WgXcQ").await.unwrap();
-/// println!("Downloaded: {} ({}s)", title, duration);
+/// let metadata = audio_epistles::episode::fetch_metadata("dQw4w9WgXcQ").await.unwrap();
+/// println!("Downloaded: {} ({}s)", metadata.title, metadata.duration);
 /// # })
 /// ```
-pub async fn fetch_metadata(video_id: &str) -> Result<(String, String, String, u32)> {
+pub async fn fetch_metadata(video_id: &str) -> Result<VideoMetadata> {
     let video_url = format!("https://www.youtube.com/watch?v={video_id}");
     info!(video_url = %video_url, "Downloading video and metadata");
 
@@ -116,10 +272,28 @@ pub async fn fetch_metadata(video_id: &str) -> Result<(String, String, String, u
                 .and_then(|d| d.as_u64())
                 .unwrap_or(0) as u32;
 
+            let chapters = video
+                .chapters
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| Chapter {
+                    start: c.start_time.unwrap_or(0.0) as u32,
+                    end: c.end_time.map(|t| t as u32),
+                    title: c.title.unwrap_or_default(),
+                })
+                .collect();
+
             let path_str = final_video_path.to_string_lossy().to_string();
             info!(path = %path_str, "Downloaded video successfully");
 
-            Ok((title, desc, path_str, duration))
+            Ok(VideoMetadata {
+                title,
+                description: desc,
+                video_path: path_str,
+                duration,
+                chapters,
+                thumbnail: video.thumbnail,
+            })
         }
         _ => Err(anyhow!("Expected single video, got playlist.")),
     }
@@ -172,21 +346,13 @@ pub(crate) fn parse_timestamp(ts: &str) -> u32 {
 /// assert_eq!(result, Some((900, 2700))); // 15:00 to 45:00
 /// ```
 pub fn extract_sermon_chapter(description: &str, video_end: u32) -> Option<(u32, u32)> {
-    // Match all chapter lines: timestamp + title
-    let re = Regex::new(r"(?m)^(\d{1,2}:\d{2}(?::\d{2})?)\s+(.+)$").ok()?;
-
-    let mut chapters: Vec<(u32, String)> = re
-        .captures_iter(description)
-        .map(|cap| (parse_timestamp(&cap[1]), cap[2].to_lowercase()))
-        .collect();
+    let chapters = top_level_chapters(description);
 
     // If no chapters → return full length
     if chapters.is_empty() {
         return Some((0, video_end));
     }
 
-    chapters.sort_by_key(|c| c.0);
-
     // Find the first video chapter called "sermon"
     if let Some((i, (start, _))) = chapters
         .iter()
@@ -204,11 +370,138 @@ pub fn extract_sermon_chapter(description: &str, video_end: u32) -> Option<(u32,
     None
 }
 
+/// Parses top-level chapter lines (timestamp + title) into `(start, lowercase title)`,
+/// sorted by start time.
+fn top_level_chapters(description: &str) -> Vec<(u32, String)> {
+    // Pattern is a compile-time constant and will never fail to compile
+    let re = Regex::new(r"(?m)^(\d{1,2}:\d{2}(?::\d{2})?)\s+(.+)$").unwrap();
+
+    let mut chapters: Vec<(u32, String)> = re
+        .captures_iter(description)
+        .map(|cap| (parse_timestamp(&cap[1]), cap[2].to_lowercase()))
+        .collect();
+    chapters.sort_by_key(|c| c.0);
+    chapters
+}
+
+/// Parses segment rules from a comma-separated list.
+///
+/// Each entry is either a keyword (`testimony`), which also names the rule, or
+/// `name=keyword` (`guest=guest speaker`). Keywords are matched case-insensitively.
+///
+/// # Example
+///
+/// ```
+/// let rules = audio_epistles::episode::parse_segment_rules("sermon, guest=Guest Speaker");
+/// assert_eq!(rules[1].name, "guest");
+/// assert_eq!(rules[1].keyword, "guest speaker");
+/// ```
+pub fn parse_segment_rules(value: &str) -> Vec<SegmentRule> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (name, keyword) = entry.split_once('=').unwrap_or((entry, entry));
+            let (name, keyword) = (name.trim(), keyword.trim());
+            (!name.is_empty() && !keyword.is_empty()).then(|| SegmentRule {
+                name: name.to_lowercase(),
+                keyword: keyword.to_lowercase(),
+            })
+        })
+        .collect()
+}
+
+/// Extracts the segments of a video that should become episodes.
+///
+/// Every top-level chapter whose title contains a rule's keyword becomes a segment
+/// that ends where the next chapter starts (or at the end of the video). A chapter
+/// is claimed by the first rule it matches. If the description has no chapters,
+/// the whole video becomes a single segment of the first rule.
+///
+/// With no rules, the legacy behaviour of `extract_sermon_chapter` applies: only
+/// the first chapter containing "sermon" is used.
+///
+/// # Arguments
+///
+/// * `description` - The video description text containing chapter markers
+/// * `video_end` - The total duration of the video in seconds
+/// * `rules` - The segment rules, in priority order
+///
+/// # Returns
+///
+/// Returns the segments in chronological order; empty if no chapter matches.
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::episode::{extract_segments, parse_segment_rules};
+/// let description = "0:00 Worship\n10:00 Testimony\n15:00 Sermon\n45:00 Closing";
+/// let segments = extract_segments(description, 3000, &parse_segment_rules("sermon,testimony"));
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!((segments[0].rule.as_str(), segments[0].start), ("testimony", 600));
+/// assert_eq!((segments[1].rule.as_str(), segments[1].end), ("sermon", 2700));
+/// ```
+pub fn extract_segments(description: &str, video_end: u32, rules: &[SegmentRule]) -> Vec<Segment> {
+    let chapters = top_level_chapters(description);
+
+    if rules.is_empty() {
+        return extract_sermon_chapter(description, video_end)
+            .map(|(start, end)| Segment {
+                rule: "sermon".to_string(),
+                title: chapters
+                    .iter()
+                    .find(|c| c.0 == start)
+                    .map(|c| c.1.clone())
+                    .unwrap_or_default(),
+                start,
+                end,
+            })
+            .into_iter()
+            .collect();
+    }
+
+    if chapters.is_empty() {
+        return vec![Segment {
+            rule: rules[0].name.clone(),
+            title: String::new(),
+            start: 0,
+            end: video_end,
+        }];
+    }
+
+    // Keep the original casing of chapter titles for templates
+    let titles: Vec<(u32, String)> = chapters::parse_chapters(description)
+        .into_iter()
+        .map(|c| (c.start, c.title))
+        .collect();
+
+    chapters
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (start, title))| {
+            let rule = rules.iter().find(|r| title.contains(&r.keyword))?;
+            let end = chapters.get(i + 1).map_or(video_end, |next| next.0);
+            let title = titles
+                .iter()
+                .find(|(s, t)| s == start && t.to_lowercase() == *title)
+                .map_or_else(|| title.clone(), |(_, t)| t.clone());
+            Some(Segment {
+                rule: rule.name.clone(),
+                title,
+                start: *start,
+                end,
+            })
+        })
+        .collect()
+}
+
 /// Trims audio from a video file using ffmpeg and saves it as MP3.
 ///
 /// This function extracts an audio segment from a video file, converts it to
 /// MP3 format using the libmp3lame codec, and saves it to the specified output path.
-/// The `-vn` flag is used to strip video, keeping only audio.
+/// The `-vn` flag is used to strip video, keeping only audio. Each tag is written
+/// as ID3v2.3 metadata; keys ffmpeg does not map to a standard frame (e.g.
+/// `scripture`) are stored as user-defined `TXXX` frames. Chapters, relative to
+/// the start of the extracted segment, are written as ID3 `CHAP` frames.
 ///
 /// # Arguments
 ///
@@ -216,6 +509,8 @@ pub fn extract_sermon_chapter(description: &str, video_end: u32) -> Option<(u32,
 /// * `output` - Path where the output MP3 file should be saved
 /// * `start` - Start time in seconds for the audio segment
 /// * `duration` - Duration in seconds of the audio segment to extract
+/// * `tags` - ID3 tags as `(key, value)` pairs (e.g. `("title", "Sunday Service")`)
+/// * `chapters` - Episode-relative chapters to embed (may be empty)
 ///
 /// # Returns
 ///
@@ -224,6 +519,7 @@ pub fn extract_sermon_chapter(description: &str, video_end: u32) -> Option<(u32,
 /// # Errors
 ///
 /// Returns an error if:
+/// - The chapter metadata file cannot be written
 /// - The ffmpeg command fails to execute
 /// - The ffmpeg process exits with a non-zero status code
 ///
@@ -235,25 +531,54 @@ pub fn extract_sermon_chapter(description: &str, video_end: u32) -> Option<(u32,
 ///     "assets/video.mp4",
 ///     "assets/audio.mp3",
 ///     900,  // 15 minutes
-///     1800  // 30 minutes
+///     1800, // 30 minutes
+///     &[("title", "Sunday Service"), ("scripture", "John 3:16")],
+///     &[],
 /// ).unwrap();
 /// ```
-pub fn trim_audio(input: &str, output: &str, start: u32, duration: u32) -> Result<()> {
-    let status = Command::new("ffmpeg")
-        .args([
-            "-y",
-            "-i",
-            input,
-            "-ss",
-            &start.to_string(),
-            "-t",
-            &duration.to_string(),
-            "-vn",
-            "-acodec",
-            "libmp3lame",
-            output,
-        ])
-        .status()?;
+pub fn trim_audio(
+    input: &str,
+    output: &str,
+    start: u32,
+    duration: u32,
+    tags: &[(&str, &str)],
+    chapters: &[Chapter],
+) -> Result<()> {
+    let mut command = Command::new("ffmpeg");
+    // Seek on the input so that chapter times are not offset by the start time
+    command.args(["-y", "-ss", &start.to_string(), "-i", input]);
+
+    let metadata_file = Path::new(output).with_extension("ffmeta");
+    if !chapters.is_empty() {
+        fs::write(&metadata_file, chapters::to_ffmetadata(chapters))?;
+        command.arg("-i").arg(&metadata_file).args([
+            "-map",
+            "0:a",
+            "-map_metadata",
+            "1",
+            "-map_chapters",
+            "1",
+        ]);
+    }
+
+    command.args([
+        "-t",
+        &duration.to_string(),
+        "-vn",
+        "-acodec",
+        "libmp3lame",
+        "-id3v2_version",
+        "3",
+    ]);
+    for (key, value) in tags {
+        command.arg("-metadata").arg(format!("{key}={value}"));
+    }
+    let status = command.arg(output).status();
+
+    if !chapters.is_empty() {
+        let _ = fs::remove_file(&metadata_file);
+    }
+    let status = status?;
 
     if status.success() {
         Ok(())
@@ -266,6 +591,13 @@ pub fn trim_audio(input: &str, output: &str, start: u32, duration: u32) -> Resul
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_episode_type() {
+        assert_eq!("Bonus".parse::<EpisodeType>().unwrap(), EpisodeType::Bonus);
+        assert_eq!(EpisodeType::Trailer.as_str(), "trailer");
+        assert!("special".parse::<EpisodeType>().is_err());
+    }
+
     #[test]
     fn test_parse_timestamp_minutes_seconds() {
         assert_eq!(parse_timestamp("12:34"), 754); // 12*60 + 34 = 754
@@ -393,4 +725,86 @@ mod tests {
         // Should still work correctly even if timestamps are not in order
         assert_eq!(result, Some((945, 2720)));
     }
+
+    #[test]
+    fn test_parse_segment_rules() {
+        let rules = parse_segment_rules(" Sermon ,guest=Guest Message,,=x,y=");
+        assert_eq!(
+            rules,
+            [
+                SegmentRule {
+                    name: "sermon".to_string(),
+                    keyword: "sermon".to_string()
+                },
+                SegmentRule {
+                    name: "guest".to_string(),
+                    keyword: "guest message".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_segments_multiple_rules() {
+        let description = r#"
+0:00 Worship
+12:00 Testimony - Sister Ada
+18:00 Guest Message
+50:00 Offering
+55:00 Sermon
+1:30:00 Closing
+"#;
+        let rules = parse_segment_rules("guest=guest message,sermon,testimony");
+        let segments = extract_segments(description, 6000, &rules);
+        assert_eq!(
+            segments,
+            [
+                Segment {
+                    rule: "testimony".to_string(),
+                    title: "Testimony - Sister Ada".to_string(),
+                    start: 720,
+                    end: 1080
+                },
+                Segment {
+                    rule: "guest".to_string(),
+                    title: "Guest Message".to_string(),
+                    start: 1080,
+                    end: 3000
+                },
+                Segment {
+                    rule: "sermon".to_string(),
+                    title: "Sermon".to_string(),
+                    start: 3300,
+                    end: 5400
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_segments_same_rule_twice() {
+        let description = "0:00 Sermon part 1\n20:00 Break\n25:00 Sermon part 2";
+        let segments = extract_segments(description, 3000, &parse_segment_rules("sermon"));
+        let ranges: Vec<(u32, u32)> = segments.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(ranges, [(0, 1200), (1500, 3000)]);
+    }
+
+    #[test]
+    fn test_extract_segments_no_chapters() {
+        let segments = extract_segments("No chapters", 3000, &parse_segment_rules("sermon"));
+        assert_eq!(segments.len(), 1);
+        assert_eq!((segments[0].start, segments[0].end), (0, 3000));
+        assert_eq!(segments[0].rule, "sermon");
+    }
+
+    #[test]
+    fn test_extract_segments_without_rules_uses_first_sermon() {
+        let description = "0:00 Pre-Sermon Worship\n5:30 Main Sermon\n15:45 Post-Sermon";
+        let segments = extract_segments(description, 3000, &[]);
+        assert_eq!(segments.len(), 1);
+        assert_eq!((segments[0].start, segments[0].end), (0, 330));
+
+        let no_match = extract_segments("0:00 Welcome\n5:00 Closing", 3000, &[]);
+        assert!(no_match.is_empty());
+    }
 }
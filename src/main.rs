@@ -3,15 +3,41 @@
 //! This application automatically monitors a YouTube playlist for new sermon videos,
 //! downloads them, extracts the sermon audio segment, and uploads it to Spotify for Podcasters.
 
+mod browser;
+mod challenge;
+mod chapters;
 mod db;
+mod delay;
+mod details;
+mod diagnostics;
+mod editor;
 mod episode;
+mod manage;
+#[cfg(test)]
+mod mock_http;
+#[cfg(test)]
+mod mock_spotify;
+mod notify;
+mod pages;
+mod podbean;
 mod processor;
+mod publish;
+mod query;
+mod rss;
+mod s3;
+mod scripture;
+mod session;
+mod speaker;
+mod template;
+mod totp;
+mod transcript;
+mod transistor;
 mod types;
 mod video;
 mod webdriver;
 
 use anyhow::Result;
-use db::{get_last_id, save_id};
+use db::{get_last_id, save_episode};
 use dotenvy::dotenv;
 use processor::process;
 use sqlx::SqlitePool;
@@ -20,6 +46,17 @@ use std::time::Instant;
 use tracing::{error, info};
 use video::fetch_video;
 
+/// Regenerates the self-hosted feed from the episode history, uploading it to
+/// object storage when `upload` is set.
+async fn write_feed(pool: &SqlitePool, upload: bool) -> Result<()> {
+    let config = rss::FeedConfig::from_env()?;
+    rss::write_feed(pool, &config).await?;
+    if upload {
+        s3::S3Client::from_env()?.upload_feed(&config.dir).await?;
+    }
+    Ok(())
+}
+
 /// Main entry point for the audio epistles automation tool.
 ///
 /// This function orchestrates the entire workflow:
@@ -28,10 +65,16 @@ use video::fetch_video;
 /// 3. Fetches the latest video ID from the configured YouTube playlist
 /// 4. Compares it with the last processed video
 /// 5. If a new video is found, processes it (download, extract audio, upload to Spotify)
-/// 6. Updates the database with the new video ID upon successful completion
+/// 6. Records the new episode, its scripture references, speaker, series and publish status upon
+///    successful completion, and regenerates the RSS feed if the `rss` publisher is used
 ///
 /// The function tracks execution time and logs the total duration at the end.
 ///
+/// When started with a management command (`edit`, `replace-audio` or `delete`, see
+/// `manage.rs`), the command is run on an uploaded episode instead of steps 3 to 6.
+/// A query (`search`, `history` or `changes`, see `query.rs`) prints the matching recorded
+/// episodes or changes instead.
+///
 /// # Returns
 ///
 /// Returns `Ok(())` on success, or an error if database connection or environment setup fails.
@@ -39,9 +82,10 @@ use video::fetch_video;
 /// # Errors
 ///
 /// Returns an error if:
+/// - The command line arguments are not a valid query or management command
 /// - The `DB_URL` environment variable is not set
 /// - Database connection or initialization fails
-/// - Any critical operation in the pipeline fails
+/// - Any critical operation in the pipeline fails, or the management command fails
 ///
 /// # Example
 ///
@@ -66,23 +110,54 @@ async fn main() -> Result<()> {
 
     dotenv().ok();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    let query = query::Query::parse(&args)?;
+    let command = match query {
+        Some(_) => None,
+        None => manage::Command::parse(&args)?,
+    };
+
     let db_url = env::var("DB_URL")?;
     let pool = SqlitePool::connect(&db_url).await?;
 
     db::init(&pool).await?;
 
+    // Queries list recorded episodes instead of checking for a new video
+    if let Some(query) = query {
+        return query::run(&pool, &query).await;
+    }
+
+    // Management commands change an uploaded episode instead of checking for a new video
+    if let Some(command) = command {
+        return manage::run(&pool, &command).await;
+    }
+
     let last_seen_id = get_last_id(&pool).await?;
 
     match fetch_video().await {
         Ok(video_id) => {
             if video_id != last_seen_id {
-                if let Err(e) = process(&video_id).await {
-                    error!(error = %e, "Failed to process new video");
-                } else {
-                    let mut transaction = pool.begin().await?;
-                    save_id(&mut transaction, &video_id).await?;
-                    transaction.commit().await?;
-                    info!(video_id = %video_id, "Updated DB with video ID");
+                match process(&video_id, &pool).await {
+                    Ok(episodes) if episodes.iter().all(|e| e.is_dry_run()) => {
+                        info!(video_id = %video_id, episodes = episodes.len(), "Dry run finished, DB not updated");
+                    }
+                    Ok(episodes) => {
+                        let mut transaction = pool.begin().await?;
+                        for episode in &episodes {
+                            save_episode(&mut transaction, episode).await?;
+                        }
+                        transaction.commit().await?;
+                        let failed = episodes.iter().filter(|e| e.failed()).count();
+                        info!(video_id = %video_id, episodes = episodes.len(), failed, "Updated DB with video ID");
+
+                        if episodes.iter().any(|e| e.is_published_to("rss")) {
+                            let upload = episodes.iter().any(|e| e.is_published_to("s3"));
+                            if let Err(e) = write_feed(&pool, upload).await {
+                                error!(error = %e, "Failed to regenerate feed");
+                            }
+                        }
+                    }
+                    Err(e) => error!(error = %e, "Failed to process new video"),
                 }
             } else {
                 info!("No new video found since last publish");
@@ -0,0 +1,494 @@
+//! Management commands for episodes that were already uploaded.
+//!
+//! A wrong trim or a typo caught after upload can be fixed from the command line
+//! instead of the Spotify for Podcasters dashboard:
+//!
+//! ```text
+//! audio_epistles edit <video_id> [--segment <name>] [--title <title>] [--description <text>]
+//! audio_epistles replace-audio <video_id> <audio_path> [--segment <name>]
+//! audio_epistles delete <video_id> [--segment <name>] [--force]
+//! ```
+//!
+//! The episode is looked up in the database by the video it was made from (and its
+//! segment when the video produced several episodes), then changed on Spotify
+//! through the browser automation in `webdriver.rs`. Every change, applied or
+//! failed, is recorded in the `episode_changes` audit table. Only drafts are deleted
+//! unless `--force` is given.
+
+use anyhow::{anyhow, bail, Context, Result};
+use sqlx::SqlitePool;
+use std::path::Path;
+use tracing::{error, info};
+
+use crate::db::{self, ChangeRecord, UploadRecord};
+use crate::diagnostics::FailureDiagnostics;
+use crate::query;
+use crate::webdriver::{self, SpotifyEpisode};
+
+/// Publisher whose episodes the commands change
+const TARGET: &str = "spotify";
+
+/// A change to an uploaded episode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Replace the title and/or description; `None` fields are left unchanged
+    Edit {
+        title: Option<String>,
+        description: Option<String>,
+    },
+    /// Replace the audio file with the one at `audio_path`
+    ReplaceAudio { audio_path: String },
+    /// Delete the episode
+    Delete,
+}
+
+impl Change {
+    /// Returns the action recorded in the audit table: `edit`, `replace_audio` or `delete`.
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::Edit { .. } => "edit",
+            Self::ReplaceAudio { .. } => "replace_audio",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// Returns the audit records of this change to `upload`, one per changed field
+    /// (a single record without a field for a deletion).
+    pub fn records(&self, upload: &UploadRecord) -> Vec<ChangeRecord> {
+        let record = |field: Option<&str>, old: Option<&str>, new: Option<&str>| ChangeRecord {
+            upload_id: upload.id,
+            target: TARGET.to_string(),
+            action: self.action().to_string(),
+            field: field.map(String::from),
+            old_value: old.map(String::from),
+            new_value: new.map(String::from),
+            ..Default::default()
+        };
+
+        match self {
+            Self::Edit { title, description } => {
+                let mut records = Vec::new();
+                if let Some(title) = title {
+                    records.push(record(Some("title"), upload.title.as_deref(), Some(title)));
+                }
+                if let Some(description) = description {
+                    records.push(record(
+                        Some("description"),
+                        upload.description.as_deref(),
+                        Some(description),
+                    ));
+                }
+                records
+            }
+            Self::ReplaceAudio { audio_path } => {
+                vec![record(Some("audio"), None, Some(audio_path))]
+            }
+            Self::Delete => vec![record(None, upload.title.as_deref(), None)],
+        }
+    }
+}
+
+/// A management command parsed from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    /// YouTube video ID the episode was made from
+    pub video_id: String,
+    /// Segment of the episode, required when the video produced several episodes
+    pub segment: Option<String>,
+    pub change: Change,
+    /// Delete the episode even if it is published or scheduled
+    pub force: bool,
+}
+
+impl Command {
+    /// Parses a management command from the program arguments (without the program name).
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the first argument is not a command (e.g. no arguments or
+    /// browser flags passed by the Docker image), in which case the regular pipeline
+    /// runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with usage if the command is unknown, an argument is missing
+    /// or unexpected, or `edit` is given nothing to change.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let args = ["delete", "dQw4w9WgXcQ"].map(String::from);
+    /// let command = audio_epistles::manage::Command::parse(&args).unwrap().unwrap();
+    /// assert_eq!(command.video_id, "dQw4w9WgXcQ");
+    /// ```
+    pub fn parse(args: &[String]) -> Result<Option<Self>> {
+        let Some(name) = args.first().filter(|arg| !arg.starts_with('-')) else {
+            return Ok(None);
+        };
+
+        let mut positional = Vec::new();
+        let mut segment = None;
+        let mut title = None;
+        let mut description = None;
+        let mut force = false;
+
+        let mut rest = args[1..].iter();
+        while let Some(arg) = rest.next() {
+            let mut value = |flag: &str| {
+                rest.next()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Missing value for {flag}\n\n{}", usage()))
+            };
+            match arg.as_str() {
+                "--segment" => segment = Some(value(arg)?),
+                "--title" if name == "edit" => title = Some(value(arg)?),
+                "--description" if name == "edit" => description = Some(value(arg)?),
+                "--force" if name == "delete" => force = true,
+                flag if flag.starts_with("--") => {
+                    bail!("Unknown option for {name}: {flag}\n\n{}", usage())
+                }
+                _ => positional.push(arg.clone()),
+            }
+        }
+
+        let change = match (name.as_str(), positional.len()) {
+            ("edit", 1) => {
+                if title.is_none() && description.is_none() {
+                    bail!("edit needs --title and/or --description\n\n{}", usage());
+                }
+                Change::Edit { title, description }
+            }
+            ("replace-audio", 2) => Change::ReplaceAudio {
+                audio_path: positional.pop().unwrap_or_default(),
+            },
+            ("delete", 1) => Change::Delete,
+            ("edit" | "replace-audio" | "delete", _) => {
+                bail!("Wrong number of arguments for {name}\n\n{}", usage())
+            }
+            _ => bail!("Unknown command: {name}\n\n{}", usage()),
+        };
+
+        Ok(Some(Self {
+            video_id: positional.swap_remove(0),
+            segment,
+            change,
+            force,
+        }))
+    }
+}
+
+/// Usage lines of the management commands
+const COMMANDS: &str =
+    "  audio_epistles edit <video_id> [--segment <name>] [--title <title>] [--description <text>]
+  audio_epistles replace-audio <video_id> <audio_path> [--segment <name>]
+  audio_epistles delete <video_id> [--segment <name>] [--force]";
+
+/// Returns the usage of the query and management commands, shown with argument errors.
+fn usage() -> String {
+    format!("{}\n{COMMANDS}", query::USAGE)
+}
+
+/// Picks the upload a command applies to among the uploads of its video.
+///
+/// # Errors
+///
+/// Returns an error if the video has no uploads, no upload of `segment`, or several
+/// uploads and no `segment` to choose between them.
+fn select_upload(
+    video_id: &str,
+    mut uploads: Vec<UploadRecord>,
+    segment: Option<&str>,
+) -> Result<UploadRecord> {
+    if uploads.is_empty() {
+        bail!("No uploads recorded for video {video_id}");
+    }
+
+    let segments = || {
+        uploads
+            .iter()
+            .map(|u| u.segment.as_deref().unwrap_or("sermon"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match segment {
+        Some(segment) => {
+            let available = segments();
+            uploads
+                .into_iter()
+                .find(|u| {
+                    u.segment
+                        .as_deref()
+                        .unwrap_or("sermon")
+                        .eq_ignore_ascii_case(segment)
+                })
+                .ok_or_else(|| {
+                    anyhow!("No {segment} upload for video {video_id} (segments: {available})")
+                })
+        }
+        None if uploads.len() > 1 => bail!(
+            "Video {video_id} has several uploads, choose one with --segment ({})",
+            segments()
+        ),
+        None => Ok(uploads.remove(0)),
+    }
+}
+
+/// Runs a management command: changes the episode on Spotify and records the change.
+///
+/// The change is recorded in the `episode_changes` audit table whether it was
+/// applied or failed (with the error and diagnostics folder). When it is applied,
+/// the title and description of the upload are updated, or its Spotify publication
+/// is marked `deleted`.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `command` - The command to run
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The upload cannot be found or was never published to Spotify (or was deleted)
+/// - The episode is not a draft and is being deleted without `--force`
+/// - The replacement audio file does not exist
+/// - The change fails on Spotify (after it was recorded)
+/// - Any database query fails
+pub async fn run(pool: &SqlitePool, command: &Command) -> Result<()> {
+    let uploads = db::get_uploads_by_video(pool, &command.video_id).await?;
+    let upload = select_upload(&command.video_id, uploads, command.segment.as_deref())?;
+
+    let publication = db::get_publications(pool, upload.id)
+        .await?
+        .into_iter()
+        .rfind(|p| p.target == TARGET && p.status.is_some())
+        .ok_or_else(|| {
+            anyhow!(
+                "Upload {} of video {} was never published to Spotify",
+                upload.id,
+                command.video_id
+            )
+        })?;
+    let status = publication.status.as_deref().unwrap_or_default();
+    if status == "deleted" {
+        bail!(
+            "Episode of video {} was deleted from Spotify",
+            command.video_id
+        );
+    }
+    if command.change == Change::Delete && status != "draft" && !command.force {
+        bail!(
+            "Episode of video {} is {status} on Spotify, pass --force to delete it",
+            command.video_id
+        );
+    }
+    if let Change::ReplaceAudio { audio_path } = &command.change {
+        if !Path::new(audio_path).is_file() {
+            bail!("Audio file not found: {audio_path}");
+        }
+    }
+
+    let title = upload
+        .title
+        .clone()
+        .with_context(|| format!("Upload {} has no recorded title", upload.id))?;
+    let episode = SpotifyEpisode {
+        title,
+        url: publication.url.clone(),
+    };
+    info!(video_id = %command.video_id, upload_id = upload.id, action = command.change.action(), "Changing Spotify episode");
+    let result = webdriver::change_episode(&episode, &command.change).await;
+
+    let mut records = command.change.records(&upload);
+    if let Err(e) = &result {
+        let diagnostics = e
+            .downcast_ref::<FailureDiagnostics>()
+            .map(|d| d.dir.display().to_string());
+        for record in &mut records {
+            record.error = Some(format!("{e:#}"));
+            record.diagnostics = diagnostics.clone();
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    if result.is_ok() {
+        match &command.change {
+            Change::Edit { title, description } => {
+                db::update_upload_details(
+                    &mut tx,
+                    upload.id,
+                    title.as_deref(),
+                    description.as_deref(),
+                )
+                .await?;
+            }
+            Change::ReplaceAudio { .. } => {}
+            Change::Delete => db::mark_publication_deleted(&mut tx, upload.id, TARGET).await?,
+        }
+    }
+    for record in &records {
+        db::save_change(&mut tx, record).await?;
+    }
+    tx.commit().await?;
+
+    match result {
+        Ok(()) => {
+            info!(video_id = %command.video_id, action = command.change.action(), "Spotify episode changed");
+            Ok(())
+        }
+        Err(e) => {
+            error!(video_id = %command.video_id, action = command.change.action(), error = %e, "Failed to change Spotify episode");
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn upload(id: i64, segment: Option<&str>) -> UploadRecord {
+        UploadRecord {
+            id,
+            video_id: "abc123".to_string(),
+            uploaded_at: "2026-10-18 12:00:00".to_string(),
+            title: Some("Faith".to_string()),
+            speaker: None,
+            series: None,
+            segment: segment.map(String::from),
+            status: Some("draft".to_string()),
+            publish_at: None,
+            guid: None,
+            description: Some("Join us online.".to_string()),
+            duration_secs: None,
+            artwork: None,
+            season: None,
+            episode_number: None,
+            episode_type: None,
+            explicit: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_without_command() {
+        assert_eq!(Command::parse(&[]).unwrap(), None);
+        // Browser flags passed by the Docker image
+        assert_eq!(
+            Command::parse(&args(&["--no-sandbox", "--disable-dev-shm-usage"])).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let edit = Command::parse(&args(&[
+            "edit",
+            "abc123",
+            "--title",
+            "Faith Works",
+            "--segment",
+            "testimony",
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(edit.video_id, "abc123");
+        assert_eq!(edit.segment.as_deref(), Some("testimony"));
+        assert_eq!(
+            edit.change,
+            Change::Edit {
+                title: Some("Faith Works".to_string()),
+                description: None
+            }
+        );
+
+        let replace = Command::parse(&args(&["replace-audio", "abc123", "fixed.mp3"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(replace.video_id, "abc123");
+        assert_eq!(
+            replace.change,
+            Change::ReplaceAudio {
+                audio_path: "fixed.mp3".to_string()
+            }
+        );
+
+        let delete = Command::parse(&args(&["delete", "abc123", "--force"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(delete.change, Change::Delete);
+        assert!(delete.force);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Command::parse(&args(&["edit", "abc123"])).is_err());
+        assert!(Command::parse(&args(&["edit", "abc123", "--title"])).is_err());
+        assert!(Command::parse(&args(&["replace-audio", "abc123"])).is_err());
+        assert!(Command::parse(&args(&["delete", "abc123", "--title", "x"])).is_err());
+        assert!(Command::parse(&args(&["delete"])).is_err());
+        assert!(Command::parse(&args(&["publish", "abc123"])).is_err());
+
+        // The usage lists the query commands as well as the management commands
+        let err = Command::parse(&args(&["delete"])).unwrap_err().to_string();
+        assert!(err.contains("audio_epistles delete <video_id>"));
+        assert!(err.contains("audio_epistles changes <video_id>"));
+    }
+
+    #[test]
+    fn test_select_upload() {
+        assert!(select_upload("abc123", Vec::new(), None).is_err());
+        assert_eq!(
+            select_upload("abc123", vec![upload(1, None)], None)
+                .unwrap()
+                .id,
+            1
+        );
+
+        let uploads = || vec![upload(1, Some("sermon")), upload(2, Some("testimony"))];
+        let err = select_upload("abc123", uploads(), None).unwrap_err();
+        assert!(err.to_string().contains("sermon, testimony"));
+        assert_eq!(
+            select_upload("abc123", uploads(), Some("Testimony"))
+                .unwrap()
+                .id,
+            2
+        );
+        assert!(select_upload("abc123", uploads(), Some("guest")).is_err());
+    }
+
+    #[test]
+    fn test_change_records() {
+        let upload = upload(7, None);
+        let edit = Change::Edit {
+            title: Some("Faith Works".to_string()),
+            description: Some("New description".to_string()),
+        };
+        let records = edit.records(&upload);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].upload_id, 7);
+        assert_eq!(records[0].target, "spotify");
+        assert_eq!(records[0].action, "edit");
+        assert_eq!(records[0].field.as_deref(), Some("title"));
+        assert_eq!(records[0].old_value.as_deref(), Some("Faith"));
+        assert_eq!(records[0].new_value.as_deref(), Some("Faith Works"));
+        assert_eq!(records[1].field.as_deref(), Some("description"));
+        assert_eq!(records[1].old_value.as_deref(), Some("Join us online."));
+
+        let replace = Change::ReplaceAudio {
+            audio_path: "fixed.mp3".to_string(),
+        };
+        let records = replace.records(&upload);
+        assert_eq!(records[0].action, "replace_audio");
+        assert_eq!(records[0].field.as_deref(), Some("audio"));
+        assert_eq!(records[0].new_value.as_deref(), Some("fixed.mp3"));
+
+        let records = Change::Delete.records(&upload);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].action, "delete");
+        assert_eq!(records[0].field, None);
+        assert_eq!(records[0].old_value.as_deref(), Some("Faith"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Local mock HTTP server for the tests of HTTP clients.
+//!
+//! Every request is recorded and answered with the response returned by the test's
+//! handler, so that tests can check the calls made to a podcast host, an object
+//! store, a WebDriver server or a webhook without the real service. The handler
+//! also gets the mock's base URL, to hand out upload URLs that point back to the mock.
+
+use reqwest::Url;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// A request received by the mock.
+#[derive(Debug, Clone, Default)]
+pub struct Recorded {
+    pub method: String,
+    /// Path and query string as sent, e.g. `/episodes/abc.mp3?uploads=`
+    pub target: String,
+    /// Path without the query string
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Recorded {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns a query parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        find(&self.query, name)
+    }
+
+    /// Returns a field of a form-encoded body.
+    pub fn field(&self, name: &str) -> Option<String> {
+        let url = Url::parse(&format!(
+            "http://mock/?{}",
+            String::from_utf8_lossy(&self.body)
+        ))
+        .ok()?;
+        let value = url
+            .query_pairs()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.to_string());
+        value
+    }
+
+    /// Returns the body parsed as JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+}
+
+fn find<'a>(pairs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// A response sent by the mock.
+#[derive(Debug, Clone, Default)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// A response with a raw body, e.g. XML or nothing.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// A response with a JSON body.
+    pub fn json(status: u16, body: &Value) -> Self {
+        Self::new(status, body.to_string()).header("Content-Type", "application/json")
+    }
+
+    /// Adds a header to the response.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {} Mock\r\n", self.status);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        ));
+        [head.into_bytes(), self.body.clone()].concat()
+    }
+}
+
+type Handler = dyn Fn(&Recorded, &str) -> Response + Send + Sync;
+
+/// A running mock server, stopped when the test ends.
+#[derive(Clone)]
+pub struct MockHttp {
+    /// Base URL of the mock, e.g. `http://127.0.0.1:41234`
+    pub url: String,
+    requests: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl MockHttp {
+    /// Starts the mock on a free local port; `handler` returns the status code and
+    /// JSON body of each response.
+    pub async fn start(
+        handler: impl Fn(&Recorded, &str) -> (u16, Value) + Send + Sync + 'static,
+    ) -> Self {
+        Self::serve(move |request, url| {
+            let (status, body) = handler(request, url);
+            Response::json(status, &body)
+        })
+        .await
+    }
+
+    /// Starts the mock on a free local port; `handler` returns each response.
+    pub async fn serve(
+        handler: impl Fn(&Recorded, &str) -> Response + Send + Sync + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let (server_url, server_requests) = (url.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (url, requests, handler) =
+                    (server_url.clone(), server_requests.clone(), handler.clone());
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(stream);
+                    let Some(request) = read_request(&mut reader).await else {
+                        return;
+                    };
+                    let response = handler(&request, &url);
+                    requests.lock().unwrap().push(request);
+                    let _ = reader.into_inner().write_all(&response.to_bytes()).await;
+                });
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Returns the requests received so far.
+    pub fn requests(&self) -> Vec<Recorded> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(reader: &mut BufReader<tokio::net::TcpStream>) -> Option<Recorded> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let raw_target = parts.next()?.to_string();
+    let target = Url::parse(&format!("http://mock{raw_target}")).ok()?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).await.ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let length = headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.ok()?;
+
+    Some(Recorded {
+        method,
+        target: raw_target,
+        path: target.path().to_string(),
+        query: target
+            .query_pairs()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect(),
+        headers,
+        body,
+    })
+}
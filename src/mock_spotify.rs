@@ -0,0 +1,1068 @@
+//! Local mock of the Spotify for Podcasters pages for end-to-end tests.
+//!
+//! An embedded HTTP server serves static replicas of the landing, login, episodes
+//! list, episode, edit and upload wizard pages, using the markup the selectors in
+//! `pages.rs` expect. Small scripts simulate the behaviour the automation relies
+//! on: the multi-step login, upload progress, the save-draft dialog, the review
+//! step, and the episode options menu. Saved episodes are kept in memory so that
+//! tests can check what the automation entered.
+//!
+//! Like Slate.js, the description editor inserts pasted HTML (see `editor.rs`).
+//!
+//! Failure scenarios are set with `MockOptions`: page elements can be left out,
+//! uploads can be slowed down or rejected, pasted descriptions ignored, and a
+//! captcha shown after the login until the test solves it.
+//!
+//! Point the automation at the mock with `SPOTIFY_BASE_URL` (see `Pages::from_env`).
+
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Email accepted by the mock login
+pub const MOCK_EMAIL: &str = "mock@example.org";
+
+/// Password accepted by the mock login
+pub const MOCK_PASSWORD: &str = "mock-password";
+
+/// Cookie set by the mock login
+const SESSION_COOKIE: &str = "mock_session=1";
+
+/// How the mock pages misbehave.
+#[derive(Debug, Clone, Default)]
+pub struct MockOptions {
+    /// Names of page elements left out of the pages, e.g. `wizard.save_draft_button`
+    pub missing: Vec<&'static str>,
+    /// How long the simulated audio upload takes, in seconds
+    pub upload_secs: u32,
+    /// Show an upload error instead of uploading the audio
+    pub reject_upload: bool,
+    /// Ignore text pasted into the description editor, so it has to be typed
+    pub ignore_paste: bool,
+    /// Send every logged-in page to a captcha until `MockSpotify::solve_captcha`
+    pub captcha: bool,
+}
+
+/// An episode saved through the mock pages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockEpisode {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// `draft`, `published` or `scheduled`
+    pub status: String,
+    /// Date and time entered for a scheduled episode, e.g. "10/25/2026 06:00 PM"
+    pub publish_at: Option<String>,
+    pub season: Option<u32>,
+    pub episode_number: Option<u32>,
+    pub episode_type: String,
+    pub explicit: bool,
+    /// File name of the uploaded audio
+    pub audio: Option<String>,
+    /// File name of the uploaded cover art
+    pub artwork: Option<String>,
+}
+
+impl MockEpisode {
+    /// Returns the status as shown on the episodes list.
+    fn label(&self) -> String {
+        match (self.status.as_str(), &self.publish_at) {
+            ("published", _) => "Published".to_string(),
+            ("scheduled", Some(at)) => format!("Scheduled for {at}"),
+            ("scheduled", None) => "Scheduled".to_string(),
+            _ => "Draft".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    options: MockOptions,
+    episodes: Vec<MockEpisode>,
+    next_id: u32,
+    captcha_solved: bool,
+}
+
+impl State {
+    /// Returns `html` unless the element `name` is left out of the pages.
+    fn el(&self, name: &str, html: &str) -> String {
+        if self.options.missing.contains(&name) {
+            String::new()
+        } else {
+            html.to_string()
+        }
+    }
+
+    fn add(&mut self, mut episode: MockEpisode) -> String {
+        self.next_id += 1;
+        episode.id = format!("mock{}", self.next_id);
+        let id = episode.id.clone();
+        self.episodes.push(episode);
+        id
+    }
+}
+
+/// A running mock of the Spotify for Podcasters pages, stopped when the test ends.
+#[derive(Debug, Clone)]
+pub struct MockSpotify {
+    /// Base URL of the mock, e.g. `http://127.0.0.1:41234`
+    pub url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockSpotify {
+    /// Starts the mock on a free local port.
+    pub async fn start(options: MockOptions) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State {
+            options,
+            ..Default::default()
+        }));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle(stream, server_state.clone()));
+            }
+        });
+
+        Self { url, state }
+    }
+
+    /// Returns the episodes saved so far.
+    pub fn episodes(&self) -> Vec<MockEpisode> {
+        self.state.lock().unwrap().episodes.clone()
+    }
+
+    /// Solves the captcha, as an operator would; the captcha page then moves on to
+    /// the episodes list.
+    pub fn solve_captcha(&self) {
+        self.state.lock().unwrap().captcha_solved = true;
+    }
+
+    /// Adds an episode as if it had been uploaded before, returning its ID.
+    pub fn add_episode(&self, title: &str, status: &str) -> String {
+        self.state.lock().unwrap().add(MockEpisode {
+            title: title.to_string(),
+            status: status.to_string(),
+            episode_type: "full".to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+/// An HTTP request received by the mock.
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    /// Path without the query string
+    path: String,
+    cookie: String,
+    body: Vec<u8>,
+}
+
+/// An HTTP response sent by the mock.
+#[derive(Debug)]
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl Response {
+    fn html(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn json(status: &'static str, body: Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    fn redirect(location: &str) -> Self {
+        Self {
+            status: "302 Found",
+            content_type: "text/plain",
+            headers: vec![("Location", location.to_string())],
+            body: String::new(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::json("404 Not Found", json!({"error": "not found"}))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.content_type,
+            self.body.len()
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        [head.into_bytes(), self.body.clone().into_bytes()].concat()
+    }
+}
+
+async fn handle(mut stream: TcpStream, state: Arc<Mutex<State>>) {
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+    let response = route(&request, &mut state.lock().unwrap());
+    let _ = stream.write_all(&response.to_bytes()).await;
+}
+
+/// Reads the request line, the headers the mock needs and the body.
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 8192];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let mut request = Request {
+        method: request_line.next()?.to_string(),
+        path: request_line.next()?.split('?').next()?.to_string(),
+        ..Default::default()
+    };
+    let mut content_length = 0;
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().ok()?,
+            "cookie" => request.cookie = value.trim().to_string(),
+            _ => {}
+        }
+    }
+
+    request.body = buffer[header_end + 4..].to_vec();
+    while request.body.len() < content_length {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        request.body.extend_from_slice(&chunk[..read]);
+    }
+
+    Some(request)
+}
+
+fn route(request: &Request, state: &mut State) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let method = request.method.as_str();
+
+    match (method, segments.as_slice()) {
+        ("GET", [""]) => return Response::html(landing_page()),
+        ("GET", ["pod", "login"]) => return Response::html(continue_page(state)),
+        ("GET", ["accounts", "login"]) => return Response::html(login_page(state)),
+        ("POST", ["api", "login"]) => return login(request),
+        ("GET", ["challenge"]) => return Response::html(challenge_page()),
+        ("GET", ["api", "challenge"]) => {
+            return Response::json("200 OK", json!({ "solved": state.captcha_solved }))
+        }
+        _ => {}
+    }
+
+    if !request.cookie.contains(SESSION_COOKIE) {
+        return match method {
+            "GET" => Response::redirect("/pod/login"),
+            _ => Response::json("401 Unauthorized", json!({"error": "not logged in"})),
+        };
+    }
+
+    if method == "GET" && state.options.captcha && !state.captcha_solved {
+        return Response::redirect("/challenge");
+    }
+
+    match (method, segments.as_slice()) {
+        ("GET", ["pod", "dashboard", "episodes"]) => Response::html(episodes_page(state)),
+        ("GET", ["pod", "dashboard", "episode", "wizard"]) => Response::html(wizard_page(state)),
+        ("GET", ["pod", "show", "mock", "episode", id]) => match find(state, id) {
+            Some(episode) => Response::html(episode_page(state, episode)),
+            None => Response::not_found(),
+        },
+        ("GET", ["pod", "show", "mock", "episode", id, "edit"]) => match find(state, id) {
+            Some(episode) => Response::html(edit_page(state, episode)),
+            None => Response::not_found(),
+        },
+        ("POST", ["api", "episodes"]) => {
+            let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+            let id = state.add(MockEpisode {
+                status: text(&body, "status").unwrap_or_else(|| "draft".to_string()),
+                ..episode_from(&body)
+            });
+            Response::json("201 Created", json!({ "id": id }))
+        }
+        ("POST", ["api", "episodes", id]) => {
+            let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+            let Some(episode) = state.episodes.iter_mut().find(|e| e.id == *id) else {
+                return Response::not_found();
+            };
+            if let Some(title) = text(&body, "title") {
+                episode.title = title;
+            }
+            if let Some(description) = text(&body, "description") {
+                episode.description = description;
+            }
+            if let Some(audio) = text(&body, "audio") {
+                episode.audio = Some(audio);
+            }
+            Response::json("200 OK", json!({ "id": id }))
+        }
+        ("DELETE", ["api", "episodes", id]) => {
+            let before = state.episodes.len();
+            state.episodes.retain(|e| e.id != *id);
+            if state.episodes.len() == before {
+                return Response::not_found();
+            }
+            Response::json("200 OK", json!({ "id": id }))
+        }
+        _ => Response::not_found(),
+    }
+}
+
+fn find(state: &State, id: &str) -> Option<MockEpisode> {
+    state.episodes.iter().find(|e| e.id == id).cloned()
+}
+
+/// Returns a non-empty, trimmed string field of a JSON body.
+fn text(body: &Value, field: &str) -> Option<String> {
+    body.get(field)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+}
+
+/// Builds an episode from the fields posted by the wizard.
+fn episode_from(body: &Value) -> MockEpisode {
+    let date_time = match (text(body, "publish_date"), text(body, "publish_time")) {
+        (Some(date), Some(time)) => Some(format!("{date} {time}")),
+        _ => None,
+    };
+    MockEpisode {
+        title: text(body, "title").unwrap_or_default(),
+        description: text(body, "description").unwrap_or_default(),
+        publish_at: date_time.filter(|_| text(body, "status").as_deref() == Some("scheduled")),
+        season: text(body, "season").and_then(|v| v.parse().ok()),
+        episode_number: text(body, "episode_number").and_then(|v| v.parse().ok()),
+        episode_type: text(body, "episode_type").unwrap_or_else(|| "full".to_string()),
+        explicit: body["explicit"].as_bool().unwrap_or(false),
+        audio: text(body, "audio"),
+        artwork: text(body, "artwork"),
+        ..Default::default()
+    }
+}
+
+fn login(request: &Request) -> Response {
+    let body: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+    if text(&body, "email").as_deref() != Some(MOCK_EMAIL)
+        || body["password"].as_str() != Some(MOCK_PASSWORD)
+    {
+        return Response::json("401 Unauthorized", json!({"error": "Incorrect password"}));
+    }
+    let mut response = Response::json("200 OK", json!({"ok": true}));
+    response
+        .headers
+        .push(("Set-Cookie", format!("{SESSION_COOKIE}; Path=/")));
+    response
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Quotes HTML as a JavaScript string literal.
+fn js(html: &str) -> String {
+    Value::from(html).to_string()
+}
+
+fn page(title: &str, body: &str, script: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+         <body>\n{body}\n<script>\n{COMMON_SCRIPT}\n{script}\n</script>\n</body></html>"
+    )
+}
+
+/// Helpers shared by the page scripts
+const COMMON_SCRIPT: &str = r#"
+function value(id) { const e = document.getElementById(id); return e ? e.value : null; }
+function checked(name) {
+  const e = document.querySelector('input[name="' + name + '"]:checked');
+  return e ? e.value : null;
+}
+function fileName(id) {
+  const e = document.getElementById(id);
+  return e && e.files && e.files.length ? e.files[0].name : null;
+}
+function send(method, url, body) {
+  return fetch(url, {
+    method,
+    headers: { 'Content-Type': 'application/json' },
+    body: body ? JSON.stringify(body) : undefined,
+  });
+}
+function on(id, event, handler) {
+  const e = document.getElementById(id);
+  if (e) e.addEventListener(event, handler);
+}
+on('description', 'paste', (event) => {
+  if (event.currentTarget.dataset.ignorePaste) return;
+  event.preventDefault();
+  const html = event.clipboardData.getData('text/html');
+  const text = event.clipboardData.getData('text/plain');
+  const range = window.getSelection().getRangeAt(0);
+  range.deleteContents();
+  range.insertNode(html ? range.createContextualFragment(html) : document.createTextNode(text));
+});
+"#;
+
+fn landing_page() -> String {
+    page(
+        "Spotify for Podcasters",
+        "<h1>Spotify for Podcasters</h1>\n<a href=\"/pod/login\">Log in</a>",
+        "",
+    )
+}
+
+fn continue_page(state: &State) -> String {
+    let button = state.el(
+        "login.continue_with_spotify",
+        "<button id=\"continue\"><span>Continue with Spotify</span></button>",
+    );
+    page(
+        "Log in",
+        &button,
+        "on('continue', 'click', () => { location.href = '/accounts/login'; });",
+    )
+}
+
+/// Captcha shown instead of the logged-in pages, polling for the test to solve it
+fn challenge_page() -> String {
+    page(
+        "Spotify",
+        "<h1>Verify you are human</h1>\n\
+         <iframe title=\"reCAPTCHA\" src=\"about:blank#recaptcha\"></iframe>",
+        r#"
+setInterval(async () => {
+  const response = await fetch('/api/challenge');
+  const { solved } = await response.json();
+  if (solved) location.href = '/pod/dashboard/episodes';
+}, 500);
+"#,
+    )
+}
+
+fn login_page(state: &State) -> String {
+    let email_step = format!(
+        "{}\n{}",
+        state.el(
+            "login.email_input",
+            "<input id=\"login-username\" name=\"username\">"
+        ),
+        state.el(
+            "login.continue_button",
+            "<button id=\"login-button\" data-step=\"email\">Continue</button>"
+        ),
+    );
+    let password_option = state.el(
+        "login.password_option",
+        "<button id=\"password-option\" data-encore-id=\"buttonTertiary\" \
+         class=\"Button-sc-1dqy6lx-0\"><span>Log in with a password</span></button>",
+    );
+    let password_step = format!(
+        "{}\n{}\n<p id=\"login-error\"></p>",
+        state.el(
+            "login.password_input",
+            "<input type=\"password\" id=\"login-password\" data-testid=\"login-password\">"
+        ),
+        state.el(
+            "login.submit_button",
+            "<button id=\"login-button\" data-step=\"password\"><span>Log In</span></button>"
+        ),
+    );
+    let script = format!(
+        r#"
+let email = '';
+document.addEventListener('click', async (event) => {{
+  const button = event.target.closest('button');
+  if (!button) return;
+  const step = document.getElementById('step');
+  if (button.dataset.step === 'email') {{
+    email = value('login-username');
+    step.innerHTML = {password_option};
+  }} else if (button.id === 'password-option') {{
+    step.innerHTML = {password_step};
+  }} else if (button.dataset.step === 'password') {{
+    const response = await send('POST', '/api/login', {{ email, password: value('login-password') }});
+    if (response.ok) {{
+      location.href = '/pod/dashboard/episodes';
+    }} else {{
+      document.getElementById('login-error').textContent = 'Incorrect username or password.';
+    }}
+  }}
+}});
+"#,
+        password_option = js(&password_option),
+        password_step = js(&password_step),
+    );
+    page(
+        "Log in to Spotify",
+        &format!("<div id=\"step\">{email_step}</div>"),
+        &script,
+    )
+}
+
+fn episodes_page(state: &State) -> String {
+    let rows: String = state
+        .episodes
+        .iter()
+        .map(|e| {
+            format!(
+                "<tr><td><a href=\"/pod/show/mock/episode/{}\">{}</a></td><td>{}</td></tr>\n",
+                e.id,
+                escape(&e.title),
+                escape(&e.label())
+            )
+        })
+        .collect();
+    page(
+        "Episodes",
+        &format!("<h1>Episodes</h1>\n<table>\n{rows}</table>"),
+        "",
+    )
+}
+
+/// Script simulating the upload of the audio file chosen in `#audio-file`
+fn upload_script(state: &State) -> String {
+    format!(
+        r#"
+const UPLOAD_SECS = {upload_secs};
+const REJECT_UPLOAD = {reject};
+on('audio-file', 'change', () => {{
+  const status = document.getElementById('upload-status');
+  const title = document.getElementById('title-input');
+  if (title) title.disabled = true;
+  if (REJECT_UPLOAD) {{
+    status.innerHTML = {error};
+    return;
+  }}
+  status.innerHTML = {progress};
+  const bar = status.querySelector('[role=progressbar]');
+  const started = Date.now();
+  const timer = setInterval(() => {{
+    const progress = UPLOAD_SECS > 0
+      ? Math.min(100, Math.floor((Date.now() - started) / (UPLOAD_SECS * 10)))
+      : 100;
+    if (bar) {{
+      // Like many progress bars, report a decimal value ("42.0")
+      bar.setAttribute('aria-valuenow', progress.toFixed(1));
+      bar.textContent = 'Uploading ' + progress + '%';
+    }}
+    if (progress >= 100) {{
+      clearInterval(timer);
+      status.innerHTML = '';
+      if (title) title.disabled = false;
+    }}
+  }}, 100);
+}});
+"#,
+        upload_secs = state.options.upload_secs,
+        reject = state.options.reject_upload,
+        error = js(&state.el(
+            "wizard.upload_error",
+            "<div data-testid=\"upload-error\">Upload failed: this file type is not supported</div>"
+        )),
+        progress = js(&state.el(
+            "wizard.upload_progress",
+            "<div role=\"progressbar\" aria-valuenow=\"0.0\">Uploading 0%</div>"
+        )),
+    )
+}
+
+/// Title and description fields of the wizard and edit forms
+fn title_and_description(state: &State, episode: &MockEpisode, disabled: bool) -> String {
+    format!(
+        "{}\n{}",
+        state.el(
+            "wizard.title_input",
+            &format!(
+                "<input id=\"title-input\" name=\"title\" value=\"{}\"{}>",
+                escape(&episode.title),
+                if disabled { " disabled" } else { "" }
+            )
+        ),
+        state.el(
+            "wizard.description_editor",
+            &format!(
+                "<div id=\"description\" role=\"textbox\" data-slate-editor=\"true\" \
+                 contenteditable=\"true\"{}>{}</div>",
+                if state.options.ignore_paste {
+                    " data-ignore-paste=\"true\""
+                } else {
+                    ""
+                },
+                escape(&episode.description)
+            )
+        ),
+    )
+}
+
+fn wizard_page(state: &State) -> String {
+    let radio = |name: &str, field: &str, value: &str, label: &str, checked: bool| {
+        state.el(
+            name,
+            &format!(
+                "<label><input type=\"radio\" name=\"{field}\" value=\"{value}\"{}>{label}</label>",
+                if checked { " checked" } else { "" }
+            ),
+        )
+    };
+    let episode_types: String = [("full", "Full"), ("trailer", "Trailer"), ("bonus", "Bonus")]
+        .iter()
+        .map(|(value, label)| {
+            radio(
+                "wizard.episode_type_option",
+                "podcastEpisodeType",
+                value,
+                label,
+                *value == "full",
+            )
+        })
+        .collect();
+
+    let body = format!(
+        r#"<h1>Upload episode</h1>
+{select_file}
+{file_input}
+<div id="upload-status"></div>
+<form id="details-form">
+{title_and_description}
+{artwork_input}
+{season_input}
+{episode_number_input}
+<fieldset>{episode_types}</fieldset>
+<fieldset>{explicit_yes}{explicit_no}</fieldset>
+</form>
+{next_button}
+{close_button}
+<div id="dialog"></div>
+<div id="review"></div>"#,
+        select_file = state.el(
+            "wizard.select_file_button",
+            "<button id=\"select-file\" type=\"button\"><span>Select a file</span></button>"
+        ),
+        file_input = state.el(
+            "wizard.file_input",
+            "<input type=\"file\" id=\"audio-file\" accept=\"audio/*\">"
+        ),
+        title_and_description = title_and_description(state, &MockEpisode::default(), true),
+        artwork_input = state.el(
+            "wizard.artwork_input",
+            "<input type=\"file\" id=\"artwork-file\" accept=\"image/*\">"
+        ),
+        season_input = state.el(
+            "wizard.season_input",
+            "<input id=\"season-number\" name=\"podcastSeasonNumber\">"
+        ),
+        episode_number_input = state.el(
+            "wizard.episode_number_input",
+            "<input id=\"episode-number\" name=\"podcastEpisodeNumber\">"
+        ),
+        explicit_yes = radio(
+            "wizard.explicit_yes",
+            "podcastEpisodeIsExplicit",
+            "true",
+            "Yes",
+            false
+        ),
+        explicit_no = radio(
+            "wizard.explicit_no",
+            "podcastEpisodeIsExplicit",
+            "false",
+            "No",
+            true
+        ),
+        next_button = state.el(
+            "wizard.next_button",
+            "<button form=\"details-form\" type=\"submit\">Next</button>"
+        ),
+        close_button = state.el(
+            "wizard.close_button",
+            "<button id=\"close-button\" type=\"button\" aria-label=\"Close\" \
+             data-encore-id=\"buttonTertiary\">Close</button>"
+        ),
+    );
+
+    let save_dialog = format!(
+        "<div role=\"dialog\">{}</div>",
+        state.el(
+            "wizard.save_draft_button",
+            "<button id=\"save-draft\"><span>Save draft</span></button>"
+        )
+    );
+    let review = format!(
+        r#"<form id="review-form">
+{now}
+{schedule}
+{date}
+{time}
+</form>
+{submit}"#,
+        now = state.el(
+            "review.publish_now_option",
+            "<label><input type=\"radio\" name=\"publish-date\" id=\"publish-date-now\" \
+             value=\"now\" checked>Now</label>"
+        ),
+        schedule = state.el(
+            "review.schedule_option",
+            "<label><input type=\"radio\" name=\"publish-date\" id=\"publish-date-schedule\" \
+             value=\"schedule\">Schedule</label>"
+        ),
+        date = state.el("review.date_input", "<input id=\"date-picker-input\">"),
+        time = state.el("review.time_input", "<input id=\"time-picker-input\">"),
+        submit = state.el(
+            "review.submit_button",
+            "<button form=\"review-form\" type=\"submit\">Publish</button>"
+        ),
+    );
+
+    let script = format!(
+        r#"{upload}
+function details() {{
+  const description = document.getElementById('description');
+  return {{
+    title: value('title-input'),
+    description: description ? description.innerText : '',
+    season: value('season-number'),
+    episode_number: value('episode-number'),
+    episode_type: checked('podcastEpisodeType'),
+    explicit: checked('podcastEpisodeIsExplicit') === 'true',
+    audio: fileName('audio-file'),
+    artwork: fileName('artwork-file'),
+  }};
+}}
+on('close-button', 'click', () => {{
+  document.getElementById('dialog').innerHTML = {save_dialog};
+  on('save-draft', 'click', async () => {{
+    await send('POST', '/api/episodes', Object.assign(details(), {{ status: 'draft' }}));
+    document.getElementById('dialog').innerHTML = '<p>Draft saved</p>';
+  }});
+}});
+on('details-form', 'submit', (event) => {{
+  event.preventDefault();
+  document.getElementById('review').innerHTML = {review};
+  on('review-form', 'submit', async (event) => {{
+    event.preventDefault();
+    const scheduled = checked('publish-date') === 'schedule';
+    await send('POST', '/api/episodes', Object.assign(details(), {{
+      status: scheduled ? 'scheduled' : 'published',
+      publish_date: value('date-picker-input'),
+      publish_time: value('time-picker-input'),
+    }}));
+    document.getElementById('review').innerHTML = '<p>Episode submitted</p>';
+  }});
+}});
+"#,
+        upload = upload_script(state),
+        save_dialog = js(&save_dialog),
+        review = js(&review),
+    );
+
+    page("Upload episode", &body, &script)
+}
+
+fn episode_page(state: &State, episode: MockEpisode) -> String {
+    let body = format!(
+        "<h1>{title}</h1>\n<p>{label}</p>\n{edit}\n{more}\n<div id=\"menu\"></div>\n<div id=\"dialog\"></div>",
+        title = escape(&episode.title),
+        label = escape(&episode.label()),
+        edit = state.el(
+            "episode.edit_button",
+            &format!(
+                "<a data-testid=\"edit-episode-button\" href=\"/pod/show/mock/episode/{}/edit\">Edit episode</a>",
+                episode.id
+            )
+        ),
+        more = state.el(
+            "episode.more_button",
+            "<button id=\"more-options\" data-testid=\"episode-options-button\" \
+             aria-label=\"More options\">...</button>"
+        ),
+    );
+    let menu = state.el(
+        "episode.delete_option",
+        "<div role=\"menu\"><div role=\"menuitem\" id=\"delete-option\">Delete episode</div></div>",
+    );
+    let confirm = format!(
+        "<div role=\"dialog\">{}</div>",
+        state.el(
+            "episode.confirm_delete_button",
+            "<button id=\"confirm-delete\" data-testid=\"confirm-delete-button\">Delete</button>"
+        )
+    );
+    let script = format!(
+        r#"
+on('more-options', 'click', () => {{
+  document.getElementById('menu').innerHTML = {menu};
+  on('delete-option', 'click', () => {{
+    document.getElementById('dialog').innerHTML = {confirm};
+    on('confirm-delete', 'click', async () => {{
+      await send('DELETE', '/api/episodes/{id}');
+      location.href = '/pod/dashboard/episodes';
+    }});
+  }});
+}});
+"#,
+        menu = js(&menu),
+        confirm = js(&confirm),
+        id = episode.id,
+    );
+
+    page(&escape(&episode.title), &body, &script)
+}
+
+fn edit_page(state: &State, episode: MockEpisode) -> String {
+    let body = format!(
+        r#"<h1>Edit episode</h1>
+<div id="upload-status"></div>
+{replace}
+{audio_input}
+<form id="details-form">
+{title_and_description}
+</form>
+{save}"#,
+        replace = state.el(
+            "edit.replace_audio_button",
+            "<button type=\"button\" data-testid=\"replace-audio-button\">Replace file</button>"
+        ),
+        audio_input = state.el(
+            "edit.audio_input",
+            "<input type=\"file\" id=\"audio-file\" accept=\"audio/*\">"
+        ),
+        title_and_description = title_and_description(state, &episode, false),
+        save = state.el(
+            "edit.save_button",
+            "<button form=\"details-form\" type=\"submit\">Save</button>"
+        ),
+    );
+    let script = format!(
+        r#"{upload}
+on('details-form', 'submit', async (event) => {{
+  event.preventDefault();
+  const description = document.getElementById('description');
+  await send('POST', '/api/episodes/{id}', {{
+    title: value('title-input'),
+    description: description ? description.innerText : null,
+    audio: fileName('audio-file'),
+  }});
+  location.href = '/pod/show/mock/episode/{id}';
+}});
+"#,
+        upload = upload_script(state),
+        id = episode.id,
+    );
+
+    page("Edit episode", &body, &script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::redirect::Policy;
+    use reqwest::StatusCode;
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .redirect(Policy::none())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mock_requires_login() {
+        let mock = MockSpotify::start(MockOptions::default()).await;
+        let http = client();
+
+        let response = http
+            .get(format!("{}/pod/dashboard/episodes", mock.url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()["location"], "/pod/login");
+
+        let response = http
+            .post(format!("{}/api/login", mock.url))
+            .json(&json!({"email": MOCK_EMAIL, "password": "wrong"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = http
+            .post(format!("{}/api/login", mock.url))
+            .json(&json!({"email": MOCK_EMAIL, "password": MOCK_PASSWORD}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["set-cookie"], "mock_session=1; Path=/");
+    }
+
+    #[tokio::test]
+    async fn test_mock_captcha() {
+        let mock = MockSpotify::start(MockOptions {
+            captcha: true,
+            ..Default::default()
+        })
+        .await;
+        let http = client();
+        let get = |path: &str| {
+            http.get(format!("{}{path}", mock.url))
+                .header("Cookie", SESSION_COOKIE)
+                .send()
+        };
+
+        let response = get("/pod/dashboard/episodes").await.unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()["location"], "/challenge");
+        let challenge = get("/challenge").await.unwrap().text().await.unwrap();
+        assert!(challenge.contains("Verify you are human"));
+        let solved: Value = get("/api/challenge").await.unwrap().json().await.unwrap();
+        assert_eq!(solved, json!({"solved": false}));
+
+        mock.solve_captcha();
+        let response = get("/pod/dashboard/episodes").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let solved: Value = get("/api/challenge").await.unwrap().json().await.unwrap();
+        assert_eq!(solved, json!({"solved": true}));
+    }
+
+    #[tokio::test]
+    async fn test_mock_saves_episodes() {
+        let mock = MockSpotify::start(MockOptions::default()).await;
+        let http = client();
+
+        let response = http
+            .post(format!("{}/api/episodes", mock.url))
+            .header("Cookie", SESSION_COOKIE)
+            .json(&json!({
+                "title": "Faith & Works",
+                "description": "Join us online.\n",
+                "status": "scheduled",
+                "publish_date": "10/25/2026",
+                "publish_time": "06:00 PM",
+                "season": "2",
+                "episode_number": "",
+                "episode_type": "bonus",
+                "explicit": true,
+                "audio": "audio.mp3",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let episodes = mock.episodes();
+        assert_eq!(
+            episodes,
+            [MockEpisode {
+                id: "mock1".to_string(),
+                title: "Faith & Works".to_string(),
+                description: "Join us online.".to_string(),
+                status: "scheduled".to_string(),
+                publish_at: Some("10/25/2026 06:00 PM".to_string()),
+                season: Some(2),
+                episode_number: None,
+                episode_type: "bonus".to_string(),
+                explicit: true,
+                audio: Some("audio.mp3".to_string()),
+                artwork: None,
+            }]
+        );
+
+        let list = http
+            .get(format!("{}/pod/dashboard/episodes", mock.url))
+            .header("Cookie", SESSION_COOKIE)
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(list.contains(
+            "<a href=\"/pod/show/mock/episode/mock1\">Faith &amp; Works</a></td>\
+             <td>Scheduled for 10/25/2026 06:00 PM</td>"
+        ));
+
+        http.post(format!("{}/api/episodes/mock1", mock.url))
+            .header("Cookie", SESSION_COOKIE)
+            .json(&json!({"title": "Faith Works", "description": null, "audio": null}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(mock.episodes()[0].title, "Faith Works");
+        assert_eq!(mock.episodes()[0].description, "Join us online.");
+
+        let response = http
+            .delete(format!("{}/api/episodes/mock1", mock.url))
+            .header("Cookie", SESSION_COOKIE)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(mock.episodes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_missing_elements() {
+        let mock = MockSpotify::start(MockOptions {
+            missing: vec!["wizard.next_button", "wizard.save_draft_button"],
+            ..Default::default()
+        })
+        .await;
+        let wizard = client()
+            .get(format!("{}/pod/dashboard/episode/wizard", mock.url))
+            .header("Cookie", SESSION_COOKIE)
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(wizard.contains("id=\"title-input\""));
+        assert!(wizard.contains("aria-label=\"Close\""));
+        assert!(!wizard.contains("form=\"details-form\" type=\"submit\""));
+        assert!(!wizard.contains("Save draft"));
+    }
+}
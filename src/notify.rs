@@ -0,0 +1,125 @@
+//! Operator notification module.
+//!
+//! Events that need a person, such as a captcha shown by Spotify (see
+//! `challenge.rs`), are logged and, when `NOTIFY_WEBHOOK_URL` is set, posted to a
+//! webhook as JSON:
+//!
+//! ```json
+//! {"text": "Spotify shows a captcha at https://...", "event": "challenge"}
+//! ```
+//!
+//! The `text` field is what Slack, Mattermost and Rocket.Chat incoming webhooks
+//! display. Sending is best effort: a notification that cannot be delivered is
+//! logged and never fails the run.
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How long delivering one notification may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends operator notifications.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    /// Webhook the notifications are posted to, if any
+    pub webhook_url: Option<String>,
+    client: Client,
+}
+
+impl Notifier {
+    /// Creates a notifier posting to `webhook_url`, or only logging without one.
+    pub fn new(webhook_url: Option<String>) -> Self {
+        Self {
+            webhook_url,
+            client: Client::new(),
+        }
+    }
+
+    /// Reads the webhook from `NOTIFY_WEBHOOK_URL`.
+    pub fn from_env() -> Self {
+        Self::new(
+            env::var("NOTIFY_WEBHOOK_URL")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+        )
+    }
+
+    /// Logs `message` and posts it to the webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Kind of event, e.g. `challenge`, sent as the `event` field
+    /// * `message` - Text for the operator
+    pub async fn send(&self, event: &str, message: &str) {
+        warn!(event = %event, "{message}");
+        let Some(url) = &self.webhook_url else {
+            return;
+        };
+        match self.post(url, event, message).await {
+            Ok(()) => info!(event = %event, "Notification sent"),
+            Err(e) => warn!(event = %event, error = %e, "Failed to send notification"),
+        }
+    }
+
+    async fn post(&self, url: &str, event: &str, message: &str) -> Result<()> {
+        let response = self
+            .client
+            .post(url)
+            .timeout(SEND_TIMEOUT)
+            .json(&json!({ "text": message, "event": event }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Webhook returned {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::MockHttp;
+
+    /// Starts a webhook that answers every request with `status`.
+    async fn webhook(status: u16) -> (String, MockHttp) {
+        let mock = MockHttp::start(move |_, _| (status, json!({}))).await;
+        (format!("{}/hook", mock.url), mock)
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_to_webhook() {
+        let (url, mock) = webhook(200).await;
+        Notifier::new(Some(url))
+            .send("challenge", "Spotify shows a captcha")
+            .await;
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/hook");
+        assert_eq!(
+            requests[0].json(),
+            json!({"text": "Spotify shows a captcha", "event": "challenge"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_not_an_error() {
+        let (url, mock) = webhook(500).await;
+        let notifier = Notifier::new(Some(url.clone()));
+        assert!(notifier.post(&url, "challenge", "text").await.is_err());
+        assert_eq!(mock.requests().len(), 1);
+
+        // Without a webhook, or with one that is down, sending only logs
+        Notifier::new(None).send("challenge", "text").await;
+        Notifier::new(Some("http://127.0.0.1:1/hook".to_string()))
+            .send("challenge", "text")
+            .await;
+    }
+}
@@ -0,0 +1,826 @@
+//! Page-object module for Spotify for Podcasters.
+//!
+//! Every element the browser automation interacts with is named (e.g.
+//! `login.password_input`) and has one or more selectors, tried in order, so that a
+//! single class name change in a new Spotify build does not break the upload. The
+//! built-in selectors are versioned with `SELECTORS_VERSION`.
+//!
+//! Selectors can be updated without recompiling through an override file
+//! (`SPOTIFY_SELECTORS_FILE`), whose selectors are tried before the built-in ones:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "elements": {
+//!     "login.password_option": ["xpath://button[.//span[text()='Log in with a password']]"]
+//!   }
+//! }
+//! ```
+//!
+//! Selectors are prefixed with `css:` or `xpath:`; unprefixed selectors starting with
+//! `/` or `(` are XPath, anything else CSS. In diagnostic mode
+//! (`SPOTIFY_SELECTOR_DIAGNOSTICS`) every selector of an element is checked and a
+//! report of the selectors that no longer match is logged after the upload.
+//!
+//! The pages also pause between steps the way a person would (see `delay.rs`).
+
+use anyhow::{anyhow, Context, Result};
+use fantoccini::elements::Element;
+use fantoccini::{Client, Locator};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::delay::{Delays, Pause};
+
+/// Address of Spotify for Podcasters, used when `SPOTIFY_BASE_URL` is not set
+pub const DEFAULT_BASE_URL: &str = "https://podcasters.spotify.com";
+
+/// Version of the built-in selectors, bumped whenever Spotify's page layout changes
+pub const SELECTORS_VERSION: u32 = 1;
+
+/// How long `wait_for` polls for an element before giving up
+const WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between two polls of `wait_for`
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Built-in selectors of every named element, grouped by page.
+const ELEMENTS: &[(&str, &[&str])] = &[
+    // Landing and login pages
+    ("login.link", &["css:a[href='/pod/login']"]),
+    (
+        "login.continue_with_spotify",
+        &[
+            "xpath://span[text()='Continue with Spotify']/ancestor::button",
+            "xpath://button[contains(normalize-space(.), 'Continue with Spotify')]",
+        ],
+    ),
+    (
+        "login.email_input",
+        &["css:input#login-username", "css:input[name='username']"],
+    ),
+    (
+        "login.continue_button",
+        &[
+            "css:button#login-button",
+            "css:button[data-testid='login-button']",
+        ],
+    ),
+    (
+        "login.password_option",
+        &[
+            "css:button[data-encore-id='buttonTertiary'].Button-sc-1dqy6lx-0",
+            "xpath://button[.//span[normalize-space(text())='Log in with a password']]",
+            "xpath://button[normalize-space(.)='Log in with a password']",
+        ],
+    ),
+    (
+        "login.password_input",
+        &[
+            "css:input[data-testid='login-password']",
+            "css:input#login-password",
+            "css:input[type='password']",
+        ],
+    ),
+    (
+        "login.submit_button",
+        &[
+            "xpath://button[@id='login-button' or @data-testid='login-button' or .//span[translate(normalize-space(text()), 'abcdefghijklmnopqrstuvwxyz', 'ABCDEFGHIJKLMNOPQRSTUVWXYZ')='LOG IN']]",
+        ],
+    ),
+    (
+        "login.one_time_code_input",
+        &[
+            "css:input[autocomplete='one-time-code']",
+            "css:input[data-testid='login-otp']",
+            "css:input[name='otp']",
+        ],
+    ),
+    (
+        "login.one_time_code_submit",
+        &[
+            "xpath://button[@type='submit' or @data-testid='submit-button' or .//span[normalize-space(text())='Verify' or normalize-space(text())='Continue']]",
+        ],
+    ),
+    // Bot challenges (see `challenge.rs`)
+    (
+        "challenge.captcha",
+        &[
+            "css:iframe[src*='recaptcha']",
+            "css:iframe[src*='hcaptcha']",
+            "css:iframe[src*='arkoselabs']",
+            "css:iframe[src*='challenges.cloudflare.com']",
+            "css:[data-testid='captcha']",
+        ],
+    ),
+    // Episode wizard
+    (
+        "wizard.select_file_button",
+        &["xpath://span[text()='Select a file']/ancestor::button"],
+    ),
+    ("wizard.file_input", &["css:input[type='file']"]),
+    (
+        "wizard.upload_progress",
+        &[
+            "css:[role='progressbar']",
+            "css:[data-testid='upload-progress']",
+        ],
+    ),
+    (
+        "wizard.processing_indicator",
+        &[
+            "css:[data-testid='processing-indicator']",
+            "xpath://*[starts-with(normalize-space(text()), 'Processing')]",
+        ],
+    ),
+    (
+        "wizard.upload_error",
+        &[
+            "css:[data-testid='upload-error']",
+            "xpath://*[contains(normalize-space(text()), 'Upload failed') or contains(normalize-space(text()), 'file type is not supported') or contains(normalize-space(text()), \"couldn't upload\")]",
+        ],
+    ),
+    (
+        "wizard.title_input",
+        &["css:input#title-input", "css:input[name='title']"],
+    ),
+    (
+        "wizard.description_editor",
+        &[
+            "css:div[role='textbox'][data-slate-editor='true']",
+            "css:div[contenteditable='true'][role='textbox']",
+        ],
+    ),
+    (
+        "wizard.artwork_input",
+        &[
+            "css:input[type='file'][accept*='image']",
+            "css:[data-testid='episode-image-upload'] input[type='file']",
+        ],
+    ),
+    (
+        "wizard.artwork_save_button",
+        &[
+            "css:[role='dialog'] button[data-testid='save-image-button']",
+            "xpath://*[@role='dialog']//button[normalize-space(.)='Save']",
+        ],
+    ),
+    (
+        "wizard.season_input",
+        &["css:input#season-number", "css:input[name='podcastSeasonNumber']"],
+    ),
+    (
+        "wizard.episode_number_input",
+        &[
+            "css:input#episode-number",
+            "css:input[name='podcastEpisodeNumber']",
+        ],
+    ),
+    // {type} is replaced with `full`, `trailer` or `bonus`
+    (
+        "wizard.episode_type_option",
+        &[
+            "xpath://input[@name='podcastEpisodeType'][@value='{type}']/ancestor::label",
+            "css:label[for='podcastEpisodeType-{type}']",
+            "css:input[name='podcastEpisodeType'][value='{type}']",
+        ],
+    ),
+    (
+        "wizard.explicit_yes",
+        &[
+            "xpath://input[@name='podcastEpisodeIsExplicit'][@value='true']/ancestor::label",
+            "css:input[name='podcastEpisodeIsExplicit'][value='true']",
+        ],
+    ),
+    (
+        "wizard.explicit_no",
+        &[
+            "xpath://input[@name='podcastEpisodeIsExplicit'][@value='false']/ancestor::label",
+            "css:input[name='podcastEpisodeIsExplicit'][value='false']",
+        ],
+    ),
+    (
+        "wizard.close_button",
+        &[
+            "css:button[aria-label='Close'][data-encore-id='buttonTertiary']",
+            "css:button[aria-label='Close']",
+        ],
+    ),
+    (
+        "wizard.save_draft_button",
+        &[
+            "xpath://span[text()='Save draft']/ancestor::button",
+            "xpath://button[normalize-space(.)='Save draft']",
+        ],
+    ),
+    (
+        "wizard.next_button",
+        &["css:button[form='details-form'][type='submit']"],
+    ),
+    // Review and publish step
+    (
+        "review.publish_now_option",
+        &["css:input#publish-date-now", "css:input[value='now']"],
+    ),
+    (
+        "review.schedule_option",
+        &["css:input#publish-date-schedule", "css:input[value='schedule']"],
+    ),
+    ("review.date_input", &["css:input#date-picker-input"]),
+    ("review.time_input", &["css:input#time-picker-input"]),
+    (
+        "review.submit_button",
+        &["css:button[form='review-form'][type='submit']"],
+    ),
+    // Episodes list; {title} is replaced with the episode title as an XPath literal
+    (
+        "episodes.row",
+        &[
+            "xpath://tr[.//*[normalize-space(text())={title}]]",
+            "xpath://*[@role='row'][.//*[normalize-space(text())={title}]]",
+        ],
+    ),
+    // Only the status cell of the row, so that a title such as "Alive in Christ" is
+    // never read as a status
+    (
+        "episodes.status",
+        &[
+            "xpath://tr[.//*[normalize-space(text())={title}]]//*[@data-testid='episode-status']",
+            "xpath://tr[.//*[normalize-space(text())={title}]]/td[not(.//*[normalize-space(text())={title}])][starts-with(normalize-space(.), 'Draft') or starts-with(normalize-space(.), 'Published') or starts-with(normalize-space(.), 'Scheduled')]",
+            "xpath://*[@role='row'][.//*[normalize-space(text())={title}]]/*[@role='cell' or @role='gridcell'][not(.//*[normalize-space(text())={title}])][starts-with(normalize-space(.), 'Draft') or starts-with(normalize-space(.), 'Published') or starts-with(normalize-space(.), 'Scheduled')]",
+        ],
+    ),
+    (
+        "episodes.link",
+        &[
+            "xpath://tr[.//*[normalize-space(text())={title}]]//a[contains(@href, '/episode/')]",
+            "xpath://*[@role='row'][.//*[normalize-space(text())={title}]]//a[contains(@href, '/episode/')]",
+            "xpath://a[contains(@href, '/episode/')][normalize-space(.)={title}]",
+        ],
+    ),
+    // Page of one episode
+    (
+        "episode.edit_button",
+        &[
+            "css:a[data-testid='edit-episode-button']",
+            "xpath://a[normalize-space(.)='Edit episode']",
+            "xpath://button[normalize-space(.)='Edit episode']",
+        ],
+    ),
+    (
+        "episode.more_button",
+        &[
+            "css:button[data-testid='episode-options-button']",
+            "css:button[aria-label='More options']",
+        ],
+    ),
+    (
+        "episode.delete_option",
+        &[
+            "xpath://*[@role='menuitem'][normalize-space(.)='Delete episode']",
+            "xpath://button[normalize-space(.)='Delete episode']",
+        ],
+    ),
+    (
+        "episode.confirm_delete_button",
+        &[
+            "css:[role='dialog'] button[data-testid='confirm-delete-button']",
+            "xpath://*[@role='dialog']//button[normalize-space(.)='Delete']",
+        ],
+    ),
+    // Episode edit form; the title and description use the wizard elements
+    (
+        "edit.replace_audio_button",
+        &[
+            "css:button[data-testid='replace-audio-button']",
+            "xpath://button[normalize-space(.)='Replace file' or normalize-space(.)='Replace audio']",
+        ],
+    ),
+    (
+        "edit.audio_input",
+        &[
+            "css:input[type='file'][accept*='audio']",
+            "css:input[type='file']:not([accept*='image'])",
+        ],
+    ),
+    (
+        "edit.save_button",
+        &[
+            "css:button[form='details-form'][type='submit']",
+            "xpath://button[normalize-space(.)='Save']",
+        ],
+    ),
+];
+
+/// A way of locating an element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Selector {
+    Css(String),
+    XPath(String),
+}
+
+impl Selector {
+    /// Parses a `css:`/`xpath:` prefixed selector, guessing the kind if unprefixed.
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if let Some(css) = text.strip_prefix("css:") {
+            Self::Css(css.trim().to_string())
+        } else if let Some(xpath) = text.strip_prefix("xpath:") {
+            Self::XPath(xpath.trim().to_string())
+        } else if text.starts_with('/') || text.starts_with('(') {
+            Self::XPath(text.to_string())
+        } else {
+            Self::Css(text.to_string())
+        }
+    }
+
+    /// Replaces `{name}` placeholders with their values.
+    fn substitute(&self, vars: &[(&str, &str)]) -> Self {
+        let apply = |text: &str| {
+            vars.iter().fold(text.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+        };
+        match self {
+            Self::Css(css) => Self::Css(apply(css)),
+            Self::XPath(xpath) => Self::XPath(apply(xpath)),
+        }
+    }
+
+    fn locator(&self) -> Locator<'_> {
+        match self {
+            Self::Css(css) => Locator::Css(css),
+            Self::XPath(xpath) => Locator::XPath(xpath),
+        }
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Css(css) => write!(f, "css:{css}"),
+            Self::XPath(xpath) => write!(f, "xpath:{xpath}"),
+        }
+    }
+}
+
+/// Outcome of checking one selector in diagnostic mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Check {
+    matched: bool,
+    /// The element is not always present (e.g. the two-factor step)
+    optional: bool,
+}
+
+/// The named elements of the Spotify pages and their selectors.
+#[derive(Debug)]
+pub struct Pages {
+    /// Address the pages are served from, without a trailing slash
+    pub base_url: String,
+    /// Pauses between the steps on the pages
+    pub delays: Delays,
+    elements: HashMap<String, Vec<Selector>>,
+    diagnostic: bool,
+    checks: Mutex<BTreeMap<(String, Selector), Check>>,
+}
+
+impl Default for Pages {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Pages {
+    /// Creates the page objects with the built-in selectors.
+    pub fn new(diagnostic: bool) -> Self {
+        let elements = ELEMENTS
+            .iter()
+            .map(|(name, selectors)| {
+                let selectors = selectors.iter().map(|s| Selector::parse(s)).collect();
+                (name.to_string(), selectors)
+            })
+            .collect();
+
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            delays: Delays::default(),
+            elements,
+            diagnostic,
+            checks: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Builds the page objects from the environment.
+    ///
+    /// - `SPOTIFY_SELECTORS_FILE`: JSON file of selectors tried before the built-in ones
+    /// - `SPOTIFY_SELECTOR_DIAGNOSTICS`: `true` to check and report every selector
+    /// - `SPOTIFY_BASE_URL`: address of Spotify for Podcasters, e.g. a local mock of
+    ///   its pages for end-to-end tests
+    /// - `SPOTIFY_DELAY_PROFILE` and `SPOTIFY_DELAY_SEED`: pauses between steps (see
+    ///   `Delays::from_env`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the override file cannot be read or is invalid, or the
+    /// delay profile or seed is invalid.
+    pub fn from_env() -> Result<Self> {
+        let diagnostic = env::var("SPOTIFY_SELECTOR_DIAGNOSTICS")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let mut pages = Self::new(diagnostic);
+        pages.delays = Delays::from_env()?;
+        if let Some(url) = env::var("SPOTIFY_BASE_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            pages.base_url = url.trim().trim_end_matches('/').to_string();
+        }
+
+        if let Some(path) = env::var("SPOTIFY_SELECTORS_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            let json = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read selectors file: {path}"))?;
+            let count = pages
+                .apply_overrides(&json)
+                .with_context(|| format!("Invalid selectors file: {path}"))?;
+            info!(path = %path, elements = count, "Loaded selector overrides");
+        }
+
+        Ok(pages)
+    }
+
+    /// Puts the selectors of an override file in front of the built-in ones.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of elements overridden.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or a selector list is not an array
+    /// of strings.
+    pub fn apply_overrides(&mut self, json: &str) -> Result<usize> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+
+        let version = value.get("version").and_then(|v| v.as_u64());
+        if version != Some(SELECTORS_VERSION as u64) {
+            warn!(
+                file_version = ?version,
+                built_in_version = SELECTORS_VERSION,
+                "Selector overrides were written for another selectors version"
+            );
+        }
+
+        let elements = value
+            .get("elements")
+            .and_then(|e| e.as_object())
+            .ok_or_else(|| anyhow!("Missing \"elements\" object"))?;
+
+        for (name, selectors) in elements {
+            let selectors = selectors
+                .as_array()
+                .ok_or_else(|| anyhow!("Selectors of {name} must be an array"))?
+                .iter()
+                .map(|s| {
+                    s.as_str()
+                        .map(Selector::parse)
+                        .ok_or_else(|| anyhow!("Selectors of {name} must be strings"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            match self.elements.get_mut(name) {
+                Some(existing) => {
+                    existing.retain(|s| !selectors.contains(s));
+                    existing.splice(0..0, selectors);
+                }
+                None => {
+                    warn!(element = %name, "Unknown element in selector overrides");
+                    self.elements.insert(name.clone(), selectors);
+                }
+            }
+        }
+
+        Ok(elements.len())
+    }
+
+    /// Returns the selectors of an element, in the order they are tried.
+    pub fn selectors(&self, name: &str) -> Result<&[Selector]> {
+        self.elements
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| anyhow!("Unknown page element: {name}"))
+    }
+
+    /// Waits for a human-like pause of the given kind.
+    pub async fn pause(&self, pause: Pause) {
+        self.delays.pause(pause).await;
+    }
+
+    /// Returns the absolute URL of a page, e.g. `/pod/dashboard/episodes`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Tries every selector of an element once and returns the first match.
+    async fn locate(
+        &self,
+        client: &Client,
+        name: &str,
+        vars: &[(&str, &str)],
+        optional: bool,
+    ) -> Result<Option<Element>> {
+        let mut found = None;
+
+        for (i, template) in self.selectors(name)?.iter().enumerate() {
+            let selector = template.substitute(vars);
+            let result = client.find(selector.locator()).await;
+
+            if self.diagnostic {
+                // Recorded without the substituted values, so that lookups of the same
+                // element for different episodes are reported together
+                let mut checks = self.checks.lock().unwrap_or_else(|e| e.into_inner());
+                let check = checks
+                    .entry((name.to_string(), template.clone()))
+                    .or_default();
+                check.matched |= result.is_ok();
+                check.optional = optional;
+            }
+
+            if let Ok(element) = result {
+                if found.is_none() {
+                    if i > 0 {
+                        debug!(element = %name, selector = %selector, "Matched fallback selector");
+                    }
+                    found = Some(element);
+                }
+                // Diagnostic mode checks the remaining selectors too
+                if !self.diagnostic {
+                    break;
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Finds an element that must be on the current page.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the element if none of its selectors match.
+    pub async fn find(&self, client: &Client, name: &str) -> Result<Element> {
+        self.find_with(client, name, &[]).await
+    }
+
+    /// Finds an element whose selectors contain `{name}` placeholders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the element if none of its selectors match.
+    pub async fn find_with(
+        &self,
+        client: &Client,
+        name: &str,
+        vars: &[(&str, &str)],
+    ) -> Result<Element> {
+        self.locate(client, name, vars, false)
+            .await?
+            .ok_or_else(|| anyhow!("Page element not found: {name}"))
+    }
+
+    /// Finds an element that may legitimately be missing from the page.
+    pub async fn try_find(&self, client: &Client, name: &str) -> Option<Element> {
+        self.try_find_with(client, name, &[]).await
+    }
+
+    /// Finds an element whose selectors contain `{name}` placeholders and that may
+    /// legitimately be missing from the page.
+    pub async fn try_find_with(
+        &self,
+        client: &Client,
+        name: &str,
+        vars: &[(&str, &str)],
+    ) -> Option<Element> {
+        self.locate(client, name, vars, true).await.ok().flatten()
+    }
+
+    /// Waits for an element to appear, polling all of its selectors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the element if it does not appear within 30 seconds.
+    pub async fn wait_for(&self, client: &Client, name: &str) -> Result<Element> {
+        self.wait_for_with(client, name, &[]).await
+    }
+
+    /// Waits for an element whose selectors contain `{name}` placeholders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the element if it does not appear within 30 seconds.
+    pub async fn wait_for_with(
+        &self,
+        client: &Client,
+        name: &str,
+        vars: &[(&str, &str)],
+    ) -> Result<Element> {
+        let deadline = Instant::now() + WAIT_TIMEOUT;
+        loop {
+            if let Some(element) = self.locate(client, name, vars, false).await? {
+                return Ok(element);
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Timed out waiting for page element: {name} ({}s)",
+                    WAIT_TIMEOUT.as_secs()
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Logs the selectors checked in diagnostic mode that no longer match.
+    ///
+    /// Does nothing outside diagnostic mode.
+    pub fn log_report(&self) {
+        if !self.diagnostic {
+            return;
+        }
+        let checks = self.checks.lock().unwrap_or_else(|e| e.into_inner());
+        let report = report(&checks);
+        if report.is_empty() {
+            info!(
+                version = SELECTORS_VERSION,
+                "Selector diagnostics: every checked selector matched"
+            );
+        }
+        for line in report {
+            warn!(version = SELECTORS_VERSION, "Selector diagnostics: {line}");
+        }
+    }
+}
+
+/// Describes the selectors that did not match, per element.
+///
+/// Elements where no selector matched are reported as broken, unless they are
+/// optional; elements that only matched through a fallback list the stale selectors.
+fn report(checks: &BTreeMap<(String, Selector), Check>) -> Vec<String> {
+    let mut by_element: BTreeMap<&str, Vec<(&Selector, Check)>> = BTreeMap::new();
+    for ((name, selector), check) in checks {
+        by_element
+            .entry(name.as_str())
+            .or_default()
+            .push((selector, *check));
+    }
+
+    let mut lines = Vec::new();
+    for (name, selectors) in by_element {
+        let stale: Vec<String> = selectors
+            .iter()
+            .filter(|(_, check)| !check.matched)
+            .map(|(selector, _)| selector.to_string())
+            .collect();
+        if stale.is_empty() {
+            continue;
+        }
+
+        if selectors.iter().any(|(_, check)| check.matched) {
+            lines.push(format!("{name}: no longer matches {}", stale.join(" | ")));
+        } else if !selectors.iter().any(|(_, check)| check.optional) {
+            lines.push(format!("{name}: not found, tried {}", stale.join(" | ")));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector_parse() {
+        assert_eq!(
+            Selector::parse("css:input#title-input"),
+            Selector::Css("input#title-input".to_string())
+        );
+        assert_eq!(
+            Selector::parse("xpath://button"),
+            Selector::XPath("//button".to_string())
+        );
+        assert_eq!(
+            Selector::parse("(//button)[1]"),
+            Selector::XPath("(//button)[1]".to_string())
+        );
+        assert_eq!(
+            Selector::parse("button.primary"),
+            Selector::Css("button.primary".to_string())
+        );
+    }
+
+    #[test]
+    fn test_built_in_selectors() {
+        let pages = Pages::default();
+        for (name, _) in ELEMENTS {
+            assert!(!pages.selectors(name).unwrap().is_empty(), "{name}");
+        }
+        assert!(pages.selectors("login.missing").is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut pages = Pages::default();
+        let count = pages
+            .apply_overrides(
+                r#"{
+                    "version": 1,
+                    "elements": {
+                        "wizard.title_input": ["input[name='episode-title']", "css:input#title-input"],
+                        "wizard.extra": ["//div"]
+                    }
+                }"#,
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            pages.selectors("wizard.title_input").unwrap(),
+            [
+                Selector::Css("input[name='episode-title']".to_string()),
+                Selector::Css("input#title-input".to_string()),
+                Selector::Css("input[name='title']".to_string()),
+            ]
+        );
+        assert_eq!(
+            pages.selectors("wizard.extra").unwrap(),
+            [Selector::XPath("//div".to_string())]
+        );
+
+        assert!(pages.apply_overrides(r#"{"version": 1}"#).is_err());
+        assert!(pages
+            .apply_overrides(r#"{"elements": {"login.link": "a"}}"#)
+            .is_err());
+    }
+
+    #[test]
+    fn test_substitute() {
+        let selector = Selector::parse("xpath://tr[.//*[normalize-space(text())={title}]]");
+        assert_eq!(
+            selector.substitute(&[("title", "'Faith'")]),
+            Selector::XPath("//tr[.//*[normalize-space(text())='Faith']]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let check = |matched, optional| Check { matched, optional };
+        let mut checks = BTreeMap::new();
+        checks.insert(
+            (
+                "login.password_option".to_string(),
+                Selector::parse("css:.old"),
+            ),
+            check(false, true),
+        );
+        checks.insert(
+            (
+                "login.password_option".to_string(),
+                Selector::parse("css:.new"),
+            ),
+            check(true, true),
+        );
+        checks.insert(
+            (
+                "wizard.title_input".to_string(),
+                Selector::parse("css:#title"),
+            ),
+            check(false, false),
+        );
+        checks.insert(
+            (
+                "login.one_time_code_input".to_string(),
+                Selector::parse("css:#otp"),
+            ),
+            check(false, true),
+        );
+        checks.insert(
+            (
+                "wizard.file_input".to_string(),
+                Selector::parse("css:input"),
+            ),
+            check(true, false),
+        );
+
+        assert_eq!(
+            report(&checks),
+            [
+                "login.password_option: no longer matches css:.old",
+                "wizard.title_input: not found, tried css:#title",
+            ]
+        );
+    }
+}
@@ -0,0 +1,446 @@
+//! Podbean publisher module.
+//!
+//! The `podbean` publisher sends episodes to a Podbean podcast through its API
+//! (<https://developers.podbean.com>), with the client ID and secret of an app
+//! registered for the podcast:
+//!
+//! 1. `POST /v1/oauth/token` exchanges the client credentials for an access token
+//! 2. `GET /v1/files/uploadAuthorize` returns a pre-signed upload URL and a file key
+//!    for the audio file and for the artwork; both are uploaded
+//! 3. `POST /v1/episodes` creates the episode with the uploaded files, the title,
+//!    description, season and episode numbers, type and explicit flag, as a draft,
+//!    published or scheduled as set by `PODBEAN_PUBLISH_MODE` (draft by default)
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{Local, TimeZone};
+use reqwest::Client;
+use std::env;
+use std::fs;
+use std::path::Path;
+use tracing::{debug, info};
+
+use crate::editor;
+use crate::episode::Episode;
+use crate::publish::{
+    api_json, credential, upload_to_url, EpisodeStatus, PublishMode, Published, Publisher,
+};
+use crate::s3;
+
+/// API used when `PODBEAN_API_URL` is not set
+pub const DEFAULT_API_URL: &str = "https://api.podbean.com";
+
+/// Connection settings of a Podbean podcast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodbeanConfig {
+    /// API base URL, without a trailing slash
+    pub api_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Whether episodes are left as drafts, published or scheduled
+    pub mode: PublishMode,
+}
+
+impl PodbeanConfig {
+    /// Reads the podcast settings from the environment.
+    ///
+    /// - `PODBEAN_CLIENT_ID`, `PODBEAN_CLIENT_SECRET` (or `PODBEAN_CLIENT_ID_FILE`,
+    ///   `PODBEAN_CLIENT_SECRET_FILE`): credentials of the Podbean app (required)
+    /// - `PODBEAN_PUBLISH_MODE`, `PODBEAN_PUBLISH_SCHEDULE`: as for Spotify
+    ///   (default `draft`)
+    /// - `PODBEAN_API_URL`: API base URL (default `https://api.podbean.com`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a credential is missing or the publish mode is invalid.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            api_url: env::var("PODBEAN_API_URL")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            client_id: credential("PODBEAN_CLIENT_ID")?,
+            client_secret: credential("PODBEAN_CLIENT_SECRET")?,
+            mode: PublishMode::from_env_for("PODBEAN")?,
+        })
+    }
+}
+
+/// Publishes episodes to a Podbean podcast through its API.
+#[derive(Debug, Clone)]
+pub struct PodbeanPublisher {
+    pub config: PodbeanConfig,
+    http: Client,
+}
+
+impl PodbeanPublisher {
+    pub fn new(config: PodbeanConfig) -> Self {
+        Self {
+            config,
+            http: Client::new(),
+        }
+    }
+
+    /// Builds the publisher from the `PODBEAN_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the podcast settings are incomplete.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(PodbeanConfig::from_env()?))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.config.api_url)
+    }
+
+    /// Exchanges the client credentials for an access token.
+    async fn access_token(&self) -> Result<String> {
+        let response = self
+            .http
+            .post(self.url("/v1/oauth/token"))
+            .basic_auth(&self.config.client_id, Some(&self.config.client_secret))
+            .form(&[("grant_type", "client_credentials")])
+            .send()
+            .await?;
+        let body = api_json(response, "get a Podbean access token").await?;
+        body["access_token"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Podbean returned no access token: {body}"))
+    }
+
+    /// Uploads a file and returns the key Podbean gave it.
+    async fn upload(&self, token: &str, path: &Path) -> Result<String> {
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("Invalid file path: {}", path.display()))?;
+        let size = fs::metadata(path)
+            .with_context(|| format!("File not found: {}", path.display()))?
+            .len();
+        let response = self
+            .http
+            .get(self.url("/v1/files/uploadAuthorize"))
+            .query(&[
+                ("access_token", token),
+                ("filename", &filename),
+                ("filesize", &size.to_string()),
+                ("content_type", s3::content_type(&filename)),
+            ])
+            .send()
+            .await?;
+        let body = api_json(response, &format!("authorize the upload of {filename}")).await?;
+        let (Some(url), Some(key)) = (body["presigned_url"].as_str(), body["file_key"].as_str())
+        else {
+            anyhow::bail!("Podbean returned no upload URL: {body}");
+        };
+
+        debug!(filename = %filename, "Uploading file to Podbean");
+        upload_to_url(&self.http, url, path).await?;
+        Ok(key.to_string())
+    }
+}
+
+/// Returns the form fields of a new episode, as sent to `POST /v1/episodes`.
+fn episode_fields(
+    episode: &Episode,
+    status: EpisodeStatus,
+    media_key: &str,
+    logo_key: Option<&str>,
+) -> Result<Vec<(&'static str, String)>> {
+    let mut fields = vec![
+        ("title", episode.title.clone()),
+        ("content", editor::to_html(&episode.description)),
+        (
+            "status",
+            match status {
+                EpisodeStatus::Draft => "draft",
+                EpisodeStatus::Published => "publish",
+                EpisodeStatus::Scheduled(_) => "future",
+            }
+            .to_string(),
+        ),
+        ("type", "public".to_string()),
+        ("media_key", media_key.to_string()),
+        (
+            "apple_episode_type",
+            episode.episode_type.as_str().to_string(),
+        ),
+        (
+            "content_explicit",
+            if episode.explicit {
+                "explicit"
+            } else {
+                "clean"
+            }
+            .to_string(),
+        ),
+    ];
+    if let Some(key) = logo_key {
+        fields.push(("logo_key", key.to_string()));
+    }
+    if let Some(season) = episode.season {
+        fields.push(("season_number", season.to_string()));
+    }
+    if let Some(number) = episode.episode_number {
+        fields.push(("episode_number", number.to_string()));
+    }
+    if let Some(at) = status.publish_at() {
+        let at = Local
+            .from_local_datetime(&at)
+            .earliest()
+            .with_context(|| format!("Invalid local time: {at}"))?;
+        fields.push(("publish_timestamp", at.timestamp().to_string()));
+    }
+    Ok(fields)
+}
+
+#[async_trait]
+impl Publisher for PodbeanPublisher {
+    fn name(&self) -> &str {
+        "podbean"
+    }
+
+    async fn publish(&self, episode: &Episode) -> Result<Published> {
+        let token = self.access_token().await?;
+        let media_key = self.upload(&token, Path::new(&episode.audio_path)).await?;
+        let logo_key = match &episode.artwork {
+            Some(artwork) => Some(self.upload(&token, Path::new(artwork)).await?),
+            None => None,
+        };
+
+        let status = self.config.mode.target_status(Local::now().naive_local());
+        let mut fields = episode_fields(episode, status, &media_key, logo_key.as_deref())?;
+        fields.push(("access_token", token));
+        let response = self
+            .http
+            .post(self.url("/v1/episodes"))
+            .form(&fields)
+            .send()
+            .await?;
+        let body = api_json(response, "create the episode").await?;
+        let created = &body["episode"];
+        let id = created["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Podbean returned no episode ID: {body}"))?;
+        let url = created["permalink_url"]
+            .as_str()
+            .filter(|url| !url.is_empty())
+            .map(str::to_string);
+
+        info!(id = %id, status = %status, "Episode created on Podbean");
+        Ok(Published {
+            status,
+            remote_id: Some(id),
+            url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::EpisodeType;
+    use crate::mock_http::MockHttp;
+    use crate::publish::PublishSchedule;
+    use serde_json::json;
+
+    /// Answers like the Podbean API, with upload URLs on the mock itself.
+    async fn mock_podbean() -> MockHttp {
+        MockHttp::start(|request, base| match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/v1/oauth/token") => (
+                200,
+                json!({"access_token": "token-1", "token_type": "Bearer", "expires_in": 604800}),
+            ),
+            ("GET", "/v1/files/uploadAuthorize") => {
+                let filename = request.param("filename").unwrap_or_default();
+                (
+                    200,
+                    json!({
+                        "presigned_url": format!("{base}/upload/{filename}?signature=abc"),
+                        "expire_at": 1700000000,
+                        "file_key": format!("key/{filename}"),
+                    }),
+                )
+            }
+            ("PUT", path) if path.starts_with("/upload/") => (200, json!({})),
+            ("POST", "/v1/episodes") => (
+                200,
+                json!({"episode": {
+                    "id": "EP123",
+                    "permalink_url": "https://sermons.podbean.com/e/walking-by-faith/",
+                }}),
+            ),
+            _ => (
+                400,
+                json!({"error": "invalid_request", "error_description": "Not found"}),
+            ),
+        })
+        .await
+    }
+
+    fn publisher(api_url: &str, mode: PublishMode) -> PodbeanPublisher {
+        PodbeanPublisher::new(PodbeanConfig {
+            api_url: api_url.to_string(),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            mode,
+        })
+    }
+
+    fn temp_file(name: &str, content: &[u8]) -> String {
+        let path = env::temp_dir().join(format!("podbean_{}_{name}", std::process::id()));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn episode(name: &str) -> Episode {
+        Episode {
+            audio_path: temp_file(&format!("{name}.mp3"), b"audio"),
+            title: "Walking by Faith".to_string(),
+            description: "Join us online.".to_string(),
+            season: Some(2),
+            episode_number: Some(7),
+            episode_type: EpisodeType::Full,
+            explicit: false,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_with_artwork() {
+        let mock = mock_podbean().await;
+        let mut episode = episode("artwork");
+        episode.artwork = Some(temp_file("artwork.jpg", b"image"));
+
+        let published = publisher(&mock.url, PublishMode::Now)
+            .publish(&episode)
+            .await
+            .unwrap();
+        assert_eq!(
+            published,
+            Published {
+                status: EpisodeStatus::Published,
+                remote_id: Some("EP123".to_string()),
+                url: Some("https://sermons.podbean.com/e/walking-by-faith/".to_string()),
+            }
+        );
+
+        let requests = mock.requests();
+        let calls: Vec<_> = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect();
+        let audio = format!("podbean_{}_artwork.mp3", std::process::id());
+        let artwork = format!("podbean_{}_artwork.jpg", std::process::id());
+        assert_eq!(
+            calls,
+            [
+                ("POST", "/v1/oauth/token"),
+                ("GET", "/v1/files/uploadAuthorize"),
+                ("PUT", format!("/upload/{audio}").as_str()),
+                ("GET", "/v1/files/uploadAuthorize"),
+                ("PUT", format!("/upload/{artwork}").as_str()),
+                ("POST", "/v1/episodes"),
+            ]
+        );
+
+        // Basic authentication with "client:secret"
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Basic Y2xpZW50OnNlY3JldA==")
+        );
+        assert_eq!(
+            requests[0].field("grant_type").as_deref(),
+            Some("client_credentials")
+        );
+        assert_eq!(requests[1].param("access_token"), Some("token-1"));
+        assert_eq!(requests[1].param("filesize"), Some("5"));
+        assert_eq!(requests[1].param("content_type"), Some("audio/mpeg"));
+        assert_eq!(requests[2].body, b"audio");
+        assert_eq!(requests[3].param("content_type"), Some("image/jpeg"));
+        assert_eq!(requests[4].body, b"image");
+
+        let create = &requests[5];
+        let field = |name: &str| create.field(name);
+        assert_eq!(field("access_token").as_deref(), Some("token-1"));
+        assert_eq!(field("title").as_deref(), Some("Walking by Faith"));
+        assert_eq!(field("content").as_deref(), Some("<p>Join us online.</p>"));
+        assert_eq!(field("status").as_deref(), Some("publish"));
+        assert_eq!(field("type").as_deref(), Some("public"));
+        assert_eq!(field("media_key"), Some(format!("key/{audio}")));
+        assert_eq!(field("logo_key"), Some(format!("key/{artwork}")));
+        assert_eq!(field("season_number").as_deref(), Some("2"));
+        assert_eq!(field("episode_number").as_deref(), Some("7"));
+        assert_eq!(field("apple_episode_type").as_deref(), Some("full"));
+        assert_eq!(field("content_explicit").as_deref(), Some("clean"));
+        assert_eq!(field("publish_timestamp"), None);
+
+        let _ = fs::remove_file(&episode.audio_path);
+        let _ = fs::remove_file(episode.artwork.unwrap());
+    }
+
+    #[test]
+    fn test_episode_fields_scheduled() {
+        let mut episode = episode("fields");
+        episode.explicit = true;
+        episode.season = None;
+        let at = PublishSchedule::parse("Sunday 18:00")
+            .unwrap()
+            .next_after(Local::now().naive_local());
+
+        let fields = episode_fields(&episode, EpisodeStatus::Scheduled(at), "media", None).unwrap();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(field("status"), Some("future"));
+        assert_eq!(field("content_explicit"), Some("explicit"));
+        assert_eq!(field("season_number"), None);
+        assert_eq!(field("logo_key"), None);
+        assert_eq!(
+            field("publish_timestamp"),
+            Some(
+                Local
+                    .from_local_datetime(&at)
+                    .earliest()
+                    .unwrap()
+                    .timestamp()
+                    .to_string()
+                    .as_str()
+            )
+        );
+
+        let _ = fs::remove_file(&episode.audio_path);
+    }
+
+    #[tokio::test]
+    async fn test_token_error() {
+        let mock = MockHttp::start(|_, _| {
+            (
+                401,
+                json!({"error": "invalid_client", "error_description": "Client authentication failed"}),
+            )
+        })
+        .await;
+        let episode = episode("error");
+
+        let err = publisher(&mock.url, PublishMode::Draft)
+            .publish(&episode)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Failed to get a Podbean access token: 401 Unauthorized"));
+        assert!(err.to_string().contains("Client authentication failed"));
+        assert_eq!(mock.requests().len(), 1);
+
+        let _ = fs::remove_file(&episode.audio_path);
+    }
+}
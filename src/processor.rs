@@ -1,14 +1,30 @@
 //! Video processing orchestration module.
 //!
 //! This module coordinates the complete workflow of processing a YouTube video:
-//! downloading, extracting audio, and uploading to Spotify for Podcasters.
+//! downloading, extracting the audio of each segment, and publishing the episodes to
+//! the configured targets (Spotify for Podcasters by default).
 
-use anyhow::Result;
-use tracing::info;
+use anyhow::{anyhow, Result};
+use sqlx::SqlitePool;
+use std::env;
+use std::fs;
+use std::path::Path;
+use tokio::task;
+use tracing::{error, info, warn};
 
 use crate::{
-    episode::{extract_sermon_chapter, fetch_metadata, trim_audio},
-    webdriver::upload,
+    chapters::{self, parse_chapters, rebase},
+    details::DetailsConfig,
+    episode::{
+        extract_segments, fetch_metadata, parse_segment_rules, trim_audio, Episode, Segment,
+        VideoMetadata,
+    },
+    publish::Publishers,
+    scripture::{self, extract_references},
+    speaker::Extractors,
+    template::{self, render, DEFAULT_DESCRIPTION_TEMPLATE, DEFAULT_TITLE_TEMPLATE},
+    transcript::{transcribe, Transcript},
+    video::playlists_containing,
 };
 
 /// Processes a YouTube video by downloading, extracting audio, and uploading.
@@ -19,77 +35,337 @@ use crate::{
 /// # Arguments
 ///
 /// * `video_id` - The YouTube video ID to process
+/// * `pool` - The episode history, used to number episodes
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success.
+/// Returns the published episodes (one per extracted segment) on success.
 ///
 /// # Errors
 ///
 /// Returns an error if any step in the processing pipeline fails:
 /// - Video download failure
 /// - Audio extraction failure
-/// - Upload to Spotify failure
+/// - Publishing failure on every target
 ///
 /// # Example
 ///
 /// ```no_run
 /// # tokio_test::block_on(async {
-/// audio_epistles::processor::process("dQw4w9WgXcQ").await.unwrap();
+/// let pool = sqlx::SqlitePool::connect("sqlite:videos.db").await.unwrap();
+/// audio_epistles::processor::process("dQw4w9WgXcQ", &pool).await.unwrap();
 /// # })
 /// ```
-pub async fn process(video_id: &str) -> Result<()> {
-    schedule(video_id).await?;
-    Ok(())
+pub async fn process(video_id: &str, pool: &SqlitePool) -> Result<Vec<Episode>> {
+    schedule(video_id, pool).await
 }
 
-/// Downloads a video, extracts the sermon audio segment, and uploads to Spotify.
+/// Downloads a video, extracts its segments, and publishes each one.
 ///
 /// This function performs the complete processing workflow:
 /// 1. Downloads the video and fetches metadata using yt-dlp
-/// 2. Parses the video description to find sermon chapter timestamps
-/// 3. Extracts the sermon audio segment using ffmpeg
-/// 4. Uploads the audio file to Spotify for Podcasters as a draft episode
+/// 2. Extracts scripture references from the title, description and chapter titles,
+///    and detects the speaker and series
+/// 3. Finds the segments to publish from the chapter timestamps in the description
+///    (see `SEGMENT_RULES`)
+/// 4. For each segment, rebases any sub-chapters inside it to episode-relative time
+///    and renders the episode title and description from the segment's templates
+/// 5. Extracts the segment audio using ffmpeg and writes its ID3 tags and chapters
+/// 6. Transcribes the audio if `TRANSCRIBE_COMMAND` is set
+/// 7. Publishes the episode to every target in `PUBLISHERS` (default `spotify`, which
+///    saves a draft, publishes or schedules it depending on `SPOTIFY_PUBLISH_MODE`)
+///
+/// The first segment's audio is saved to `AUDIO_FILE` (default `assets/audio.mp3`);
+/// later segments get a numbered suffix (`audio-2.mp3`, `audio-3.mp3`, ...). Without
+/// `SEGMENT_RULES`, only the first sermon chapter is used, or the whole video if the
+/// description has no chapters. Sub-chapters are also saved as Podcasting 2.0
+/// chapters (`audio.chapters.json`). Transcripts are saved next to the audio
+/// (`audio.txt`, `.srt`, `.vtt`, `.json`); a failed transcription is logged and does
+/// not stop the upload. Each target's outcome is recorded on the episode; a segment
+/// that fails, or that no target accepts, is logged and skipped, so the others are
+/// still published.
 ///
-/// The extracted audio is saved to `assets/audio.mp3`. If no sermon chapter
-/// is found in the description, the entire video audio is extracted.
+/// Each episode carries the details of `DetailsConfig`: cover art (the series image,
+/// the YouTube thumbnail or `EPISODE_ARTWORK`), season and episode numbers counted
+/// from the episode history in `pool` (`EPISODE_NUMBERING`), and the episode type
+/// and explicit flag of its segment.
+///
+/// Templates may use the placeholders `{title}`, `{description}`, `{video_id}`,
+/// `{scripture}` (all references, joined with "; "), `{speaker}`, `{series}`,
+/// `{segment}` (the chapter title) and `{show_notes}` (one "timestamp title" line
+/// per sub-chapter). Each rule can override the templates with
+/// `SEGMENT_<NAME>_TITLE_TEMPLATE` and `SEGMENT_<NAME>_DESCRIPTION_TEMPLATE`.
 ///
 /// # Arguments
 ///
 /// * `video_id` - The YouTube video ID to process
+/// * `pool` - The episode history, used to number episodes
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success.
+/// Returns one episode per segment, in chronological order, on success. A segment
+/// that failed is returned with the error of each publisher (see `Episode::failed`),
+/// so that it is recorded in the database.
 ///
 /// # Errors
 ///
 /// Returns an error if:
+/// - A speaker or series pattern, the episode details or the publisher configuration
+///   is invalid
+/// - The episode history cannot be read
 /// - Video download or metadata fetching fails
-/// - No sermon chapter is found and no fallback is available
-/// - Audio extraction with ffmpeg fails
-/// - Upload to Spotify fails
+/// - No chapter matches a segment rule
+/// - Every segment fails to be extracted or published
 ///
 /// # Example
 ///
 /// ```no_run
 /// # tokio_test::block_on(async {
-/// audio_epistles::processor::schedule("dQw4w9WgXcQ").await.unwrap();
+/// let pool = sqlx::SqlitePool::connect("sqlite:videos.db").await.unwrap();
+/// audio_epistles::processor::schedule("dQw4w9WgXcQ", &pool).await.unwrap();
 /// # })
 /// ```
-pub async fn schedule(video_id: &str) -> Result<()> {
-    let (title, desc, video_path, duration) = fetch_metadata(video_id).await?;
-    info!(title = %title, path = %video_path, duration_secs = duration, "Video metadata retrieved");
+pub async fn schedule(video_id: &str, pool: &SqlitePool) -> Result<Vec<Episode>> {
+    let extractors = Extractors::from_env()?;
+    let details = DetailsConfig::from_env()?;
+    let publishers = Publishers::from_env()?;
+    let metadata = fetch_metadata(video_id).await?;
+    info!(title = %metadata.title, path = %metadata.video_path, duration_secs = metadata.duration, "Video metadata retrieved");
+
+    let mut texts = vec![metadata.title.as_str(), metadata.description.as_str()];
+    texts.extend(metadata.chapters.iter().map(|c| c.title.as_str()));
+    let references = extract_references(&texts);
+    let scripture = scripture::join(&references);
+    if !references.is_empty() {
+        info!(scripture = %scripture, "Scripture references found");
+    }
+
+    // The video is in the sermon playlist; series playlists are checked one by one
+    let sermon_playlist = env::var("SERMON_PLAYLIST_ID").unwrap_or_default();
+    let series_playlists: Vec<&str> = extractors
+        .series_playlist_ids()
+        .into_iter()
+        .filter(|id| *id != sermon_playlist)
+        .collect();
+    let mut playlists = playlists_containing(video_id, &series_playlists).await;
+    if !sermon_playlist.is_empty() {
+        playlists.push(sermon_playlist);
+    }
+    let speaker = extractors.speaker(&metadata.title, &metadata.description);
+    let series = extractors.series(&metadata.title, &metadata.description, &playlists);
+    info!(speaker = ?speaker, series = ?series, "Speaker and series detected");
+
+    let rules = parse_segment_rules(&env::var("SEGMENT_RULES").unwrap_or_default());
+    let segments = extract_segments(&metadata.description, metadata.duration, &rules);
+    if segments.is_empty() {
+        return Err(anyhow!("No chapter matches a segment rule"));
+    }
+    info!(segments = segments.len(), "Segments found");
+
+    let audio_file = env::var("AUDIO_FILE").unwrap_or_else(|_| "assets/audio.mp3".to_string());
+    let artwork_dir = Path::new(&audio_file)
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let artwork = details
+        .artwork(
+            series.as_deref(),
+            metadata.thumbnail.as_deref(),
+            artwork_dir,
+        )
+        .await;
+    let numbers = details.numbers(pool, series.as_deref()).await?;
+    if numbers.episode.is_some() {
+        info!(season = ?numbers.season, episode = ?numbers.episode, "Episode numbers assigned");
+    }
+    let all_chapters = parse_chapters(&metadata.description);
+    let mut episodes = Vec::new();
+
+    for (i, segment) in segments.iter().enumerate() {
+        // A segment may name its own speaker ("Testimony - Sister Ada")
+        let segment_speaker = extractors
+            .speaker(&segment.title, "")
+            .or_else(|| speaker.clone());
+        // Failed segments leave no gap in the numbering
+        let published = episodes.iter().filter(|e: &&Episode| !e.failed()).count();
+        let numbers = numbers.offset(published as u32);
+        let mut episode = Episode {
+            video_id: video_id.to_string(),
+            guid: segment_guid(video_id, i),
+            segment: segment.rule.clone(),
+            audio_path: segment_audio_path(&audio_file, i),
+            artwork: artwork.clone(),
+            season: numbers.season,
+            episode_number: numbers.episode,
+            episode_type: details.episode_type(&segment.rule)?,
+            explicit: details.explicit(&segment.rule),
+            duration: segment.end - segment.start,
+            title: String::new(),
+            description: String::new(),
+            scripture: references.clone(),
+            speaker: segment_speaker,
+            series: series.clone(),
+            transcript: None,
+            chapters: rebase(&all_chapters, segment.start, segment.end),
+            publications: Vec::new(),
+        };
+
+        // Failed segments are recorded with their errors, without an episode number
+        if let Err(e) =
+            publish_segment(&metadata, segment, &mut episode, &scripture, &publishers).await
+        {
+            error!(segment = %segment.rule, start_secs = segment.start, error = %e, "Failed to publish segment");
+            episode.publications = publishers.failed(&e);
+        }
+        if episode.failed() {
+            episode.episode_number = None;
+        }
+        episodes.push(episode);
+    }
+
+    if episodes.iter().all(Episode::failed) {
+        return Err(anyhow!("All {} segments failed", segments.len()));
+    }
+    Ok(episodes)
+}
+
+/// Returns the audio path of the segment at `index`, numbering all but the first.
+fn segment_audio_path(audio_file: &str, index: usize) -> String {
+    if index == 0 {
+        return audio_file.to_string();
+    }
+    let path = Path::new(audio_file);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}-{}.{}", index + 1, ext.to_string_lossy()),
+        None => format!("{stem}-{}", index + 1),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Returns the GUID of the segment at `index`, numbering all but the first.
+fn segment_guid(video_id: &str, index: usize) -> String {
+    if index == 0 {
+        video_id.to_string()
+    } else {
+        format!("{video_id}-{}", index + 1)
+    }
+}
+
+/// Renders, trims, transcribes and publishes one segment of a video.
+async fn publish_segment(
+    metadata: &VideoMetadata,
+    segment: &Segment,
+    episode: &mut Episode,
+    scripture: &str,
+    publishers: &Publishers,
+) -> Result<()> {
+    let show_notes = chapters::show_notes(&episode.chapters);
+    let vars = [
+        ("title", metadata.title.as_str()),
+        ("description", metadata.description.as_str()),
+        ("video_id", episode.video_id.as_str()),
+        ("scripture", scripture),
+        ("speaker", episode.speaker.as_deref().unwrap_or_default()),
+        ("series", episode.series.as_deref().unwrap_or_default()),
+        ("segment", segment.title.as_str()),
+        ("show_notes", show_notes.as_str()),
+    ];
+    let prefix = format!("SEGMENT_{}", segment.rule.to_uppercase());
+    episode.title = render(
+        &template::from_env(
+            &format!("{prefix}_TITLE_TEMPLATE"),
+            &template::from_env("EPISODE_TITLE_TEMPLATE", DEFAULT_TITLE_TEMPLATE),
+        ),
+        &vars,
+    );
+    episode.description = render(
+        &template::from_env(
+            &format!("{prefix}_DESCRIPTION_TEMPLATE"),
+            &template::from_env("EPISODE_DESCRIPTION_TEMPLATE", DEFAULT_DESCRIPTION_TEMPLATE),
+        ),
+        &vars,
+    );
 
-    let output_audio = "assets/audio.mp3";
+    let output_audio = Path::new(&episode.audio_path);
+    let duration = episode.duration;
+    let tags = [
+        ("title", episode.title.as_str()),
+        ("comment", episode.description.as_str()),
+        ("scripture", scripture),
+        ("artist", episode.speaker.as_deref().unwrap_or_default()),
+        ("album", episode.series.as_deref().unwrap_or_default()),
+    ];
+    trim_audio(
+        &metadata.video_path,
+        &episode.audio_path,
+        segment.start,
+        duration,
+        &tags,
+        &episode.chapters,
+    )?;
+    info!(segment = %segment.rule, output_path = %episode.audio_path, duration_secs = duration, "Audio saved successfully");
 
-    if let Some((start, end)) = extract_sermon_chapter(&desc, duration) {
-        let duration = end - start;
-        trim_audio(&video_path, output_audio, start, duration)?;
-        info!(output_path = %output_audio, duration_secs = duration, "Audio saved successfully");
+    if !episode.chapters.is_empty() {
+        let chapters_file = output_audio.with_extension("chapters.json");
+        fs::write(&chapters_file, chapters::to_json(&episode.chapters))?;
+        info!(chapters = episode.chapters.len(), path = %chapters_file.display(), "Chapters saved successfully");
     }
 
-    upload(&title).await?;
+    episode.transcript = match env::var("TRANSCRIBE_COMMAND") {
+        Ok(command) if !command.trim().is_empty() => {
+            match transcribe_audio(output_audio, command).await {
+                Ok(transcript) => Some(transcript),
+                Err(e) => {
+                    warn!(error = %e, "Transcription failed, continuing without transcript");
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
 
+    episode.publications = publishers.publish_all(episode).await;
     Ok(())
 }
+
+/// Transcribes the episode audio and writes the transcript files next to it.
+async fn transcribe_audio(audio: &Path, command: String) -> Result<Transcript> {
+    let audio = audio.to_path_buf();
+    // Spawn blocking because ffmpeg and the speech-to-text command are synchronous
+    let (transcript, files) = task::spawn_blocking(move || {
+        let transcript = transcribe(&audio, &command)?;
+        let files = transcript.write_files(&audio)?;
+        anyhow::Ok((transcript, files))
+    })
+    .await??;
+    info!(
+        segments = transcript.segments.len(),
+        path = %files.srt.display(),
+        "Transcript saved successfully"
+    );
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_audio_path() {
+        assert_eq!(
+            segment_audio_path("assets/audio.mp3", 0),
+            "assets/audio.mp3"
+        );
+        assert_eq!(
+            segment_audio_path("assets/audio.mp3", 1),
+            "assets/audio-2.mp3"
+        );
+        assert_eq!(segment_audio_path("/tmp/episode", 2), "/tmp/episode-3");
+    }
+
+    #[test]
+    fn test_segment_guid() {
+        assert_eq!(segment_guid("dQw4w9WgXcQ", 0), "dQw4w9WgXcQ");
+        assert_eq!(segment_guid("dQw4w9WgXcQ", 1), "dQw4w9WgXcQ-2");
+    }
+}
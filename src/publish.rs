@@ -0,0 +1,665 @@
+//! Episode publishing module.
+//!
+//! Episodes are published through `Publisher` implementations (such as the Spotify
+//! browser automation in `webdriver.rs`, or the Transistor and Podbean APIs in
+//! `transistor.rs` and `podbean.rs`), selected by the `PUBLISHERS` environment
+//! variable. The same episode can be sent to several targets, each tracked on its own.
+//! Publishers read their credentials with `credential`, from the environment or a
+//! secrets file.
+//!
+//! An episode can be left as a draft, published immediately, or scheduled for the
+//! next occurrence of a weekly slot such as "Sunday 18:00" (local time). This module
+//! also parses the configured mode and works out the status an episode should end up in.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use tracing::{error, info};
+
+use crate::diagnostics::FailureDiagnostics;
+use crate::episode::Episode;
+use crate::podbean::PodbeanPublisher;
+use crate::rss::RssPublisher;
+use crate::s3::{self, S3Publisher};
+use crate::transistor::TransistorPublisher;
+use crate::webdriver::SpotifyPublisher;
+
+/// Publishers used when `PUBLISHERS` is not set
+pub const DEFAULT_PUBLISHERS: &str = "spotify";
+
+/// What a target reports back after publishing an episode.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Published {
+    /// Status of the episode on the target
+    pub status: EpisodeStatus,
+    /// The target's ID for the episode, if known
+    pub remote_id: Option<String>,
+    /// Public or dashboard URL of the episode, if known
+    pub url: Option<String>,
+}
+
+/// The outcome of publishing an episode to one target.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Publication {
+    /// Name of the publisher (e.g. "spotify")
+    pub target: String,
+    /// What the target reported, or `None` if publishing failed
+    pub published: Option<Published>,
+    /// Why publishing failed
+    pub error: Option<String>,
+    /// Folder of the diagnostics captured when publishing failed, if any
+    pub diagnostics: Option<String>,
+    /// True if the episode was only checked, not sent
+    pub dry_run: bool,
+}
+
+impl Publication {
+    /// Returns true if the episode was published (or would have been, in a dry run).
+    pub fn succeeded(&self) -> bool {
+        self.published.is_some()
+    }
+}
+
+/// A destination episodes can be published to.
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// Short name used in `PUBLISHERS` and recorded with each publication.
+    fn name(&self) -> &str;
+
+    /// Publishes the episode audio, metadata and artwork.
+    async fn publish(&self, episode: &Episode) -> Result<Published>;
+
+    /// Checks that the episode could be published without sending anything.
+    ///
+    /// The default implementation checks that the audio and artwork files exist.
+    async fn dry_run(&self, episode: &Episode) -> Result<Published> {
+        let files = std::iter::once(&episode.audio_path).chain(&episode.artwork);
+        for file in files {
+            if !Path::new(file).is_file() {
+                anyhow::bail!("File not found: {file}");
+            }
+        }
+        info!(target = self.name(), title = %episode.title, "Dry run: episode not published");
+        Ok(Published::default())
+    }
+}
+
+/// The publishers configured for this run.
+pub struct Publishers {
+    publishers: Vec<Box<dyn Publisher>>,
+    dry_run: bool,
+}
+
+impl Publishers {
+    /// Builds the publishers named in the environment.
+    ///
+    /// - `PUBLISHERS`: comma-separated publisher names (default `spotify`)
+    /// - `PUBLISH_DRY_RUN`: `true` to check episodes without publishing them
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a publisher name is unknown, none is configured, or a
+    /// publisher's own configuration is invalid.
+    pub fn from_env() -> Result<Self> {
+        let names = env::var("PUBLISHERS").unwrap_or_else(|_| DEFAULT_PUBLISHERS.to_string());
+        let dry_run = env::var("PUBLISH_DRY_RUN")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        let mut publishers: Vec<Box<dyn Publisher>> = Vec::new();
+        for name in parse_names(&names) {
+            publishers.push(match name.as_str() {
+                "spotify" => Box::new(SpotifyPublisher::from_env()?),
+                "rss" => Box::new(RssPublisher::from_env()?),
+                "s3" => Box::new(S3Publisher::from_env()?),
+                "transistor" => Box::new(TransistorPublisher::from_env()?),
+                "podbean" => Box::new(PodbeanPublisher::from_env()?),
+                other => anyhow::bail!("Unknown publisher in PUBLISHERS: {other}"),
+            });
+        }
+        if publishers.is_empty() {
+            anyhow::bail!("PUBLISHERS must name at least one publisher");
+        }
+
+        Ok(Self::new(publishers, dry_run))
+    }
+
+    /// Wraps already-built publishers.
+    pub fn new(publishers: Vec<Box<dyn Publisher>>, dry_run: bool) -> Self {
+        Self {
+            publishers,
+            dry_run,
+        }
+    }
+
+    /// Publishes an episode to every target in turn.
+    ///
+    /// A target that fails is logged and recorded with its error; the remaining
+    /// targets are still tried.
+    pub async fn publish_all(&self, episode: &Episode) -> Vec<Publication> {
+        let mut publications = Vec::new();
+        for publisher in &self.publishers {
+            let result = if self.dry_run {
+                publisher.dry_run(episode).await
+            } else {
+                publisher.publish(episode).await
+            };
+
+            let target = publisher.name().to_string();
+            publications.push(match result {
+                Ok(published) => {
+                    info!(target = %target, status = %published.status, remote_id = ?published.remote_id, "Episode published");
+                    Publication {
+                        target,
+                        published: Some(published),
+                        error: None,
+                        diagnostics: None,
+                        dry_run: self.dry_run,
+                    }
+                }
+                Err(e) => {
+                    error!(target = %target, error = %e, "Failed to publish episode");
+                    Publication {
+                        target,
+                        published: None,
+                        error: Some(format!("{e:#}")),
+                        diagnostics: e
+                            .downcast_ref::<FailureDiagnostics>()
+                            .map(|d| d.dir.display().to_string()),
+                        dry_run: self.dry_run,
+                    }
+                }
+            });
+        }
+        publications
+    }
+
+    /// Records `error` as the outcome for every target, for an episode that could not
+    /// be prepared for publishing (e.g. its audio could not be extracted).
+    pub fn failed(&self, error: &anyhow::Error) -> Vec<Publication> {
+        self.publishers
+            .iter()
+            .map(|publisher| Publication {
+                target: publisher.name().to_string(),
+                published: None,
+                error: Some(format!("{error:#}")),
+                diagnostics: None,
+                dry_run: self.dry_run,
+            })
+            .collect()
+    }
+}
+
+/// Reads a credential from the environment variable `name` or, when it is not set,
+/// from the file named in `<name>_FILE` (e.g. a Docker secret).
+///
+/// # Arguments
+///
+/// * `name` - The variable, e.g. `SPOTIFY_PASSWORD` or `TRANSISTOR_API_KEY`
+///
+/// # Returns
+///
+/// Returns the credential with surrounding whitespace removed.
+///
+/// # Errors
+///
+/// Returns an error naming the variable if neither is set, or if the file cannot
+/// be read or is empty.
+///
+/// # Example
+///
+/// ```no_run
+/// // TRANSISTOR_API_KEY=... or TRANSISTOR_API_KEY_FILE=/run/secrets/transistor
+/// let key = audio_epistles::publish::credential("TRANSISTOR_API_KEY").unwrap();
+/// ```
+pub fn credential(name: &str) -> Result<String> {
+    let var = |name: &str| {
+        env::var(name)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    if let Some(value) = var(name) {
+        return Ok(value);
+    }
+
+    let file_var = format!("{name}_FILE");
+    let file = var(&file_var).with_context(|| format!("{name} or {file_var} must be set"))?;
+    let value = fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read {file_var}: {file}"))?
+        .trim()
+        .to_string();
+    if value.is_empty() {
+        anyhow::bail!("{file_var} is empty: {file}");
+    }
+    Ok(value)
+}
+
+/// Uploads a file to a pre-signed URL handed out by a podcast host's API.
+///
+/// The file is streamed rather than read into memory, with its size as the
+/// Content-Length that pre-signed URLs require.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or the upload is refused.
+pub async fn upload_to_url(http: &reqwest::Client, url: &str, path: &Path) -> Result<()> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let size = file.metadata().await?.len();
+    let response = http
+        .put(url)
+        .header("Content-Type", s3::content_type(&path.to_string_lossy()))
+        .header("Content-Length", size)
+        .body(file)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        anyhow::bail!("Upload of {} failed: {}", path.display(), response.status());
+    }
+    Ok(())
+}
+
+/// Reads the JSON body of a podcast host's API response.
+///
+/// # Arguments
+///
+/// * `response` - The API response
+/// * `action` - What the request did, for the error message (e.g. "create the episode")
+///
+/// # Errors
+///
+/// Returns an error with the status and the body sent by the host if the request
+/// failed, or if the body is not JSON.
+pub async fn api_json(response: reqwest::Response, action: &str) -> Result<serde_json::Value> {
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("Failed to {action}: {status}: {}", body.trim());
+    }
+    serde_json::from_str(&body).with_context(|| format!("Invalid response to {action}: {body}"))
+}
+
+/// Splits a comma-separated list of publisher names, lowercased and deduplicated.
+fn parse_names(value: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in value.split(',').map(|n| n.trim().to_lowercase()) {
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// A weekly (or daily) local time at which scheduled episodes go live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublishSchedule {
+    /// Day of the week, or `None` for every day
+    pub weekday: Option<Weekday>,
+    pub time: NaiveTime,
+}
+
+impl PublishSchedule {
+    /// Parses a schedule such as `"Sunday 18:00"`, `"sun 6:30"` or `"18:00"` (daily).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let schedule = audio_epistles::publish::PublishSchedule::parse("Sunday 18:00").unwrap();
+    /// assert_eq!(schedule.weekday, Some(chrono::Weekday::Sun));
+    /// ```
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut parts = value.split_whitespace();
+        let (weekday, time) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(time), None, None) => (None, time),
+            (Some(day), Some(time), None) => {
+                let weekday = day
+                    .parse::<Weekday>()
+                    .map_err(|_| anyhow!("Unknown weekday: {day}"))?;
+                (Some(weekday), time)
+            }
+            _ => anyhow::bail!("Expected \"<weekday> HH:MM\" or \"HH:MM\", got: {value}"),
+        };
+        let time = NaiveTime::parse_from_str(time, "%H:%M")
+            .with_context(|| format!("Invalid time: {time}"))?;
+
+        Ok(Self { weekday, time })
+    }
+
+    /// Returns the first occurrence of this slot strictly after `now`.
+    pub fn next_after(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let mut candidate = now.date().and_time(self.time);
+        while candidate <= now || self.weekday.is_some_and(|day| candidate.weekday() != day) {
+            candidate += Duration::days(1);
+        }
+        candidate
+    }
+}
+
+/// How an episode is left once its details are filled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishMode {
+    /// Save as a draft for someone to publish by hand
+    Draft,
+    /// Publish immediately
+    Now,
+    /// Schedule for the next occurrence of a slot
+    Schedule(PublishSchedule),
+}
+
+impl PublishMode {
+    /// Reads the publish mode of the Spotify feed from the environment.
+    ///
+    /// - `SPOTIFY_PUBLISH_MODE`: `draft` (default), `now` or `schedule`
+    /// - `SPOTIFY_PUBLISH_SCHEDULE`: the slot used by `schedule`, e.g. `Sunday 18:00`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mode is unknown, or if it is `schedule` and the slot
+    /// is missing or invalid.
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_for("SPOTIFY")
+    }
+
+    /// Reads the publish mode of a target from `<prefix>_PUBLISH_MODE` and
+    /// `<prefix>_PUBLISH_SCHEDULE`, e.g. `TRANSISTOR_PUBLISH_MODE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mode is unknown, or if it is `schedule` and the slot
+    /// is missing or invalid.
+    pub fn from_env_for(prefix: &str) -> Result<Self> {
+        let mode = env::var(format!("{prefix}_PUBLISH_MODE")).unwrap_or_default();
+        let schedule = env::var(format!("{prefix}_PUBLISH_SCHEDULE")).ok();
+        Self::parse(&mode, schedule.as_deref())
+            .with_context(|| format!("Invalid {prefix}_PUBLISH_MODE or {prefix}_PUBLISH_SCHEDULE"))
+    }
+
+    /// Parses a mode name and, for `schedule`, its slot.
+    pub fn parse(mode: &str, schedule: Option<&str>) -> Result<Self> {
+        match mode.trim().to_lowercase().as_str() {
+            "" | "draft" => Ok(Self::Draft),
+            "now" | "publish" => Ok(Self::Now),
+            "schedule" => {
+                let schedule = schedule
+                    .filter(|s| !s.trim().is_empty())
+                    .ok_or_else(|| anyhow!("A publish schedule must be set for `schedule`"))?;
+                Ok(Self::Schedule(PublishSchedule::parse(schedule)?))
+            }
+            other => anyhow::bail!("Unknown publish mode: {other}"),
+        }
+    }
+
+    /// Returns the status an episode published in this mode at `now` should have.
+    pub fn target_status(&self, now: NaiveDateTime) -> EpisodeStatus {
+        match self {
+            Self::Draft => EpisodeStatus::Draft,
+            Self::Now => EpisodeStatus::Published,
+            Self::Schedule(schedule) => EpisodeStatus::Scheduled(schedule.next_after(now)),
+        }
+    }
+}
+
+/// The state of an episode on the podcast host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpisodeStatus {
+    #[default]
+    Draft,
+    Published,
+    /// Scheduled to go live at the given local time
+    Scheduled(NaiveDateTime),
+}
+
+impl EpisodeStatus {
+    /// Returns the name stored in the database (`draft`, `published` or `scheduled`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Scheduled(_) => "scheduled",
+        }
+    }
+
+    /// Returns the scheduled publish time, if any.
+    pub fn publish_at(&self) -> Option<NaiveDateTime> {
+        match self {
+            Self::Scheduled(at) => Some(*at),
+            _ => None,
+        }
+    }
+
+    /// Returns true if `label` (the status cell of an episode list) matches this status.
+    ///
+    /// Only the leading word is compared, so "Scheduled for Oct 25" is scheduled.
+    pub fn matches_label(&self, label: &str) -> bool {
+        let word = label
+            .split(|c: char| !c.is_alphabetic())
+            .find(|word| !word.is_empty())
+            .unwrap_or_default();
+        word.eq_ignore_ascii_case(self.as_str())
+    }
+}
+
+impl fmt::Display for EpisodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scheduled(at) => write!(f, "scheduled for {}", at.format("%Y-%m-%d %H:%M")),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// A publisher that succeeds or fails without side effects
+    struct FakePublisher {
+        name: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Publisher for FakePublisher {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn publish(&self, episode: &Episode) -> Result<Published> {
+            if self.fail {
+                anyhow::bail!("{} is down", self.name);
+            }
+            Ok(Published {
+                status: EpisodeStatus::Published,
+                remote_id: Some(format!("{}-{}", self.name, episode.video_id)),
+                url: None,
+            })
+        }
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let schedule = PublishSchedule::parse("sun 6:30").unwrap();
+        assert_eq!(schedule.weekday, Some(Weekday::Sun));
+        assert_eq!(schedule.time, NaiveTime::from_hms_opt(6, 30, 0).unwrap());
+
+        assert_eq!(PublishSchedule::parse("18:00").unwrap().weekday, None);
+        assert!(PublishSchedule::parse("Someday 18:00").is_err());
+        assert!(PublishSchedule::parse("Sunday 25:00").is_err());
+        assert!(PublishSchedule::parse("").is_err());
+    }
+
+    #[test]
+    fn test_next_after_weekly() {
+        let schedule = PublishSchedule::parse("Sunday 18:00").unwrap();
+        // 2026-10-14 is a Wednesday
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 14, 9, 0)),
+            at(2026, 10, 18, 18, 0)
+        );
+        // Sunday before and after the slot
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 18, 17, 59)),
+            at(2026, 10, 18, 18, 0)
+        );
+        assert_eq!(
+            schedule.next_after(at(2026, 10, 18, 18, 0)),
+            at(2026, 10, 25, 18, 0)
+        );
+    }
+
+    #[test]
+    fn test_next_after_daily() {
+        let schedule = PublishSchedule::parse("06:00").unwrap();
+        assert_eq!(
+            schedule.next_after(at(2026, 12, 31, 7, 0)),
+            at(2027, 1, 1, 6, 0)
+        );
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(PublishMode::parse("", None).unwrap(), PublishMode::Draft);
+        assert_eq!(PublishMode::parse("NOW", None).unwrap(), PublishMode::Now);
+        assert!(PublishMode::parse("schedule", None).is_err());
+        assert!(PublishMode::parse("later", None).is_err());
+
+        let mode = PublishMode::parse("schedule", Some("Sunday 18:00")).unwrap();
+        assert_eq!(
+            mode.target_status(at(2026, 10, 14, 9, 0)),
+            EpisodeStatus::Scheduled(at(2026, 10, 18, 18, 0))
+        );
+    }
+
+    #[test]
+    fn test_credential() {
+        env::set_var("TEST_CREDENTIAL_KEY", " secret \n");
+        assert_eq!(credential("TEST_CREDENTIAL_KEY").unwrap(), "secret");
+
+        let file = env::temp_dir().join(format!("credential_{}", std::process::id()));
+        fs::write(&file, "from-file\n").unwrap();
+        env::set_var("TEST_CREDENTIAL_SECRET_FILE", &file);
+        assert_eq!(credential("TEST_CREDENTIAL_SECRET").unwrap(), "from-file");
+
+        fs::write(&file, "\n").unwrap();
+        assert!(credential("TEST_CREDENTIAL_SECRET").is_err());
+        fs::remove_file(&file).unwrap();
+
+        let err = credential("TEST_CREDENTIAL_MISSING").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TEST_CREDENTIAL_MISSING or TEST_CREDENTIAL_MISSING_FILE must be set"
+        );
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse_names(" Spotify, rss,,spotify "), ["spotify", "rss"]);
+        assert!(parse_names("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_all_tracks_targets_independently() {
+        let publishers = Publishers::new(
+            vec![
+                Box::new(FakePublisher {
+                    name: "down",
+                    fail: true,
+                }),
+                Box::new(FakePublisher {
+                    name: "up",
+                    fail: false,
+                }),
+            ],
+            false,
+        );
+        let episode = Episode {
+            video_id: "abc".to_string(),
+            ..Default::default()
+        };
+
+        let publications = publishers.publish_all(&episode).await;
+        assert_eq!(publications.len(), 2);
+        assert!(!publications[0].succeeded());
+        assert_eq!(publications[0].error.as_deref(), Some("down is down"));
+        assert_eq!(publications[1].target, "up");
+        assert_eq!(
+            publications[1]
+                .published
+                .as_ref()
+                .unwrap()
+                .remote_id
+                .as_deref(),
+            Some("up-abc")
+        );
+        let episode = Episode {
+            publications,
+            ..episode
+        };
+        assert!(!episode.failed());
+
+        // A segment that could not be prepared fails on every target
+        let publications = publishers.failed(&anyhow!("ffmpeg failed"));
+        assert_eq!(publications.len(), 2);
+        assert_eq!(publications[1].target, "up");
+        assert_eq!(publications[1].error.as_deref(), Some("ffmpeg failed"));
+        assert!(Episode {
+            publications,
+            ..Default::default()
+        }
+        .failed());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_checks_files_without_publishing() {
+        let publishers = Publishers::new(
+            vec![Box::new(FakePublisher {
+                name: "down",
+                fail: true,
+            })],
+            true,
+        );
+        let mut episode = Episode {
+            audio_path: "Cargo.toml".to_string(),
+            ..Default::default()
+        };
+
+        let publications = publishers.publish_all(&episode).await;
+        assert!(publications[0].succeeded());
+        assert!(publications[0].dry_run);
+
+        episode.artwork = Some("missing.jpg".to_string());
+        let publications = publishers.publish_all(&episode).await;
+        assert_eq!(
+            publications[0].error.as_deref(),
+            Some("File not found: missing.jpg")
+        );
+    }
+
+    #[test]
+    fn test_status_matches_label() {
+        assert!(EpisodeStatus::Published.matches_label("Published"));
+        assert!(EpisodeStatus::Scheduled(at(2026, 10, 18, 18, 0)).matches_label("Scheduled"));
+        assert!(!EpisodeStatus::Published.matches_label("Draft"));
+        assert!(EpisodeStatus::Scheduled(at(2026, 10, 25, 18, 0))
+            .matches_label("Scheduled for 10/25/2026 06:00 PM"));
+        // Titles containing a status word are not statuses
+        assert!(!EpisodeStatus::Published.matches_label("Alive in Christ"));
+        assert!(!EpisodeStatus::Published.matches_label("Living by Faith Draft"));
+        assert!(!EpisodeStatus::Draft.matches_label("Drafted into His Army"));
+        assert_eq!(EpisodeStatus::Draft.publish_at(), None);
+    }
+}
@@ -0,0 +1,531 @@
+//! Read-only commands that list recorded episodes.
+//!
+//! ```text
+//! audio_epistles search <passage>
+//! audio_epistles history [--speaker <name>] [--limit <n>]
+//! audio_epistles changes <video_id>
+//! ```
+//!
+//! `search` finds the episodes that reference a book of the Bible or one of its
+//! chapters, e.g. `search Romans` or `search Rom 8`, from the scripture references
+//! recorded with each upload (see `scripture.rs`). `history` lists the latest
+//! episodes, optionally only those of one speaker (see `speaker.rs`). Results are
+//! printed one episode per line, most recent first.
+//!
+//! `changes` lists the edits, audio replacements and deletions made to the
+//! episodes of a video with the management commands (see `manage.rs`), oldest
+//! first, from the `episode_changes` audit table.
+
+use anyhow::{anyhow, bail, Result};
+use sqlx::SqlitePool;
+
+use crate::db::{self, ChangeRecord, PublicationRecord, UploadRecord};
+use crate::scripture;
+
+/// A query parsed from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Episodes referencing a book, or a chapter of it
+    Search {
+        book: &'static str,
+        chapter: Option<u32>,
+    },
+    /// The latest episodes, optionally of one speaker
+    History { speaker: Option<String>, limit: u32 },
+    /// The audited changes to the episodes of a video
+    Changes { video_id: String },
+}
+
+/// Episodes listed by `history` without `--limit`
+pub const DEFAULT_HISTORY_LIMIT: u32 = 20;
+
+/// Characters of a changed value shown by `changes` before it is cut off
+const VALUE_PREVIEW_LEN: usize = 60;
+
+impl Query {
+    /// Parses a query from the program arguments (without the program name).
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if the first argument is not a query command, so that it can be
+    /// parsed as a management command (see `manage.rs`) or run the regular pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with usage if the passage is missing or not a book of the Bible,
+    /// an option of `history` is unknown or has an invalid value, or `changes` is not
+    /// given exactly one video ID.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use audio_epistles::query::Query;
+    ///
+    /// let args = ["search", "Rom", "8"].map(String::from);
+    /// assert_eq!(
+    ///     Query::parse(&args).unwrap(),
+    ///     Some(Query::Search { book: "Romans", chapter: Some(8) })
+    /// );
+    /// ```
+    pub fn parse(args: &[String]) -> Result<Option<Self>> {
+        match args.first().map(String::as_str) {
+            Some("search") => {
+                let passage = args[1..].join(" ");
+                if passage.trim().is_empty() {
+                    bail!("search needs a book or passage, e.g. \"Romans 8\"\n\n{USAGE}");
+                }
+                let (book, chapter) = parse_passage(&passage)?;
+                Ok(Some(Self::Search { book, chapter }))
+            }
+            Some("history") => {
+                let mut speaker = None;
+                let mut limit = DEFAULT_HISTORY_LIMIT;
+                let mut rest = args[1..].iter();
+                while let Some(arg) = rest.next() {
+                    let value = rest
+                        .next()
+                        .ok_or_else(|| anyhow!("Missing value for {arg}\n\n{USAGE}"));
+                    match arg.as_str() {
+                        "--speaker" => speaker = Some(value?.clone()),
+                        "--limit" => {
+                            let value = value?;
+                            limit = value
+                                .parse()
+                                .ok()
+                                .filter(|limit| *limit > 0)
+                                .ok_or_else(|| anyhow!("Invalid --limit: {value}"))?;
+                        }
+                        other => bail!("Unknown option for history: {other}\n\n{USAGE}"),
+                    }
+                }
+                Ok(Some(Self::History { speaker, limit }))
+            }
+            Some("changes") => match &args[1..] {
+                [video_id] if !video_id.starts_with("--") => Ok(Some(Self::Changes {
+                    video_id: video_id.clone(),
+                })),
+                _ => bail!("changes needs one video ID\n\n{USAGE}"),
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Usage of the query commands, shown with argument errors
+pub(crate) const USAGE: &str = "Usage:
+  audio_epistles search <passage>
+  audio_epistles history [--speaker <name>] [--limit <n>]
+  audio_epistles changes <video_id>";
+
+/// Reads the book and chapter searched for, e.g. "Rom 8:28" or "1 Corinthians".
+fn parse_passage(passage: &str) -> Result<(&'static str, Option<u32>)> {
+    if let Some(reference) = scripture::parse_references(passage).first() {
+        return Ok((reference.book, Some(reference.chapter)));
+    }
+    scripture::find_book(passage)
+        .map(|book| (book, None))
+        .ok_or_else(|| anyhow!("Not a book or passage of the Bible: {passage}"))
+}
+
+/// Formats an episode as one line: upload time, video ID, segment, title, speaker,
+/// series, references, publish status (e.g. `scheduled for 2026-10-18 18:00:00`) and
+/// the targets it failed on, with the error and diagnostics folder.
+fn format_upload(
+    upload: &UploadRecord,
+    refs: &[String],
+    publications: &[PublicationRecord],
+) -> String {
+    let mut line = format!("{}  {}", upload.uploaded_at, upload.video_id);
+    if let Some(segment) = upload.segment.as_deref().filter(|s| !s.is_empty()) {
+        line.push_str(&format!(" ({segment})"));
+    }
+    line.push_str(&format!(
+        "  {}",
+        upload.title.as_deref().unwrap_or("(untitled)")
+    ));
+    if let Some(speaker) = &upload.speaker {
+        line.push_str(&format!(" - {speaker}"));
+    }
+    if let Some(series) = upload.series.as_deref().filter(|s| !s.is_empty()) {
+        line.push_str(&format!(" ({series})"));
+    }
+    if !refs.is_empty() {
+        line.push_str(&format!("  [{}]", refs.join("; ")));
+    }
+    if let Some(status) = &upload.status {
+        line.push_str(&format!("  {status}"));
+        if let Some(publish_at) = &upload.publish_at {
+            line.push_str(&format!(" for {publish_at}"));
+        }
+    }
+    for publication in publications {
+        if let Some(error) = &publication.error {
+            line.push_str(&format!("  {} FAILED: {error}", publication.target));
+            if let Some(diagnostics) = &publication.diagnostics {
+                line.push_str(&format!(" (diagnostics: {diagnostics})"));
+            }
+        }
+    }
+    line
+}
+
+/// Formats an audited change as one line: time, segment, target, action, changed
+/// value and outcome, e.g. `2026-10-18 18:00:00  sermon  spotify edit title: "Old" -> "New"`.
+fn format_change(change: &ChangeRecord, segment: Option<&str>) -> String {
+    let mut line = change.created_at.clone();
+    if let Some(segment) = segment.filter(|s| !s.is_empty()) {
+        line.push_str(&format!("  {segment}"));
+    }
+    line.push_str(&format!("  {} {}", change.target, change.action));
+    if let Some(field) = &change.field {
+        let value = |value: &Option<String>| match value {
+            Some(value) => format!("\"{}\"", preview(value)),
+            None => "(none)".to_string(),
+        };
+        line.push_str(&format!(
+            " {field}: {} -> {}",
+            value(&change.old_value),
+            value(&change.new_value)
+        ));
+    }
+    if let Some(error) = &change.error {
+        line.push_str(&format!("  FAILED: {error}"));
+        if let Some(diagnostics) = &change.diagnostics {
+            line.push_str(&format!(" (diagnostics: {diagnostics})"));
+        }
+    }
+    line
+}
+
+/// Shortens a changed value to one line of at most `VALUE_PREVIEW_LEN` characters.
+fn preview(value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.chars().count() <= VALUE_PREVIEW_LEN {
+        return value;
+    }
+    let cut: String = value.chars().take(VALUE_PREVIEW_LEN).collect();
+    format!("{}...", cut.trim_end())
+}
+
+/// Returns the lines printed for a query.
+///
+/// # Errors
+///
+/// Returns an error if a database query fails.
+async fn lines(pool: &SqlitePool, query: &Query) -> Result<Vec<String>> {
+    match query {
+        Query::Search { book, chapter } => {
+            let mut lines = Vec::new();
+            for upload in db::find_uploads_by_book(pool, book, *chapter).await? {
+                let refs = db::get_scripture_refs(pool, upload.id).await?;
+                let publications = db::get_publications(pool, upload.id).await?;
+                lines.push(format_upload(&upload, &refs, &publications));
+            }
+            if lines.is_empty() {
+                let passage = match chapter {
+                    Some(chapter) => format!("{book} {chapter}"),
+                    None => book.to_string(),
+                };
+                lines.push(format!("No episodes reference {passage}"));
+            }
+            Ok(lines)
+        }
+        Query::History { speaker, limit } => {
+            let uploads = match speaker {
+                Some(speaker) => db::get_upload_history_by_speaker(pool, speaker, *limit).await?,
+                None => db::get_upload_history(pool, *limit).await?,
+            };
+            let mut lines = Vec::new();
+            for upload in &uploads {
+                let refs = db::get_scripture_refs(pool, upload.id).await?;
+                let publications = db::get_publications(pool, upload.id).await?;
+                lines.push(format_upload(upload, &refs, &publications));
+            }
+            if lines.is_empty() {
+                lines.push(match speaker {
+                    Some(speaker) => format!("No episodes recorded for {speaker}"),
+                    None => "No episodes recorded".to_string(),
+                });
+            }
+            Ok(lines)
+        }
+        Query::Changes { video_id } => {
+            let uploads = db::get_uploads_by_video(pool, video_id).await?;
+            if uploads.is_empty() {
+                bail!("No upload found for video {video_id}");
+            }
+            // Segments are only named when the video produced several episodes
+            let named = uploads.len() > 1;
+            let mut lines = Vec::new();
+            for upload in &uploads {
+                let segment = upload.segment.as_deref().filter(|_| named);
+                for change in db::get_changes(pool, upload.id).await? {
+                    lines.push(format_change(&change, segment));
+                }
+            }
+            if lines.is_empty() {
+                lines.push(format!("No changes recorded for video {video_id}"));
+            }
+            Ok(lines)
+        }
+    }
+}
+
+/// Runs a query and prints its results to standard output.
+///
+/// # Errors
+///
+/// Returns an error if a database query fails, or no upload of the video asked for
+/// by `changes` is recorded.
+pub async fn run(pool: &SqlitePool, query: &Query) -> Result<()> {
+    for line in lines(pool, query).await? {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::Episode;
+    use crate::publish::{EpisodeStatus, Publication, Published};
+    use chrono::NaiveDate;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    async fn test_db(episodes: &[Episode]) -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        db::init(&pool).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        for episode in episodes {
+            db::save_episode(&mut tx, episode).await.unwrap();
+        }
+        tx.commit().await.unwrap();
+        pool
+    }
+
+    #[test]
+    fn test_parse_search() {
+        assert_eq!(
+            Query::parse(&args(&["search", "Rom", "8:28"])).unwrap(),
+            Some(Query::Search {
+                book: "Romans",
+                chapter: Some(8)
+            })
+        );
+        assert_eq!(
+            Query::parse(&args(&["search", "1st Cor."])).unwrap(),
+            Some(Query::Search {
+                book: "1 Corinthians",
+                chapter: None
+            })
+        );
+        assert!(Query::parse(&args(&["search"])).is_err());
+        assert!(Query::parse(&args(&["search", "Faith"])).is_err());
+        assert_eq!(Query::parse(&args(&["delete", "abc123"])).unwrap(), None);
+        assert_eq!(Query::parse(&[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_history() {
+        assert_eq!(
+            Query::parse(&args(&["history"])).unwrap(),
+            Some(Query::History {
+                speaker: None,
+                limit: DEFAULT_HISTORY_LIMIT
+            })
+        );
+        assert_eq!(
+            Query::parse(&args(&["history", "--speaker", "Jane Doe", "--limit", "5"])).unwrap(),
+            Some(Query::History {
+                speaker: Some("Jane Doe".to_string()),
+                limit: 5
+            })
+        );
+        assert!(Query::parse(&args(&["history", "--speaker"])).is_err());
+        assert!(Query::parse(&args(&["history", "--limit", "0"])).is_err());
+        assert!(Query::parse(&args(&["history", "--series", "Faith"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_changes() {
+        assert_eq!(
+            Query::parse(&args(&["changes", "dQw4w9WgXcQ"])).unwrap(),
+            Some(Query::Changes {
+                video_id: "dQw4w9WgXcQ".to_string()
+            })
+        );
+        assert!(Query::parse(&args(&["changes"])).is_err());
+        assert!(Query::parse(&args(&["changes", "abc", "def"])).is_err());
+        assert!(Query::parse(&args(&["changes", "--segment"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_changes() {
+        let episode = |segment: &str| Episode {
+            video_id: "abc".to_string(),
+            title: "Walking by Faith".to_string(),
+            segment: segment.to_string(),
+            ..Default::default()
+        };
+        let pool = test_db(&[episode("sermon"), episode("testimony")]).await;
+        let uploads = db::get_uploads_by_video(&pool, "abc").await.unwrap();
+
+        let changes = Query::Changes {
+            video_id: "abc".to_string(),
+        };
+        assert_eq!(
+            lines(&pool, &changes).await.unwrap(),
+            ["No changes recorded for video abc"]
+        );
+
+        let mut tx = pool.begin().await.unwrap();
+        db::save_change(
+            &mut tx,
+            &ChangeRecord {
+                upload_id: uploads[0].id,
+                target: "spotify".to_string(),
+                action: "edit".to_string(),
+                field: Some("description".to_string()),
+                old_value: Some("Join us\nonline ".repeat(10)),
+                new_value: Some("Join us online.".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        db::save_change(
+            &mut tx,
+            &ChangeRecord {
+                upload_id: uploads[1].id,
+                target: "spotify".to_string(),
+                action: "delete".to_string(),
+                error: Some("Episode not found".to_string()),
+                diagnostics: Some("diagnostics/20261018-180000-delete".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        let found = lines(&pool, &changes).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found[0].ends_with(
+            "  sermon  spotify edit description: \"Join us online Join us online Join us online Join us online...\" -> \"Join us online.\""
+        ));
+        assert!(found[1].ends_with(
+            "  testimony  spotify delete  FAILED: Episode not found (diagnostics: diagnostics/20261018-180000-delete)"
+        ));
+
+        let missing = Query::Changes {
+            video_id: "missing".to_string(),
+        };
+        assert!(lines(&pool, &missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_history_by_speaker() {
+        let episode = |video_id: &str, speaker: &str, series: Option<&str>| Episode {
+            video_id: video_id.to_string(),
+            title: format!("Sermon {video_id}"),
+            speaker: Some(speaker.to_string()),
+            series: series.map(String::from),
+            ..Default::default()
+        };
+        let pool = test_db(&[
+            Episode {
+                publications: vec![Publication {
+                    target: "spotify".to_string(),
+                    error: Some("Login failed".to_string()),
+                    diagnostics: Some("diagnostics/20261018-180000-login".to_string()),
+                    ..Default::default()
+                }],
+                ..episode("abc", "Pastor Kofi Mensah", None)
+            },
+            episode("def", "Rev. Jane Doe", None),
+            Episode {
+                publications: vec![
+                    Publication {
+                        target: "spotify".to_string(),
+                        published: Some(Published {
+                            status: EpisodeStatus::Scheduled(
+                                NaiveDate::from_ymd_opt(2026, 10, 18)
+                                    .unwrap()
+                                    .and_hms_opt(18, 0, 0)
+                                    .unwrap(),
+                            ),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    Publication {
+                        target: "rss".to_string(),
+                        error: Some("Feed directory not writable".to_string()),
+                        ..Default::default()
+                    },
+                ],
+                ..episode("ghi", "Pastor Kofi Mensah", Some("Walking by Faith"))
+            },
+        ])
+        .await;
+
+        let history = |speaker: Option<&str>, limit| Query::History {
+            speaker: speaker.map(String::from),
+            limit,
+        };
+        let found = lines(&pool, &history(Some("pastor kofi mensah"), 10))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found[0].ends_with(
+            "ghi  Sermon ghi - Pastor Kofi Mensah (Walking by Faith)  scheduled for 2026-10-18 18:00:00  rss FAILED: Feed directory not writable"
+        ));
+        assert!(found[1].ends_with(
+            "abc  Sermon abc - Pastor Kofi Mensah  spotify FAILED: Login failed (diagnostics: diagnostics/20261018-180000-login)"
+        ));
+
+        assert_eq!(lines(&pool, &history(None, 2)).await.unwrap().len(), 2);
+        assert_eq!(
+            lines(&pool, &history(Some("Guest"), 10)).await.unwrap(),
+            ["No episodes recorded for Guest"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let episode = |video_id: &str, title: &str, text: &str| Episode {
+            video_id: video_id.to_string(),
+            title: title.to_string(),
+            scripture: scripture::parse_references(text),
+            ..Default::default()
+        };
+        let pool = test_db(&[
+            episode("abc", "Walking by Faith", "Heb 11:1-6; Rom 10:17"),
+            episode("def", "More than Conquerors", "Romans 8:28-39"),
+        ])
+        .await;
+
+        let search = |book, chapter| Query::Search { book, chapter };
+        let found = lines(&pool, &search("Romans", None)).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found[0].contains("def  More than Conquerors  [Romans 8:28-39]"));
+        assert!(found[1].contains("abc  Walking by Faith  [Hebrews 11:1-6; Romans 10:17]"));
+
+        let found = lines(&pool, &search("Romans", Some(8))).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("More than Conquerors"));
+
+        assert_eq!(
+            lines(&pool, &search("Genesis", Some(1))).await.unwrap(),
+            ["No episodes reference Genesis 1"]
+        );
+    }
+}
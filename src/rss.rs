@@ -0,0 +1,686 @@
+//! Self-hosted podcast feed module.
+//!
+//! The `rss` publisher copies each episode's audio and artwork into a static output
+//! directory, and `write_feed` renders an RSS 2.0 feed with the iTunes and
+//! Podcasting 2.0 namespaces from the episode history in the database. The directory
+//! can be served by any web server at `FEED_BASE_URL`.
+//!
+//! Layout of the output directory:
+//!
+//! ```text
+//! feed.xml
+//! artwork.jpg                    # FEED_ARTWORK, if set
+//! episodes/<guid>.mp3
+//! episodes/<guid>.jpg            # episode artwork, if any
+//! episodes/<guid>.chapters.json  # Podcasting 2.0 chapters, if any
+//! episodes/<guid>.vtt|.srt|.json # transcripts, if any
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sqlx::SqlitePool;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::chapters;
+use crate::db::{self, UploadRecord};
+use crate::episode::Episode;
+use crate::publish::{EpisodeStatus, Published, Publisher};
+
+/// Output directory used when `FEED_DIR` is not set
+pub const DEFAULT_FEED_DIR: &str = "public";
+
+/// Maximum number of episodes listed in the feed
+const MAX_FEED_ITEMS: u32 = 300;
+
+/// Transcript formats linked from each item: (file extension, database format, MIME type)
+const TRANSCRIPT_FORMATS: [(&str, &str, &str); 3] = [
+    ("vtt", "vtt", "text/vtt"),
+    ("srt", "srt", "application/x-subrip"),
+    ("json", "json", "application/json"),
+];
+
+/// Channel-level settings of the feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedConfig {
+    /// Directory the feed and media files are written to
+    pub dir: PathBuf,
+    /// Public URL the directory is served at, without a trailing slash
+    pub base_url: String,
+    pub title: String,
+    pub description: String,
+    /// Podcast author, also used as the iTunes owner name
+    pub author: String,
+    /// iTunes owner email, if any
+    pub email: Option<String>,
+    pub language: String,
+    /// Path to the podcast cover art, if any
+    pub artwork: Option<String>,
+    /// iTunes category and optional subcategory, e.g. ("Religion & Spirituality", "Christianity")
+    pub category: (String, Option<String>),
+    pub explicit: bool,
+}
+
+impl FeedConfig {
+    /// Reads the feed settings from the environment.
+    ///
+    /// - `FEED_BASE_URL` (required): public URL of the output directory
+    /// - `FEED_DIR`: output directory (default `public`)
+    /// - `FEED_TITLE`, `FEED_DESCRIPTION`, `FEED_AUTHOR`, `FEED_EMAIL`, `FEED_LANGUAGE`
+    /// - `FEED_ARTWORK`: path to the podcast cover art
+    /// - `FEED_CATEGORY`: `Category>Subcategory` (default `Religion & Spirituality>Christianity`)
+    /// - `FEED_EXPLICIT`: `true` if the podcast is explicit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `FEED_BASE_URL` is not set.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let base_url = var("FEED_BASE_URL").context("FEED_BASE_URL must be set")?;
+        let category = var("FEED_CATEGORY")
+            .unwrap_or_else(|| "Religion & Spirituality>Christianity".to_string());
+        let category = match category.split_once('>') {
+            Some((main, sub)) => (main.trim().to_string(), Some(sub.trim().to_string())),
+            None => (category.trim().to_string(), None),
+        };
+
+        Ok(Self {
+            dir: PathBuf::from(var("FEED_DIR").unwrap_or_else(|| DEFAULT_FEED_DIR.to_string())),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            title: var("FEED_TITLE").unwrap_or_else(|| "Audio Epistles".to_string()),
+            description: var("FEED_DESCRIPTION").unwrap_or_else(|| "Sunday sermons".to_string()),
+            author: var("FEED_AUTHOR").unwrap_or_default(),
+            email: var("FEED_EMAIL"),
+            language: var("FEED_LANGUAGE").unwrap_or_else(|| "en-us".to_string()),
+            artwork: var("FEED_ARTWORK"),
+            category,
+            explicit: var("FEED_EXPLICIT").is_some_and(|v| v.eq_ignore_ascii_case("true")),
+        })
+    }
+
+    /// Returns the public URL of a file relative to the output directory.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.base_url)
+    }
+
+    /// Returns the path, relative to the output directory, of an episode file.
+    pub fn episode_file(guid: &str, extension: &str) -> String {
+        format!("episodes/{guid}.{extension}")
+    }
+}
+
+/// An episode as listed in the feed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedItem {
+    pub guid: String,
+    pub title: String,
+    pub description: String,
+    /// Publication date in RFC 2822 format
+    pub pub_date: String,
+    /// Link to the original video
+    pub link: String,
+    pub audio_url: String,
+    /// Audio file size in bytes
+    pub audio_length: u64,
+    /// Audio length in seconds
+    pub duration: u32,
+    pub author: Option<String>,
+    pub artwork_url: Option<String>,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+    /// `full`, `trailer` or `bonus`; `full` if empty
+    pub episode_type: String,
+    /// Overrides the channel's explicit flag
+    pub explicit: Option<bool>,
+    pub chapters_url: Option<String>,
+    /// `(URL, MIME type)` of each transcript
+    pub transcripts: Vec<(String, String)>,
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders the feed XML.
+///
+/// # Arguments
+///
+/// * `config` - The channel settings
+/// * `items` - The episodes, most recent first
+/// * `build_date` - The feed build date in RFC 2822 format
+///
+/// # Example
+///
+/// ```no_run
+/// let config = audio_epistles::rss::FeedConfig::from_env().unwrap();
+/// let xml = audio_epistles::rss::render_feed(&config, &[], "Sun, 18 Oct 2026 18:00:00 +0000");
+/// assert!(xml.contains("<rss version=\"2.0\""));
+/// ```
+pub fn render_feed(config: &FeedConfig, items: &[FeedItem], build_date: &str) -> String {
+    let explicit = |explicit: bool| if explicit { "true" } else { "false" };
+    let mut xml = String::new();
+
+    // Writing to a String never fails
+    let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd" xmlns:podcast="https://podcastindex.org/namespace/1.0" xmlns:atom="http://www.w3.org/2005/Atom">"#
+    );
+    let _ = writeln!(xml, "  <channel>");
+    let _ = writeln!(xml, "    <title>{}</title>", escape(&config.title));
+    let _ = writeln!(xml, "    <link>{}</link>", escape(&config.base_url));
+    let _ = writeln!(
+        xml,
+        r#"    <atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        escape(&config.url("feed.xml"))
+    );
+    let _ = writeln!(
+        xml,
+        "    <description>{}</description>",
+        escape(&config.description)
+    );
+    let _ = writeln!(xml, "    <language>{}</language>", escape(&config.language));
+    let _ = writeln!(xml, "    <lastBuildDate>{build_date}</lastBuildDate>");
+    let _ = writeln!(xml, "    <generator>audio_epistles</generator>");
+    let _ = writeln!(
+        xml,
+        "    <itunes:author>{}</itunes:author>",
+        escape(&config.author)
+    );
+    let _ = writeln!(xml, "    <itunes:owner>");
+    let _ = writeln!(
+        xml,
+        "      <itunes:name>{}</itunes:name>",
+        escape(&config.author)
+    );
+    if let Some(email) = &config.email {
+        let _ = writeln!(xml, "      <itunes:email>{}</itunes:email>", escape(email));
+    }
+    let _ = writeln!(xml, "    </itunes:owner>");
+    if let Some(artwork) = &config.artwork {
+        let url = escape(&config.url(&channel_artwork_file(artwork)));
+        let _ = writeln!(xml, r#"    <itunes:image href="{url}"/>"#);
+        let _ = writeln!(
+            xml,
+            "    <image>\n      <url>{url}</url>\n      <title>{}</title>\n      <link>{}</link>\n    </image>",
+            escape(&config.title),
+            escape(&config.base_url)
+        );
+    }
+    let (category, subcategory) = &config.category;
+    match subcategory {
+        Some(sub) => {
+            let _ = writeln!(
+                xml,
+                "    <itunes:category text=\"{}\">\n      <itunes:category text=\"{}\"/>\n    </itunes:category>",
+                escape(category),
+                escape(sub)
+            );
+        }
+        None => {
+            let _ = writeln!(xml, r#"    <itunes:category text="{}"/>"#, escape(category));
+        }
+    }
+    let _ = writeln!(
+        xml,
+        "    <itunes:explicit>{}</itunes:explicit>",
+        explicit(config.explicit)
+    );
+    let _ = writeln!(xml, "    <itunes:type>episodic</itunes:type>");
+
+    for item in items {
+        let _ = writeln!(xml, "    <item>");
+        let _ = writeln!(xml, "      <title>{}</title>", escape(&item.title));
+        let _ = writeln!(
+            xml,
+            "      <itunes:title>{}</itunes:title>",
+            escape(&item.title)
+        );
+        let _ = writeln!(
+            xml,
+            "      <description>{}</description>",
+            escape(&item.description)
+        );
+        let _ = writeln!(xml, "      <link>{}</link>", escape(&item.link));
+        let _ = writeln!(
+            xml,
+            r#"      <guid isPermaLink="false">{}</guid>"#,
+            escape(&item.guid)
+        );
+        let _ = writeln!(xml, "      <pubDate>{}</pubDate>", item.pub_date);
+        let _ = writeln!(
+            xml,
+            r#"      <enclosure url="{}" length="{}" type="audio/mpeg"/>"#,
+            escape(&item.audio_url),
+            item.audio_length
+        );
+        let _ = writeln!(
+            xml,
+            "      <itunes:duration>{}</itunes:duration>",
+            item.duration
+        );
+        if let Some(author) = &item.author {
+            let _ = writeln!(
+                xml,
+                "      <itunes:author>{}</itunes:author>",
+                escape(author)
+            );
+        }
+        if let Some(url) = &item.artwork_url {
+            let _ = writeln!(xml, r#"      <itunes:image href="{}"/>"#, escape(url));
+        }
+        if let Some(season) = item.season {
+            let _ = writeln!(xml, "      <itunes:season>{season}</itunes:season>");
+        }
+        if let Some(episode) = item.episode {
+            let _ = writeln!(xml, "      <itunes:episode>{episode}</itunes:episode>");
+        }
+        let episode_type = match item.episode_type.as_str() {
+            "" => "full",
+            episode_type => episode_type,
+        };
+        let _ = writeln!(
+            xml,
+            "      <itunes:episodeType>{}</itunes:episodeType>",
+            escape(episode_type)
+        );
+        let _ = writeln!(
+            xml,
+            "      <itunes:explicit>{}</itunes:explicit>",
+            explicit(item.explicit.unwrap_or(config.explicit))
+        );
+        if let Some(url) = &item.chapters_url {
+            let _ = writeln!(
+                xml,
+                r#"      <podcast:chapters url="{}" type="application/json+chapters"/>"#,
+                escape(url)
+            );
+        }
+        for (url, mime) in &item.transcripts {
+            let _ = writeln!(
+                xml,
+                r#"      <podcast:transcript url="{}" type="{mime}"/>"#,
+                escape(url)
+            );
+        }
+        let _ = writeln!(xml, "    </item>");
+    }
+
+    let _ = writeln!(xml, "  </channel>");
+    let _ = writeln!(xml, "</rss>");
+
+    xml
+}
+
+/// Returns the file name of the podcast cover art in the output directory.
+fn channel_artwork_file(artwork: &str) -> String {
+    format!("artwork.{}", extension(artwork, "jpg"))
+}
+
+/// Returns the lowercase extension of `path`, or `default` if it has none.
+fn extension(path: &str, default: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Converts an SQLite UTC timestamp ("2026-10-18 18:00:00") to RFC 2822.
+fn rfc2822(timestamp: &str) -> Option<String> {
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|t| t.and_utc().to_rfc2822())
+}
+
+/// Copies a file, creating its parent directory if needed.
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+    Ok(())
+}
+
+/// Builds the feed item of a published upload, writing its chapters and transcripts
+/// to the output directory.
+///
+/// Returns `None` if the upload's audio is missing from the output directory.
+async fn feed_item(
+    pool: &SqlitePool,
+    config: &FeedConfig,
+    upload: &UploadRecord,
+    audio_url: Option<String>,
+) -> Result<Option<FeedItem>> {
+    let guid = upload
+        .guid
+        .clone()
+        .unwrap_or_else(|| upload.video_id.clone());
+    let audio_file = FeedConfig::episode_file(&guid, "mp3");
+    let audio_length = match fs::metadata(config.dir.join(&audio_file)) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => {
+            warn!(guid = %guid, "Episode audio missing from feed directory, skipping");
+            return Ok(None);
+        }
+    };
+
+    let chapters = db::get_chapters(pool, upload.id).await?;
+    let chapters_url = if chapters.is_empty() {
+        None
+    } else {
+        let file = FeedConfig::episode_file(&guid, "chapters.json");
+        fs::write(config.dir.join(&file), chapters::to_json(&chapters))?;
+        Some(config.url(&file))
+    };
+
+    let mut transcripts = Vec::new();
+    for (ext, format, mime) in TRANSCRIPT_FORMATS {
+        if let Some(transcript) = db::get_transcript(pool, upload.id, format).await? {
+            let file = FeedConfig::episode_file(&guid, ext);
+            fs::write(config.dir.join(&file), transcript)?;
+            transcripts.push((config.url(&file), mime.to_string()));
+        }
+    }
+
+    let artwork_url = upload
+        .artwork
+        .as_deref()
+        .map(|artwork| config.url(&FeedConfig::episode_file(&guid, &extension(artwork, "jpg"))));
+
+    Ok(Some(FeedItem {
+        title: upload.title.clone().unwrap_or_else(|| guid.clone()),
+        description: upload.description.clone().unwrap_or_default(),
+        pub_date: rfc2822(&upload.uploaded_at).unwrap_or_default(),
+        link: format!("https://www.youtube.com/watch?v={}", upload.video_id),
+        audio_url: audio_url.unwrap_or_else(|| config.url(&audio_file)),
+        audio_length,
+        duration: upload.duration_secs.unwrap_or(0),
+        author: upload.speaker.clone(),
+        artwork_url,
+        season: upload.season,
+        episode: upload.episode_number,
+        episode_type: upload.episode_type.clone().unwrap_or_default(),
+        explicit: upload.explicit,
+        chapters_url,
+        transcripts,
+        guid,
+    }))
+}
+
+/// Regenerates the feed from every upload published by the `rss` publisher.
+///
+/// Episodes also published by the `s3` publisher use the bucket URL of their audio
+/// as the enclosure.
+///
+/// Chapters and transcripts are written next to each episode's audio, and the
+/// podcast cover art is copied into the output directory.
+///
+/// # Arguments
+///
+/// * `pool` - A reference to the SQLite connection pool
+/// * `config` - The feed settings
+///
+/// # Returns
+///
+/// Returns the path of the written `feed.xml`.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails or any file cannot be written.
+///
+/// # Example
+///
+/// ```no_run
+/// # use sqlx::SqlitePool;
+/// # tokio_test::block_on(async {
+/// let pool = SqlitePool::connect("videos.db").await.unwrap();
+/// let config = audio_epistles::rss::FeedConfig::from_env().unwrap();
+/// let feed = audio_epistles::rss::write_feed(&pool, &config).await.unwrap();
+/// println!("Feed written to {}", feed.display());
+/// # })
+/// ```
+pub async fn write_feed(pool: &SqlitePool, config: &FeedConfig) -> Result<PathBuf> {
+    fs::create_dir_all(config.dir.join("episodes"))?;
+
+    let mut items = Vec::new();
+    for (upload, publication) in db::get_published_uploads(pool, "rss", MAX_FEED_ITEMS).await? {
+        // Audio also uploaded to object storage is served from the bucket
+        let s3_url = db::get_publications(pool, upload.id)
+            .await?
+            .into_iter()
+            .find(|p| p.target == "s3" && p.status.is_some())
+            .and_then(|p| p.url);
+        let audio_url = s3_url.or(publication.url);
+        if let Some(item) = feed_item(pool, config, &upload, audio_url).await? {
+            items.push(item);
+        }
+    }
+
+    if let Some(artwork) = &config.artwork {
+        copy_file(
+            Path::new(artwork),
+            &config.dir.join(channel_artwork_file(artwork)),
+        )?;
+    }
+
+    let feed_path = config.dir.join("feed.xml");
+    let xml = render_feed(config, &items, &Utc::now().to_rfc2822());
+    fs::write(&feed_path, xml)?;
+    info!(items = items.len(), path = %feed_path.display(), "Feed written successfully");
+
+    Ok(feed_path)
+}
+
+/// Publishes episodes by copying their audio and artwork into the feed directory.
+///
+/// The feed itself is regenerated from the database by `write_feed` once the run's
+/// episodes are recorded.
+#[derive(Debug, Clone)]
+pub struct RssPublisher {
+    pub config: FeedConfig,
+}
+
+impl RssPublisher {
+    /// Builds the publisher from the `FEED_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `FEED_BASE_URL` is not set.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            config: FeedConfig::from_env()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for RssPublisher {
+    fn name(&self) -> &str {
+        "rss"
+    }
+
+    async fn publish(&self, episode: &Episode) -> Result<Published> {
+        if episode.guid.is_empty() {
+            return Err(anyhow!("Episode has no GUID"));
+        }
+
+        let audio_file = FeedConfig::episode_file(&episode.guid, "mp3");
+        copy_file(
+            Path::new(&episode.audio_path),
+            &self.config.dir.join(&audio_file),
+        )?;
+
+        if let Some(artwork) = &episode.artwork {
+            let file = FeedConfig::episode_file(&episode.guid, &extension(artwork, "jpg"));
+            copy_file(Path::new(artwork), &self.config.dir.join(file))?;
+        }
+
+        Ok(Published {
+            status: EpisodeStatus::Published,
+            remote_id: Some(episode.guid.clone()),
+            url: Some(self.config.url(&audio_file)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::Chapter;
+    use crate::publish::Publication;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn config(dir: PathBuf) -> FeedConfig {
+        FeedConfig {
+            dir,
+            base_url: "https://podcast.example.org".to_string(),
+            title: "Audio Epistles".to_string(),
+            description: "Sermons & teachings".to_string(),
+            author: "Dominion Chapel".to_string(),
+            email: Some("media@example.org".to_string()),
+            language: "en-us".to_string(),
+            artwork: None,
+            category: (
+                "Religion & Spirituality".to_string(),
+                Some("Christianity".to_string()),
+            ),
+            explicit: false,
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("Faith & <Works> \"Jas 2\" 'v17'\u{1}"),
+            "Faith &amp; &lt;Works&gt; &quot;Jas 2&quot; &apos;v17&apos;"
+        );
+    }
+
+    #[test]
+    fn test_rfc2822() {
+        assert_eq!(
+            rfc2822("2026-10-18 18:00:00").as_deref(),
+            Some("Sun, 18 Oct 2026 18:00:00 +0000")
+        );
+        assert_eq!(rfc2822("yesterday"), None);
+    }
+
+    #[test]
+    fn test_render_feed() {
+        let item = FeedItem {
+            guid: "abc123-2".to_string(),
+            title: "Faith & Works".to_string(),
+            pub_date: "Sun, 18 Oct 2026 18:00:00 +0000".to_string(),
+            audio_url: "https://podcast.example.org/episodes/abc123-2.mp3".to_string(),
+            audio_length: 1024,
+            duration: 1800,
+            season: Some(2),
+            episode: Some(14),
+            episode_type: "bonus".to_string(),
+            explicit: Some(true),
+            chapters_url: Some("https://podcast.example.org/c.json".to_string()),
+            transcripts: vec![(
+                "https://podcast.example.org/t.vtt".to_string(),
+                "text/vtt".to_string(),
+            )],
+            ..Default::default()
+        };
+        let xml = render_feed(&config(PathBuf::from("public")), &[item], "now");
+
+        assert!(xml.contains(r#"xmlns:podcast="https://podcastindex.org/namespace/1.0""#));
+        assert!(xml.contains("<description>Sermons &amp; teachings</description>"));
+        assert!(xml.contains(r#"<itunes:category text="Religion &amp; Spirituality">"#));
+        assert!(xml.contains("<title>Faith &amp; Works</title>"));
+        assert!(xml.contains(r#"<guid isPermaLink="false">abc123-2</guid>"#));
+        assert!(xml.contains(
+            r#"<enclosure url="https://podcast.example.org/episodes/abc123-2.mp3" length="1024" type="audio/mpeg"/>"#
+        ));
+        assert!(xml.contains("<itunes:duration>1800</itunes:duration>"));
+        assert!(xml.contains("<itunes:season>2</itunes:season>"));
+        assert!(xml.contains("<itunes:episode>14</itunes:episode>"));
+        assert!(xml.contains("<itunes:episodeType>bonus</itunes:episodeType>"));
+        // The channel is not explicit, the episode is
+        assert!(xml.contains("      <itunes:explicit>true</itunes:explicit>"));
+        assert!(xml.contains(r#"<podcast:chapters url="https://podcast.example.org/c.json""#));
+        assert!(xml.contains(
+            r#"<podcast:transcript url="https://podcast.example.org/t.vtt" type="text/vtt"/>"#
+        ));
+        assert!(!xml.contains("<itunes:image"));
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_write_feed() {
+        let dir = env::temp_dir().join(format!("audio_epistles_rss_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let audio = dir.join("audio.mp3");
+        fs::write(&audio, b"ID3 audio").unwrap();
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        db::init(&pool).await.unwrap();
+
+        let publisher = RssPublisher {
+            config: config(dir.join("public")),
+        };
+        let mut episode = Episode {
+            video_id: "abc123".to_string(),
+            guid: "abc123".to_string(),
+            audio_path: audio.to_string_lossy().into_owned(),
+            title: "Walking by Faith".to_string(),
+            duration: 1800,
+            chapters: vec![Chapter {
+                start: 0,
+                end: Some(60),
+                title: "Introduction".to_string(),
+            }],
+            ..Default::default()
+        };
+        let published = publisher.publish(&episode).await.unwrap();
+        assert_eq!(
+            published.url.as_deref(),
+            Some("https://podcast.example.org/episodes/abc123.mp3")
+        );
+
+        episode.publications = vec![Publication {
+            target: "rss".to_string(),
+            published: Some(published),
+            ..Default::default()
+        }];
+        let mut tx = pool.begin().await.unwrap();
+        db::save_episode(&mut tx, &episode).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let feed = write_feed(&pool, &publisher.config).await.unwrap();
+        let xml = fs::read_to_string(feed).unwrap();
+        assert!(xml.contains("<title>Walking by Faith</title>"));
+        assert!(xml.contains(r#"length="9" type="audio/mpeg""#));
+        assert!(xml.contains("episodes/abc123.chapters.json"));
+        assert!(dir.join("public/episodes/abc123.chapters.json").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,819 @@
+//! S3-compatible object storage module.
+//!
+//! The `s3` publisher uploads episode audio and artwork to a bucket on any
+//! S3-compatible service (AWS S3, Backblaze B2, MinIO), and `upload_feed` uploads
+//! the generated feed files. Requests are signed with AWS Signature Version 4; files
+//! larger than the multipart threshold are sent as a multipart upload.
+//!
+//! Objects use the same layout as the feed directory (`episodes/<guid>.mp3`,
+//! `feed.xml`, ...), so the public URLs returned for the audio can be used as feed
+//! enclosures.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, KeyInit, Mac};
+use regex::Regex;
+use reqwest::{Body, Client, Method, StatusCode};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+use tracing::{debug, info};
+
+use crate::episode::Episode;
+use crate::publish::{EpisodeStatus, Published, Publisher};
+use crate::rss::FeedConfig;
+
+/// Files at least this large are uploaded in parts when `S3_MULTIPART_THRESHOLD` is not set
+pub const DEFAULT_MULTIPART_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// Size of each part of a multipart upload (S3 requires at least 5 MiB)
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Cache header of files that never change once uploaded (audio, artwork)
+const CACHE_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache header of files rewritten on every run (feed, chapters, transcripts)
+const CACHE_SHORT: &str = "public, max-age=300";
+
+/// Connection settings of an S3-compatible bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Config {
+    /// Service endpoint, e.g. `https://s3.us-east-1.amazonaws.com` or `http://localhost:9000`
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Public base URL of the bucket, without a trailing slash
+    pub public_url: String,
+    /// Key prefix for every object (e.g. "podcast/"), may be empty
+    pub prefix: String,
+    /// Address the bucket as `endpoint/bucket` instead of `bucket.endpoint`
+    pub path_style: bool,
+    pub multipart_threshold: u64,
+}
+
+impl S3Config {
+    /// Reads the bucket settings from the environment.
+    ///
+    /// - `S3_ENDPOINT`, `S3_BUCKET`, `S3_ACCESS_KEY_ID`, `S3_SECRET_ACCESS_KEY` (required)
+    /// - `S3_REGION`: signing region (default `us-east-1`)
+    /// - `S3_PUBLIC_URL`: public URL of the bucket (default: the bucket URL)
+    /// - `S3_PREFIX`: key prefix for every object
+    /// - `S3_PATH_STYLE`: `false` for virtual-hosted-style addressing (default `true`,
+    ///   as required by MinIO)
+    /// - `S3_MULTIPART_THRESHOLD`: size in bytes from which files are uploaded in parts
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a required variable is missing or the threshold is invalid.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let required = |name: &str| var(name).with_context(|| format!("{name} must be set"));
+
+        let mut config = Self {
+            endpoint: required("S3_ENDPOINT")?.trim_end_matches('/').to_string(),
+            bucket: required("S3_BUCKET")?,
+            region: var("S3_REGION").unwrap_or_else(|| "us-east-1".to_string()),
+            access_key_id: required("S3_ACCESS_KEY_ID")?,
+            secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+            public_url: String::new(),
+            prefix: var("S3_PREFIX").unwrap_or_default(),
+            path_style: var("S3_PATH_STYLE").is_none_or(|v| !v.eq_ignore_ascii_case("false")),
+            multipart_threshold: match var("S3_MULTIPART_THRESHOLD") {
+                Some(v) => v
+                    .parse()
+                    .with_context(|| format!("Invalid S3_MULTIPART_THRESHOLD: {v}"))?,
+                None => DEFAULT_MULTIPART_THRESHOLD,
+            },
+        };
+        config.public_url = match var("S3_PUBLIC_URL") {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => config.bucket_url(),
+        };
+
+        Ok(config)
+    }
+
+    /// Returns the URL of the bucket on the service endpoint.
+    fn bucket_url(&self) -> String {
+        if self.path_style {
+            return format!("{}/{}", self.endpoint, self.bucket);
+        }
+        match self.endpoint.split_once("://") {
+            Some((scheme, host)) => format!("{scheme}://{}.{host}", self.bucket),
+            None => format!("https://{}.{}", self.bucket, self.endpoint),
+        }
+    }
+
+    /// Returns the full object key of a path in the feed layout.
+    pub fn key(&self, path: &str) -> String {
+        format!("{}{path}", self.prefix)
+    }
+
+    /// Returns the public URL of an object.
+    pub fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, uri_encode(key, false))
+    }
+}
+
+/// Percent-encodes a string as required by Signature Version 4, keeping `/` unless
+/// `encode_slash` is set.
+fn uri_encode(text: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+/// Returns the lowercase hex encoding of `bytes`.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Derives the Signature Version 4 signing key for a date (`YYYYMMDD`).
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+/// Returns the content type of a file from its extension.
+pub fn content_type(path: &str) -> &'static str {
+    let ext = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "xml" => "application/rss+xml",
+        "json" => "application/json",
+        "vtt" => "text/vtt",
+        "srt" => "application/x-subrip",
+        "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A minimal client for the S3 object API.
+#[derive(Debug, Clone)]
+pub struct S3Client {
+    pub config: S3Config,
+    http: Client,
+}
+
+impl S3Client {
+    /// Creates a client for the configured bucket.
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            config,
+            http: Client::new(),
+        }
+    }
+
+    /// Creates a client from the `S3_*` environment variables.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(S3Config::from_env()?))
+    }
+
+    /// Sends a signed request for an object and returns the response if it succeeded.
+    async fn request(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: impl Into<Body>,
+    ) -> Result<reqwest::Response> {
+        let response = self.send(method.clone(), key, query, headers, body).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("S3 {method} {key} failed ({status}): {body}"));
+        }
+        Ok(response)
+    }
+
+    /// Sends a signed request for an object, whatever its response status.
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: impl Into<Body>,
+    ) -> Result<reqwest::Response> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+
+        let bucket_url = self.config.bucket_url();
+        let url = reqwest::Url::parse(&format!("{bucket_url}/{}", uri_encode(key, false)))
+            .with_context(|| format!("Invalid S3 URL: {bucket_url}"))?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let mut sorted_query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        sorted_query.sort();
+        let canonical_query = sorted_query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        // The payload is not hashed so that files can be streamed without reading them twice
+        let payload_hash = "UNSIGNED-PAYLOAD";
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{}\n{canonical_query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+            url.path()
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key_bytes = signing_key(
+            &self.config.secret_access_key,
+            &date,
+            &self.config.region,
+            "s3",
+        );
+        let signature = hex(&hmac_sha256(&key_bytes, &string_to_sign));
+
+        let full_url = if canonical_query.is_empty() {
+            url.to_string()
+        } else {
+            format!("{url}?{canonical_query}")
+        };
+        let mut request = self
+            .http
+            .request(method.clone(), &full_url)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                    self.config.access_key_id
+                ),
+            );
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        Ok(request.body(body).send().await?)
+    }
+
+    /// Uploads an object in a single request.
+    ///
+    /// `length` is sent as the Content-Length, which S3 requires for a streamed body.
+    pub async fn put_object(
+        &self,
+        key: &str,
+        body: impl Into<Body>,
+        length: u64,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<()> {
+        self.request(
+            Method::PUT,
+            key,
+            &[],
+            &[
+                ("Content-Type", content_type),
+                ("Cache-Control", cache_control),
+                ("Content-Length", &length.to_string()),
+            ],
+            body,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Returns true if the bucket already holds an object under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the bucket answers anything but
+    /// found or not found.
+    pub async fn object_exists(&self, key: &str) -> Result<bool> {
+        let response = self.send(Method::HEAD, key, &[], &[], Vec::new()).await?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            status => Err(anyhow!("S3 HEAD {key} failed ({status})")),
+        }
+    }
+
+    /// Uploads a file, using a multipart upload if it is at least the threshold size.
+    ///
+    /// # Returns
+    ///
+    /// Returns the public URL of the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or any request fails; a failed
+    /// multipart upload is aborted so that no parts are left behind.
+    pub async fn upload_file(&self, path: &Path, key: &str, cache_control: &str) -> Result<String> {
+        let content_type = content_type(key);
+        let size = fs::metadata(path)
+            .with_context(|| format!("File not found: {}", path.display()))?
+            .len();
+
+        if size < self.config.multipart_threshold {
+            let file = File::open(path).await?;
+            self.put_object(key, file, size, content_type, cache_control)
+                .await?;
+        } else {
+            let upload_id = self
+                .create_multipart_upload(key, content_type, cache_control)
+                .await?;
+            if let Err(e) = self.upload_parts(path, key, &upload_id).await {
+                // Best effort: the original error is more useful than an abort failure
+                let _ = self
+                    .request(
+                        Method::DELETE,
+                        key,
+                        &[("uploadId", &upload_id)],
+                        &[],
+                        Vec::new(),
+                    )
+                    .await;
+                return Err(e);
+            }
+        }
+
+        debug!(key = %key, size, "Uploaded object");
+        Ok(self.config.public_url(key))
+    }
+
+    /// Starts a multipart upload and returns its upload ID.
+    async fn create_multipart_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<String> {
+        let response = self
+            .request(
+                Method::POST,
+                key,
+                &[("uploads", "")],
+                &[
+                    ("Content-Type", content_type),
+                    ("Cache-Control", cache_control),
+                ],
+                Vec::new(),
+            )
+            .await?;
+        let body = response.text().await?;
+        xml_value(&body, "UploadId").ok_or_else(|| anyhow!("No UploadId in response: {body}"))
+    }
+
+    /// Uploads a file in parts and completes the multipart upload.
+    ///
+    /// The file is read one part at a time, so only one part is held in memory.
+    async fn upload_parts(&self, path: &Path, key: &str, upload_id: &str) -> Result<()> {
+        let mut file = File::open(path).await?;
+        let mut completed = String::from("<CompleteMultipartUpload>");
+
+        for part_number in 1.. {
+            let mut part = Vec::with_capacity(PART_SIZE);
+            (&mut file)
+                .take(PART_SIZE as u64)
+                .read_to_end(&mut part)
+                .await?;
+            if part.is_empty() {
+                break;
+            }
+            let part_number = part_number.to_string();
+            let response = self
+                .request(
+                    Method::PUT,
+                    key,
+                    &[("partNumber", &part_number), ("uploadId", upload_id)],
+                    &[],
+                    part,
+                )
+                .await?;
+            let etag = response
+                .headers()
+                .get("ETag")
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| anyhow!("No ETag for part {part_number} of {key}"))?;
+            let _ = write!(
+                completed,
+                "<Part><PartNumber>{part_number}</PartNumber><ETag>{etag}</ETag></Part>"
+            );
+        }
+        completed.push_str("</CompleteMultipartUpload>");
+
+        let response = self
+            .request(
+                Method::POST,
+                key,
+                &[("uploadId", upload_id)],
+                &[("Content-Type", "application/xml")],
+                completed.into_bytes(),
+            )
+            .await?;
+        // S3 may report a failed completion in a 200 response
+        let body = response.text().await?;
+        if body.contains("<Error>") {
+            return Err(anyhow!(
+                "Failed to complete multipart upload of {key}: {body}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Uploads the feed and its chapters, transcripts and artwork from the feed
+    /// directory; episode audio is uploaded by the publisher.
+    ///
+    /// Artwork never changes once uploaded, so it is only uploaded if the bucket
+    /// does not hold it yet (episode artwork is usually uploaded by the publisher).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read or any upload fails.
+    pub async fn upload_feed(&self, dir: &Path) -> Result<()> {
+        let mut files = Vec::new();
+        collect_files(dir, dir, &mut files)?;
+
+        let mut uploaded = 0;
+        for relative in files {
+            if relative.ends_with(".mp3") {
+                continue;
+            }
+            let key = self.config.key(&relative);
+            let cache_control = if content_type(&relative).starts_with("image/") {
+                if self.object_exists(&key).await? {
+                    continue;
+                }
+                CACHE_IMMUTABLE
+            } else {
+                CACHE_SHORT
+            };
+            self.upload_file(&dir.join(&relative), &key, cache_control)
+                .await?;
+            uploaded += 1;
+        }
+        info!(files = uploaded, bucket = %self.config.bucket, "Feed uploaded to object storage");
+
+        Ok(())
+    }
+}
+
+/// Lists the files under `dir` as `/`-separated paths relative to `root`.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the text of the first `<tag>` element in an XML response.
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    // Tag names are fixed identifiers and always form a valid pattern
+    let re = Regex::new(&format!("<{tag}>([^<]*)</{tag}>")).ok()?;
+    re.captures(xml).map(|cap| cap[1].to_string())
+}
+
+/// Publishes episodes by uploading their audio and artwork to an S3-compatible bucket.
+#[derive(Debug, Clone)]
+pub struct S3Publisher {
+    pub client: S3Client,
+}
+
+impl S3Publisher {
+    /// Builds the publisher from the `S3_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bucket settings are incomplete.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            client: S3Client::from_env()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for S3Publisher {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    async fn publish(&self, episode: &Episode) -> Result<Published> {
+        if episode.guid.is_empty() {
+            return Err(anyhow!("Episode has no GUID"));
+        }
+
+        let key = self
+            .client
+            .config
+            .key(&FeedConfig::episode_file(&episode.guid, "mp3"));
+        let url = self
+            .client
+            .upload_file(Path::new(&episode.audio_path), &key, CACHE_IMMUTABLE)
+            .await?;
+
+        if let Some(artwork) = &episode.artwork {
+            let ext = Path::new(artwork)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "jpg".to_string());
+            let artwork_key = self
+                .client
+                .config
+                .key(&FeedConfig::episode_file(&episode.guid, &ext));
+            self.client
+                .upload_file(Path::new(artwork), &artwork_key, CACHE_IMMUTABLE)
+                .await?;
+        }
+
+        info!(key = %key, url = %url, "Episode audio uploaded to object storage");
+        Ok(Published {
+            status: EpisodeStatus::Published,
+            remote_id: Some(key),
+            url: Some(url),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::{MockHttp, Response};
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    /// Starts a mock S3 server that answers every request like MinIO would; objects
+    /// exist once they have been PUT.
+    async fn mock_s3() -> MockHttp {
+        let stored = Mutex::new(HashSet::new());
+        MockHttp::serve(move |request, _| match request.method.as_str() {
+            "HEAD" if stored.lock().unwrap().contains(&request.path) => Response::new(200, ""),
+            "HEAD" => Response::new(404, ""),
+            "POST" if request.param("uploads").is_some() => Response::new(
+                200,
+                "<InitiateMultipartUploadResult><UploadId>upload-1</UploadId></InitiateMultipartUploadResult>",
+            ),
+            "PUT" if request.param("partNumber").is_some() => {
+                Response::new(200, "").header("ETag", "\"etag-part\"")
+            }
+            "PUT" => {
+                stored.lock().unwrap().insert(request.path.clone());
+                Response::new(200, "")
+            }
+            _ => Response::new(200, ""),
+        })
+        .await
+    }
+
+    fn config(endpoint: &str) -> S3Config {
+        S3Config {
+            endpoint: endpoint.to_string(),
+            bucket: "sermons".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "minioadmin".to_string(),
+            secret_access_key: "minioadmin".to_string(),
+            public_url: "https://cdn.example.org".to_string(),
+            prefix: "podcast/".to_string(),
+            path_style: true,
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+        }
+    }
+
+    fn temp_file(name: &str, size: usize) -> std::path::PathBuf {
+        let path = env::temp_dir().join(format!("audio_epistles_s3_{}_{name}", std::process::id()));
+        fs::write(&path, vec![b'a'; size]).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(
+            uri_encode("episodes/Faith & Works.mp3", false),
+            "episodes/Faith%20%26%20Works.mp3"
+        );
+        assert_eq!(uri_encode("a/b", true), "a%2Fb");
+    }
+
+    #[test]
+    fn test_bucket_url() {
+        let mut config = config("https://s3.us-east-1.amazonaws.com");
+        assert_eq!(
+            config.bucket_url(),
+            "https://s3.us-east-1.amazonaws.com/sermons"
+        );
+        config.path_style = false;
+        assert_eq!(
+            config.bucket_url(),
+            "https://sermons.s3.us-east-1.amazonaws.com"
+        );
+        assert_eq!(
+            config.public_url(&config.key("episodes/abc.mp3")),
+            "https://cdn.example.org/podcast/episodes/abc.mp3"
+        );
+    }
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(content_type("episodes/abc.mp3"), "audio/mpeg");
+        assert_eq!(content_type("feed.xml"), "application/rss+xml");
+        assert_eq!(content_type("artwork.JPG"), "image/jpeg");
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_single_request() {
+        let mock = mock_s3().await;
+        let client = S3Client::new(config(&mock.url));
+        let file = temp_file("single.mp3", 1024);
+
+        let url = client
+            .upload_file(&file, "podcast/episodes/abc.mp3", CACHE_IMMUTABLE)
+            .await
+            .unwrap();
+        assert_eq!(url, "https://cdn.example.org/podcast/episodes/abc.mp3");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(requests[0].target, "/sermons/podcast/episodes/abc.mp3");
+        assert_eq!(requests[0].header("content-type"), Some("audio/mpeg"));
+        assert_eq!(requests[0].header("cache-control"), Some(CACHE_IMMUTABLE));
+        assert!(requests[0]
+            .header("authorization")
+            .unwrap()
+            .starts_with("AWS4-HMAC-SHA256 Credential=minioadmin/"));
+        assert_eq!(requests[0].body.len(), 1024);
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[tokio::test]
+    async fn test_upload_file_multipart() {
+        let mock = mock_s3().await;
+        let mut config = config(&mock.url);
+        config.multipart_threshold = 1;
+        let client = S3Client::new(config);
+        let file = temp_file("multipart.mp3", PART_SIZE + 10);
+
+        client
+            .upload_file(&file, "episodes/abc.mp3", CACHE_IMMUTABLE)
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        let summary: Vec<(&str, &str)> = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.target.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("POST", "/sermons/episodes/abc.mp3?uploads="),
+                (
+                    "PUT",
+                    "/sermons/episodes/abc.mp3?partNumber=1&uploadId=upload-1"
+                ),
+                (
+                    "PUT",
+                    "/sermons/episodes/abc.mp3?partNumber=2&uploadId=upload-1"
+                ),
+                ("POST", "/sermons/episodes/abc.mp3?uploadId=upload-1"),
+            ]
+        );
+        assert_eq!(requests[1].body.len(), PART_SIZE);
+        assert_eq!(requests[2].body.len(), 10);
+        let completed = String::from_utf8_lossy(&requests[3].body);
+        assert!(completed.contains("<PartNumber>2</PartNumber><ETag>\"etag-part\"</ETag>"));
+
+        let _ = fs::remove_file(file);
+    }
+
+    #[tokio::test]
+    async fn test_upload_feed_skips_uploaded_artwork() {
+        let mock = mock_s3().await;
+        let client = S3Client::new(config(&mock.url));
+        let dir = env::temp_dir().join(format!("audio_epistles_s3_feed_{}", std::process::id()));
+        fs::create_dir_all(dir.join("episodes")).unwrap();
+        for file in [
+            "feed.xml",
+            "artwork.jpg",
+            "episodes/abc.mp3",
+            "episodes/abc.jpg",
+            "episodes/abc.chapters.json",
+        ] {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        // The publisher uploaded the episode artwork with the audio
+        client
+            .upload_file(
+                &dir.join("episodes/abc.jpg"),
+                &client.config.key("episodes/abc.jpg"),
+                CACHE_IMMUTABLE,
+            )
+            .await
+            .unwrap();
+
+        client.upload_feed(&dir).await.unwrap();
+        client.upload_feed(&dir).await.unwrap();
+
+        let mut uploads: Vec<String> = mock
+            .requests()
+            .iter()
+            .filter(|r| r.method == "PUT")
+            .map(|r| r.target.clone())
+            .collect();
+        uploads.sort();
+        assert_eq!(
+            uploads,
+            [
+                "/sermons/podcast/artwork.jpg",
+                "/sermons/podcast/episodes/abc.chapters.json",
+                "/sermons/podcast/episodes/abc.chapters.json",
+                "/sermons/podcast/episodes/abc.jpg",
+                "/sermons/podcast/feed.xml",
+                "/sermons/podcast/feed.xml",
+            ]
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    /// Uploads to a real MinIO server, e.g. `docker compose --profile minio up minio`,
+    /// with `S3_ENDPOINT`, `S3_BUCKET`, `S3_ACCESS_KEY_ID` and `S3_SECRET_ACCESS_KEY` set
+    #[tokio::test]
+    #[ignore]
+    async fn test_upload_to_minio() {
+        let client = S3Client::from_env().unwrap();
+        let file = temp_file("minio.mp3", 1024);
+        let url = client
+            .upload_file(&file, &client.config.key("test/minio.mp3"), CACHE_SHORT)
+            .await
+            .unwrap();
+        assert!(url.ends_with("test/minio.mp3"));
+        let _ = fs::remove_file(file);
+    }
+}
@@ -0,0 +1,486 @@
+//! Scripture reference extraction module.
+//!
+//! This module recognises Bible references (e.g. "Jn 3:16-18 (NIV)", "1st Cor. 13",
+//! "Romans 8:28, 31; 12:1") in free text such as video titles, descriptions and
+//! chapter titles, and normalises them to a canonical form ("John 3:16-18 (NIV)").
+
+use regex::{Captures, Regex};
+use std::cmp::Reverse;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Canonical book name, number of chapters, and accepted aliases (lowercase).
+///
+/// Aliases for numbered books are written with a leading "1 ", "2 " or "3 ";
+/// ordinal and roman-numeral variants ("1st", "First", "I") are derived from them.
+const BOOKS: &[(&str, u32, &[&str])] = &[
+    ("Genesis", 50, &["genesis", "gen", "gn"]),
+    ("Exodus", 40, &["exodus", "exod", "exo", "ex"]),
+    ("Leviticus", 27, &["leviticus", "lev", "lv"]),
+    ("Numbers", 36, &["numbers", "num", "nm"]),
+    ("Deuteronomy", 34, &["deuteronomy", "deut", "dt"]),
+    ("Joshua", 24, &["joshua", "josh", "jos"]),
+    ("Judges", 21, &["judges", "judg", "jdg"]),
+    ("Ruth", 4, &["ruth", "rth"]),
+    ("1 Samuel", 31, &["1 samuel", "1 sam", "1 sa"]),
+    ("2 Samuel", 24, &["2 samuel", "2 sam", "2 sa"]),
+    ("1 Kings", 22, &["1 kings", "1 kgs", "1 ki"]),
+    ("2 Kings", 25, &["2 kings", "2 kgs", "2 ki"]),
+    ("1 Chronicles", 29, &["1 chronicles", "1 chron", "1 chr"]),
+    ("2 Chronicles", 36, &["2 chronicles", "2 chron", "2 chr"]),
+    ("Ezra", 10, &["ezra", "ezr"]),
+    ("Nehemiah", 13, &["nehemiah", "neh"]),
+    ("Esther", 10, &["esther", "esth", "est"]),
+    ("Job", 42, &["job"]),
+    ("Psalms", 150, &["psalms", "psalm", "pss", "psa", "ps"]),
+    ("Proverbs", 31, &["proverbs", "prov", "prv"]),
+    (
+        "Ecclesiastes",
+        12,
+        &["ecclesiastes", "eccles", "eccl", "ecc", "qoh"],
+    ),
+    (
+        "Song of Solomon",
+        8,
+        &["song of solomon", "song of songs", "song of sol", "sos"],
+    ),
+    ("Isaiah", 66, &["isaiah", "isa"]),
+    ("Jeremiah", 52, &["jeremiah", "jer"]),
+    ("Lamentations", 5, &["lamentations", "lam"]),
+    ("Ezekiel", 48, &["ezekiel", "ezek", "ezk"]),
+    ("Daniel", 12, &["daniel", "dan", "dn"]),
+    ("Hosea", 14, &["hosea", "hos"]),
+    ("Joel", 3, &["joel"]),
+    ("Amos", 9, &["amos"]),
+    ("Obadiah", 1, &["obadiah", "obad"]),
+    ("Jonah", 4, &["jonah", "jon"]),
+    ("Micah", 7, &["micah", "mic"]),
+    ("Nahum", 3, &["nahum", "nah"]),
+    ("Habakkuk", 3, &["habakkuk", "hab"]),
+    ("Zephaniah", 3, &["zephaniah", "zeph", "zep"]),
+    ("Haggai", 2, &["haggai", "hag"]),
+    ("Zechariah", 14, &["zechariah", "zech", "zec"]),
+    ("Malachi", 4, &["malachi", "mal"]),
+    ("Matthew", 28, &["matthew", "matt", "mat", "mt"]),
+    ("Mark", 16, &["mark", "mrk", "mk"]),
+    ("Luke", 24, &["luke", "luk", "lk"]),
+    ("John", 21, &["john", "jhn", "jn"]),
+    ("Acts", 28, &["acts", "act"]),
+    ("Romans", 16, &["romans", "rom"]),
+    ("1 Corinthians", 16, &["1 corinthians", "1 cor", "1 co"]),
+    ("2 Corinthians", 13, &["2 corinthians", "2 cor", "2 co"]),
+    ("Galatians", 6, &["galatians", "gal"]),
+    ("Ephesians", 6, &["ephesians", "eph"]),
+    ("Philippians", 4, &["philippians", "phil", "php"]),
+    ("Colossians", 4, &["colossians", "col"]),
+    (
+        "1 Thessalonians",
+        5,
+        &["1 thessalonians", "1 thess", "1 thes", "1 th"],
+    ),
+    (
+        "2 Thessalonians",
+        3,
+        &["2 thessalonians", "2 thess", "2 thes", "2 th"],
+    ),
+    ("1 Timothy", 6, &["1 timothy", "1 tim", "1 ti"]),
+    ("2 Timothy", 4, &["2 timothy", "2 tim", "2 ti"]),
+    ("Titus", 3, &["titus", "tit"]),
+    ("Philemon", 1, &["philemon", "philem", "phlm", "phm"]),
+    ("Hebrews", 13, &["hebrews", "heb"]),
+    ("James", 5, &["james", "jas", "jms"]),
+    ("1 Peter", 5, &["1 peter", "1 pet", "1 pt"]),
+    ("2 Peter", 3, &["2 peter", "2 pet", "2 pt"]),
+    ("1 John", 5, &["1 john", "1 jhn", "1 jn"]),
+    ("2 John", 1, &["2 john", "2 jhn", "2 jn"]),
+    ("3 John", 1, &["3 john", "3 jhn", "3 jn"]),
+    ("Jude", 1, &["jude", "jud"]),
+    ("Revelation", 22, &["revelation", "revelations", "rev"]),
+];
+
+/// Translations recognised after a reference, e.g. "John 3:16 (NKJV)" or "John 3:16 NIV".
+const TRANSLATIONS: &[&str] = &[
+    "NKJV", "KJV", "NIV", "NIRV", "ESV", "NLT", "NASB", "AMPC", "AMP", "MSG", "HCSB", "CSB",
+    "NRSV", "RSV", "TPT", "NET", "CEV", "GNT", "ASV", "WEB", "YLT",
+];
+
+/// A normalised Bible reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptureRef {
+    /// Canonical book name (e.g. "1 Corinthians")
+    pub book: &'static str,
+    pub chapter: u32,
+    pub verse: Option<u32>,
+    /// Set only when the reference ends in a different chapter (e.g. "John 3:16-4:2")
+    pub end_chapter: Option<u32>,
+    pub end_verse: Option<u32>,
+    /// Upper-case translation abbreviation (e.g. "KJV")
+    pub translation: Option<String>,
+}
+
+impl fmt::Display for ScriptureRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.book, self.chapter)?;
+        if let Some(verse) = self.verse {
+            write!(f, ":{verse}")?;
+        }
+        match (self.end_chapter, self.end_verse) {
+            (Some(chapter), Some(verse)) => write!(f, "-{chapter}:{verse}")?,
+            (Some(chapter), None) => write!(f, "-{chapter}")?,
+            (None, Some(verse)) => write!(f, "-{verse}")?,
+            (None, None) => {}
+        }
+        if let Some(translation) = &self.translation {
+            write!(f, " ({translation})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase alias → (canonical name, chapter count), with numbered-book variants expanded.
+fn aliases() -> Vec<(String, &'static str, u32)> {
+    let mut aliases = Vec::new();
+    for (book, chapters, names) in BOOKS {
+        for name in *names {
+            aliases.push((name.to_string(), *book, *chapters));
+
+            let Some((number, rest)) = name.split_once(' ') else {
+                continue;
+            };
+            let variants: &[&str] = match number {
+                "1" => &["1", "1st", "i", "first"],
+                "2" => &["2", "2nd", "ii", "second"],
+                "3" => &["3", "3rd", "iii", "third"],
+                _ => &[],
+            };
+            for prefix in variants {
+                if *prefix == number {
+                    // "1Jn" is common; "IJn" or "Firstjn" are not
+                    aliases.push((format!("{prefix}{rest}"), *book, *chapters));
+                } else if rest.len() >= 3 {
+                    aliases.push((format!("{prefix} {rest}"), *book, *chapters));
+                }
+            }
+        }
+    }
+    // Longest first so "1 john" wins over "john" and "psalms" over "ps"
+    aliases.sort_by_key(|(alias, _, _)| Reverse(alias.len()));
+    aliases
+}
+
+struct Parser {
+    reference: Regex,
+    continuation: Regex,
+    aliases: Vec<(String, &'static str, u32)>,
+}
+
+fn parser() -> &'static Parser {
+    static PARSER: OnceLock<Parser> = OnceLock::new();
+    PARSER.get_or_init(|| {
+        let aliases = aliases();
+        let books = aliases
+            .iter()
+            .map(|(alias, _, _)| regex::escape(alias).replace(' ', r"\s+"))
+            .collect::<Vec<_>>()
+            .join("|");
+        let translations = TRANSLATIONS.join("|");
+        let range = r"(\d{1,3})(?:\s*:\s*(\d{1,3}))?(?:\s*[-–—]\s*(\d{1,3})(?:\s*:\s*(\d{1,3}))?)?";
+        // Bare abbreviations must be upper case so "John 3:16 net gain" is not read as NET
+        let translation =
+            format!(r"(?:\s*\(\s*({translations})\s*\)|\s+(?-i:({translations}))\b)?");
+
+        // Patterns are built from constant tables and will never fail to compile
+        Parser {
+            reference: Regex::new(&format!(r"(?i)\b({books})\.?\s*{range}\b{translation}"))
+                .unwrap(),
+            continuation: Regex::new(&format!(r"(?i)^\s*[,;]\s*{range}\b{translation}")).unwrap(),
+            aliases,
+        }
+    })
+}
+
+fn number(caps: &Captures<'_>, index: usize) -> Option<u32> {
+    caps.get(index).and_then(|m| m.as_str().parse().ok())
+}
+
+/// Builds a reference from `(chapter, verse, end, end_verse)` captures starting at `offset`.
+///
+/// When `context` is the previous reference in a list, a bare number continues it:
+/// "John 3:16, 18" is verse 18 of chapter 3, while "Psalm 23, 24" is chapter 24.
+fn build(
+    caps: &Captures<'_>,
+    offset: usize,
+    book: &'static str,
+    max_chapter: u32,
+    context: Option<&ScriptureRef>,
+) -> Option<ScriptureRef> {
+    let first = number(caps, offset)?;
+    let second = number(caps, offset + 1);
+    let end = number(caps, offset + 2);
+    let end_verse = number(caps, offset + 3);
+    let translation = caps
+        .get(offset + 4)
+        .or_else(|| caps.get(offset + 5))
+        .map(|m| m.as_str().to_uppercase())
+        .or_else(|| context.and_then(|c| c.translation.clone()));
+
+    let continues_verses = context.is_some_and(|c| c.verse.is_some()) && second.is_none();
+    let (chapter, verse) = match (second, context) {
+        (Some(verse), _) => (first, Some(verse)),
+        (None, Some(previous)) if continues_verses => (
+            previous.end_chapter.unwrap_or(previous.chapter),
+            Some(first),
+        ),
+        // Single-chapter books cite verses directly ("Jude 3")
+        (None, _) if max_chapter == 1 && end_verse.is_none() => (1, Some(first)),
+        (None, _) => (first, None),
+    };
+
+    let (end_chapter, end_verse) = match (end, end_verse) {
+        (Some(c), Some(v)) if c != chapter => (Some(c), Some(v)),
+        (Some(_), Some(v)) => (None, Some(v)),
+        (Some(v), None) if verse.is_some() => (None, Some(v)),
+        (Some(c), None) => (Some(c), None),
+        (None, _) => (None, None),
+    };
+
+    let valid = chapter >= 1
+        && chapter <= max_chapter
+        && end_chapter.is_none_or(|c| c > chapter && c <= max_chapter)
+        && verse.is_none_or(|v| v >= 1)
+        && match (verse, end_chapter, end_verse) {
+            (Some(v), None, Some(e)) => e > v,
+            _ => true,
+        };
+
+    valid.then_some(ScriptureRef {
+        book,
+        chapter,
+        verse,
+        end_chapter,
+        end_verse,
+        translation,
+    })
+}
+
+/// Extracts every Bible reference found in `text`, in order of appearance.
+///
+/// Book names may be full ("Revelation"), abbreviated with or without a period
+/// ("Rev.", "Jn") and numbered books accept digit, ordinal, roman and word prefixes
+/// ("1 Cor", "1st Corinthians", "I Cor", "First Corinthians"). A reference may be
+/// followed by further chapters or verses of the same book separated by `,` or `;`
+/// and by a translation abbreviation, optionally in parentheses.
+///
+/// # Arguments
+///
+/// * `text` - Free text to scan
+///
+/// # Returns
+///
+/// Returns the references found; chapters or verses outside the book's range are ignored.
+///
+/// # Example
+///
+/// ```
+/// let refs = audio_epistles::scripture::parse_references("Text: Rom. 8:28, 31; 12:1-2 (NKJV)");
+/// let refs: Vec<String> = refs.iter().map(|r| r.to_string()).collect();
+/// assert_eq!(refs, ["Romans 8:28 (NKJV)", "Romans 8:31 (NKJV)", "Romans 12:1-2 (NKJV)"]);
+/// ```
+pub fn parse_references(text: &str) -> Vec<ScriptureRef> {
+    let parser = parser();
+    let mut refs = Vec::new();
+    let mut pos = 0;
+
+    while let Some(caps) = parser.reference.captures_at(text, pos) {
+        let whole = caps.get(0).map_or(pos..text.len(), |m| m.range());
+        pos = whole.end;
+
+        let alias = caps[1].to_lowercase();
+        let alias: String = alias.split_whitespace().collect::<Vec<_>>().join(" ");
+        let Some((_, book, max_chapter)) = parser.aliases.iter().find(|(a, _, _)| *a == alias)
+        else {
+            continue;
+        };
+
+        let Some(first) = build(&caps, 2, book, *max_chapter, None) else {
+            continue;
+        };
+        let mut group = vec![first];
+
+        // Follow ", 31" and "; 12:1" continuations of the same book
+        while let Some(next) = parser.continuation.captures(&text[pos..]) {
+            let end = pos + next.get(0).map_or(0, |m| m.end());
+            // "John 3:16, 2 Cor 5:17" starts a new book rather than continuing
+            let starts_new_book = parser.reference.find_at(text, pos).is_some_and(|m| {
+                m.start() < end
+                    && text[pos..m.start()]
+                        .trim_start_matches([',', ';', ' '])
+                        .is_empty()
+            });
+            if starts_new_book {
+                break;
+            }
+            let Some(reference) = build(&next, 1, book, *max_chapter, group.last()) else {
+                break;
+            };
+            group.push(reference);
+            pos = end;
+        }
+
+        // A trailing translation applies to the whole list
+        if let Some(translation) = group.last().and_then(|r| r.translation.clone()) {
+            for reference in &mut group {
+                reference
+                    .translation
+                    .get_or_insert_with(|| translation.clone());
+            }
+        }
+        refs.extend(group);
+    }
+
+    refs
+}
+
+/// Extracts references from several texts (title, description, chapter titles)
+/// and removes duplicates, keeping the first occurrence.
+///
+/// # Example
+///
+/// ```
+/// let refs = audio_epistles::scripture::extract_references(&["Faith | Heb 11:1", "Read Hebrews 11:1"]);
+/// assert_eq!(refs.len(), 1);
+/// assert_eq!(refs[0].to_string(), "Hebrews 11:1");
+/// ```
+pub fn extract_references(texts: &[&str]) -> Vec<ScriptureRef> {
+    let mut refs: Vec<ScriptureRef> = Vec::new();
+    for reference in texts.iter().flat_map(|text| parse_references(text)) {
+        if !refs.contains(&reference) {
+            refs.push(reference);
+        }
+    }
+    refs
+}
+
+/// Returns the canonical name of a book from any accepted alias, e.g. "1 Cor" or
+/// "Rom.".
+///
+/// # Example
+///
+/// ```
+/// use audio_epistles::scripture::find_book;
+///
+/// assert_eq!(find_book("1st Cor."), Some("1 Corinthians"));
+/// assert_eq!(find_book("Hezekiah"), None);
+/// ```
+pub fn find_book(name: &str) -> Option<&'static str> {
+    let alias = name.trim().trim_end_matches('.').to_lowercase();
+    let alias = alias.split_whitespace().collect::<Vec<_>>().join(" ");
+    parser()
+        .aliases
+        .iter()
+        .find(|(a, _, _)| *a == alias)
+        .map(|(_, book, _)| *book)
+}
+
+/// Joins references into a single canonical string, e.g. "John 3:16; Romans 8:28".
+pub fn join(refs: &[ScriptureRef]) -> String {
+    refs.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(text: &str) -> Vec<String> {
+        parse_references(text)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_full_book_names() {
+        assert_eq!(canonical("Read John 3:16 today"), ["John 3:16"]);
+        assert_eq!(canonical("Revelation 21:1-4"), ["Revelation 21:1-4"]);
+        assert_eq!(canonical("Song of Solomon 2:4"), ["Song of Solomon 2:4"]);
+    }
+
+    #[test]
+    fn test_parse_abbreviations() {
+        assert_eq!(canonical("Jn 3:16"), ["John 3:16"]);
+        assert_eq!(canonical("Rom. 8:28"), ["Romans 8:28"]);
+        assert_eq!(canonical("PS 23"), ["Psalms 23"]);
+        assert_eq!(canonical("Heb 11:1-6"), ["Hebrews 11:1-6"]);
+    }
+
+    #[test]
+    fn test_parse_numbered_books() {
+        assert_eq!(canonical("1 Cor 13:4"), ["1 Corinthians 13:4"]);
+        assert_eq!(canonical("1st Corinthians 13"), ["1 Corinthians 13"]);
+        assert_eq!(canonical("II Timothy 3:16"), ["2 Timothy 3:16"]);
+        assert_eq!(canonical("First John 1:9"), ["1 John 1:9"]);
+        assert_eq!(canonical("1Jn 4:8"), ["1 John 4:8"]);
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(canonical("John 3:16-4:2"), ["John 3:16-4:2"]);
+        assert_eq!(canonical("Romans 5-8"), ["Romans 5-8"]);
+        assert_eq!(canonical("Matthew 5:3 – 12"), ["Matthew 5:3-12"]);
+    }
+
+    #[test]
+    fn test_parse_multiple_references() {
+        assert_eq!(
+            canonical("Romans 8:28, 31; 12:1-2"),
+            ["Romans 8:28", "Romans 8:31", "Romans 12:1-2"]
+        );
+        assert_eq!(canonical("Psalm 23, 24"), ["Psalms 23", "Psalms 24"]);
+        assert_eq!(
+            canonical("John 3:16, 2 Cor 5:17"),
+            ["John 3:16", "2 Corinthians 5:17"]
+        );
+    }
+
+    #[test]
+    fn test_parse_translations() {
+        assert_eq!(canonical("John 3:16 (kjv)"), ["John 3:16 (KJV)"]);
+        assert_eq!(canonical("Isaiah 40:31 NIV"), ["Isaiah 40:31 (NIV)"]);
+        assert_eq!(
+            canonical("Eph 2:8; 2:10 (ESV)"),
+            ["Ephesians 2:8 (ESV)", "Ephesians 2:10 (ESV)"]
+        );
+    }
+
+    #[test]
+    fn test_parse_single_chapter_books() {
+        assert_eq!(canonical("Jude 3"), ["Jude 1:3"]);
+        assert_eq!(canonical("Philemon 1:6"), ["Philemon 1:6"]);
+    }
+
+    #[test]
+    fn test_parse_ignores_out_of_range_and_plain_words() {
+        assert!(canonical("Mark 100").is_empty());
+        assert!(canonical("Join us at 9:30 for worship").is_empty());
+        assert!(canonical("Sunday Service | Jan 1, 2024").is_empty());
+        assert!(canonical("John 3:18-16").is_empty());
+    }
+
+    #[test]
+    fn test_find_book() {
+        assert_eq!(find_book("Romans"), Some("Romans"));
+        assert_eq!(find_book(" rom. "), Some("Romans"));
+        assert_eq!(find_book("First  John"), Some("1 John"));
+        assert_eq!(find_book("Psalm"), Some("Psalms"));
+        assert_eq!(find_book("Faith"), None);
+    }
+
+    #[test]
+    fn test_extract_references_deduplicates() {
+        let refs = extract_references(&["Faith | Heb 11:1", "Read Hebrews 11:1 and Jas 2:17"]);
+        assert_eq!(join(&refs), "Hebrews 11:1; James 2:17");
+    }
+}
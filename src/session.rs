@@ -0,0 +1,289 @@
+//! Spotify session persistence module.
+//!
+//! After a successful login the browser's cookies are saved to an encrypted file and
+//! restored on the next run, so that the full login (and its long, bot-detection-prone
+//! sequence of delays) only happens once the session has expired.
+//!
+//! The file holds the cookies in `Set-Cookie` format, one per line, encrypted with
+//! AES-256-GCM under a key derived from `SPOTIFY_SESSION_KEY` with Argon2id and a
+//! random salt stored in the file, so that a stolen file cannot be brute-forced
+//! quickly. Persistence is disabled when no key is set.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use fantoccini::cookies::Cookie;
+use rand::{rng, Rng};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use tracing::{debug, info};
+
+/// Session file used when `SPOTIFY_SESSION_FILE` is not set
+pub const DEFAULT_SESSION_FILE: &str = ".spotify-session";
+
+/// Identifies the file format, so that a future format can be told apart
+const MAGIC: &[u8] = b"AESS2";
+
+/// Length of the random salt of the key derivation, stored after `MAGIC`
+const SALT_LEN: usize = 16;
+
+const NONCE_LEN: usize = 12;
+
+/// An encrypted file holding the cookies of a logged-in Spotify session.
+#[derive(Clone)]
+pub struct SessionStore {
+    pub path: PathBuf,
+    passphrase: String,
+}
+
+impl std::fmt::Debug for SessionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the passphrase
+        f.debug_struct("SessionStore")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SessionStore {
+    /// Creates a store at `path`, encrypted with a key derived from `passphrase`.
+    pub fn new(path: impl Into<PathBuf>, passphrase: &str) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    /// Reads the store settings from the environment.
+    ///
+    /// - `SPOTIFY_SESSION_KEY`: passphrase the session is encrypted with; persistence
+    ///   is disabled if it is not set
+    /// - `SPOTIFY_SESSION_FILE`: path of the session file (default `.spotify-session`)
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if session persistence is disabled.
+    pub fn from_env() -> Option<Self> {
+        let passphrase = env::var("SPOTIFY_SESSION_KEY")
+            .ok()
+            .filter(|v| !v.is_empty())?;
+        let path = env::var("SPOTIFY_SESSION_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_SESSION_FILE.to_string());
+        Some(Self::new(path, &passphrase))
+    }
+
+    /// Derives the cipher key from the passphrase and `salt` with Argon2id.
+    fn cipher(&self, salt: &[u8]) -> Result<Aes256Gcm> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Failed to derive session key: {e}"))?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+
+    /// Encrypts `plaintext` with a fresh random salt and nonce.
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut salt = [0u8; SALT_LEN];
+        rng().fill(&mut salt);
+        let mut nonce = [0u8; NONCE_LEN];
+        rng().fill(&mut nonce);
+        let ciphertext = self
+            .cipher(&salt)?
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt session"))?;
+
+        Ok([MAGIC, &salt, &nonce, &ciphertext].concat())
+    }
+
+    /// Decrypts data written by `encrypt`.
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let data = data
+            .strip_prefix(MAGIC)
+            .filter(|rest| rest.len() > SALT_LEN + NONCE_LEN)
+            .ok_or_else(|| anyhow!("Unrecognized session file format"))?;
+        let (salt, data) = data.split_at(SALT_LEN);
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+
+        self.cipher(salt)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt session (wrong SPOTIFY_SESSION_KEY?)"))
+    }
+
+    /// Encrypts and saves the cookies of a logged-in session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, cookies: &[Cookie<'static>]) -> Result<()> {
+        let lines = cookies
+            .iter()
+            .map(|cookie| cookie.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.encrypt(lines.as_bytes())?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        restrict_permissions(&self.path)?;
+
+        info!(cookies = cookies.len(), path = %self.path.display(), "Spotify session saved");
+        Ok(())
+    }
+
+    /// Loads the saved session, dropping cookies that have already expired.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if no session has been saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decrypted.
+    pub fn load(&self) -> Result<Option<Vec<Cookie<'static>>>> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context(format!("Failed to read {}", self.path.display())),
+        };
+        let text = String::from_utf8(self.decrypt(&data)?).context("Invalid session file")?;
+
+        let now = time_now();
+        let mut cookies = Vec::new();
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            let cookie = Cookie::parse(line.to_string())
+                .with_context(|| format!("Invalid cookie in session file: {line}"))?;
+            let expired = cookie
+                .expires_datetime()
+                .is_some_and(|expires| expires.unix_timestamp() <= now);
+            if expired {
+                debug!(cookie = %cookie.name(), "Dropping expired cookie");
+            } else {
+                cookies.push(cookie);
+            }
+        }
+
+        Ok(Some(cookies))
+    }
+
+    /// Deletes the saved session, e.g. after Spotify rejected it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be removed.
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the current Unix time in seconds.
+fn time_now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Makes the session file readable by its owner only.
+#[cfg(unix)]
+fn restrict_permissions(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "audio_epistles_session_{}_{name}",
+            std::process::id()
+        ))
+    }
+
+    fn cookie(text: &str) -> Cookie<'static> {
+        Cookie::parse(text.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let store = SessionStore::new(temp_path("roundtrip"), "correct horse");
+        let cookies = vec![
+            cookie("sp_dc=abc123; Domain=spotify.com; Path=/; Secure; HttpOnly; Expires=Fri, 01 Jan 2100 00:00:00 GMT"),
+            cookie("sp_t=session; Path=/"),
+            cookie("stale=1; Expires=Thu, 01 Jan 2015 00:00:00 GMT"),
+        ];
+        store.save(&cookies).unwrap();
+
+        let raw = fs::read(&store.path).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("abc123"));
+
+        let loaded = store.load().unwrap().unwrap();
+        let names: Vec<&str> = loaded.iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["sp_dc", "sp_t"]);
+        assert_eq!(loaded[0].value(), "abc123");
+        assert_eq!(loaded[0].domain(), Some("spotify.com"));
+        assert_eq!(loaded[0].http_only(), Some(true));
+
+        store.clear().unwrap();
+        assert!(store.load().unwrap().is_none());
+        store.clear().unwrap();
+    }
+
+    #[test]
+    fn test_load_with_wrong_key() {
+        let path = temp_path("wrong_key");
+        SessionStore::new(&path, "correct horse")
+            .save(&[cookie("sp_dc=abc123")])
+            .unwrap();
+
+        let err = SessionStore::new(&path, "battery staple")
+            .load()
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to decrypt"));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampering() {
+        let store = SessionStore::new(temp_path("unused"), "correct horse");
+        let mut data = store.encrypt(b"sp_dc=abc123").unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(store.decrypt(&data).is_err());
+        assert!(store.decrypt(b"not a session").is_err());
+    }
+
+    #[test]
+    fn test_encrypt_uses_fresh_salt() {
+        let store = SessionStore::new(temp_path("unused"), "correct horse");
+        let first = store.encrypt(b"sp_dc=abc123").unwrap();
+        let second = store.encrypt(b"sp_dc=abc123").unwrap();
+        assert!(first.starts_with(b"AESS2"));
+        let salt = MAGIC.len()..MAGIC.len() + SALT_LEN;
+        assert_ne!(first[salt.clone()], second[salt]);
+        assert_eq!(store.decrypt(&second).unwrap(), b"sp_dc=abc123");
+
+        // Files of the unsalted format are not accepted
+        let mut old = first.clone();
+        old[..MAGIC.len()].copy_from_slice(b"AESS1");
+        assert!(store
+            .decrypt(&old)
+            .unwrap_err()
+            .to_string()
+            .contains("Unrecognized"));
+    }
+}
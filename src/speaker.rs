@@ -0,0 +1,296 @@
+//! Speaker and series detection module.
+//!
+//! This module works out who preached and which sermon series an episode belongs to,
+//! using configurable extractors: a regex on the title/description, a list of known
+//! speakers with aliases, and a mapping from YouTube playlist IDs to series names.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::env;
+
+/// Speaker pattern used when `SPEAKER_PATTERN` is not set (matches "Speaker: Name" lines)
+pub const DEFAULT_SPEAKER_PATTERN: &str =
+    r"(?im)^\s*(?:speaker|preacher|minister|preached by)\s*[:\-]\s*(.+?)\s*$";
+
+/// Series pattern used when `SERIES_PATTERN` is not set (matches "Series: Name" lines)
+pub const DEFAULT_SERIES_PATTERN: &str = r"(?im)^\s*(?:sermon\s+)?series\s*[:\-]\s*(.+?)\s*$";
+
+/// A speaker known by a canonical name and the aliases used in titles and descriptions.
+#[derive(Debug, Clone)]
+pub struct KnownSpeaker {
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// Matches the name or any alias as whole words, compiled once
+    pattern: Regex,
+}
+
+impl KnownSpeaker {
+    pub fn new(name: String, aliases: Vec<String>) -> Self {
+        let names = std::iter::once(&name)
+            .chain(&aliases)
+            .map(|n| regex::escape(n))
+            .collect::<Vec<_>>()
+            .join("|");
+        // Patterns are escaped literals and will never fail to compile
+        let pattern = Regex::new(&format!(r"(?i)\b(?:{names})\b")).unwrap();
+        Self {
+            name,
+            aliases,
+            pattern,
+        }
+    }
+
+    /// Returns true if `text` is this speaker's name or one of its aliases.
+    fn is(&self, text: &str) -> bool {
+        let text = text.trim();
+        text.eq_ignore_ascii_case(&self.name)
+            || self.aliases.iter().any(|a| text.eq_ignore_ascii_case(a))
+    }
+
+    /// Returns the byte offset of the first mention of this speaker in `text`.
+    fn find_in(&self, text: &str) -> Option<usize> {
+        self.pattern.find(text).map(|m| m.start())
+    }
+}
+
+/// Configured speaker and series extractors.
+#[derive(Debug, Clone)]
+pub struct Extractors {
+    pub speaker_pattern: Regex,
+    pub series_pattern: Regex,
+    pub known_speakers: Vec<KnownSpeaker>,
+    /// `(playlist ID, series name)` pairs
+    pub series_playlists: Vec<(String, String)>,
+}
+
+impl Extractors {
+    /// Builds the extractors from environment variables.
+    ///
+    /// - `SPEAKER_PATTERN` / `SERIES_PATTERN`: regexes whose first capture group is
+    ///   the speaker or series name
+    /// - `KNOWN_SPEAKERS`: `Name:alias,alias;Name:alias` (aliases optional)
+    /// - `SERIES_PLAYLISTS`: `PLAYLIST_ID=Series name;PLAYLIST_ID=Series name`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is not a valid regex or has no capture group.
+    pub fn from_env() -> Result<Self> {
+        let speaker_pattern =
+            env::var("SPEAKER_PATTERN").unwrap_or_else(|_| DEFAULT_SPEAKER_PATTERN.to_string());
+        let series_pattern =
+            env::var("SERIES_PATTERN").unwrap_or_else(|_| DEFAULT_SERIES_PATTERN.to_string());
+
+        Ok(Self {
+            speaker_pattern: compile(&speaker_pattern).context("Invalid SPEAKER_PATTERN")?,
+            series_pattern: compile(&series_pattern).context("Invalid SERIES_PATTERN")?,
+            known_speakers: parse_known_speakers(&env::var("KNOWN_SPEAKERS").unwrap_or_default()),
+            series_playlists: parse_series_playlists(
+                &env::var("SERIES_PLAYLISTS").unwrap_or_default(),
+            ),
+        })
+    }
+
+    /// Detects the speaker of an episode.
+    ///
+    /// The speaker pattern is tried on the title and then the description; a match is
+    /// replaced by the canonical name if it is a known speaker's alias. Otherwise the
+    /// first known speaker mentioned in the title, then the description, is used.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let extractors = audio_epistles::speaker::Extractors::from_env().unwrap();
+    /// let speaker = extractors.speaker("Faith | Ps. Kofi", "Speaker: Pastor Kofi Mensah");
+    /// ```
+    pub fn speaker(&self, title: &str, description: &str) -> Option<String> {
+        if let Some(found) = capture(&self.speaker_pattern, &[title, description]) {
+            let canonical = self
+                .known_speakers
+                .iter()
+                .find(|s| s.is(&found))
+                .map(|s| s.name.clone());
+            return Some(canonical.unwrap_or(found));
+        }
+
+        [title, description].iter().find_map(|text| {
+            self.known_speakers
+                .iter()
+                .filter_map(|s| s.find_in(text).map(|pos| (pos, s)))
+                .min_by_key(|(pos, _)| *pos)
+                .map(|(_, s)| s.name.clone())
+        })
+    }
+
+    /// Detects the series of an episode.
+    ///
+    /// A series mapped to one of the playlists the video belongs to takes precedence
+    /// over the series pattern, which is tried on the title and then the description.
+    /// When the video is in several mapped playlists, the first mapping in
+    /// `SERIES_PLAYLISTS` wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The video title
+    /// * `description` - The video description
+    /// * `playlists` - IDs of the playlists the video is known to belong to (see
+    ///   `video::playlists_containing`)
+    pub fn series(&self, title: &str, description: &str, playlists: &[String]) -> Option<String> {
+        self.series_playlists
+            .iter()
+            .find(|(playlist, _)| playlists.contains(playlist))
+            .map(|(_, series)| series.clone())
+            .or_else(|| capture(&self.series_pattern, &[title, description]))
+    }
+
+    /// Returns the IDs of the playlists mapped to a series.
+    pub fn series_playlist_ids(&self) -> Vec<&str> {
+        self.series_playlists
+            .iter()
+            .map(|(playlist, _)| playlist.as_str())
+            .collect()
+    }
+}
+
+/// Compiles a pattern, requiring at least one capture group for the name.
+fn compile(pattern: &str) -> Result<Regex> {
+    let re = Regex::new(pattern)?;
+    if re.captures_len() < 2 {
+        anyhow::bail!("pattern must contain a capture group: {pattern}");
+    }
+    Ok(re)
+}
+
+/// Returns the first non-empty capture of `re` in `texts`, in order.
+fn capture(re: &Regex, texts: &[&str]) -> Option<String> {
+    texts.iter().find_map(|text| {
+        re.captures(text)
+            .and_then(|cap| cap.get(1))
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|s| !s.is_empty())
+    })
+}
+
+/// Parses `Name:alias,alias;Name` into known speakers.
+pub(crate) fn parse_known_speakers(value: &str) -> Vec<KnownSpeaker> {
+    value
+        .split(';')
+        .filter_map(|entry| {
+            let (name, aliases) = entry.split_once(':').unwrap_or((entry, ""));
+            let name = name.trim();
+            (!name.is_empty()).then(|| {
+                KnownSpeaker::new(
+                    name.to_string(),
+                    aliases
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|a| !a.is_empty())
+                        .map(String::from)
+                        .collect(),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parses `PLAYLIST_ID=Series;PLAYLIST_ID=Series` into `(playlist ID, series)` pairs.
+pub(crate) fn parse_series_playlists(value: &str) -> Vec<(String, String)> {
+    value
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(id, series)| (id.trim().to_string(), series.trim().to_string()))
+        .filter(|(id, series)| !id.is_empty() && !series.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extractors() -> Extractors {
+        Extractors {
+            speaker_pattern: compile(DEFAULT_SPEAKER_PATTERN).unwrap(),
+            series_pattern: compile(DEFAULT_SERIES_PATTERN).unwrap(),
+            known_speakers: parse_known_speakers(
+                "Pastor Kofi Mensah:Ps. Kofi,Kofi Mensah;Rev. Jane Doe:Jane Doe",
+            ),
+            series_playlists: parse_series_playlists("PLfaith=Walking by Faith"),
+        }
+    }
+
+    #[test]
+    fn test_parse_known_speakers() {
+        let speakers = parse_known_speakers("A:a1, a2; B ;;");
+        assert_eq!(speakers.len(), 2);
+        assert_eq!(speakers[0].name, "A");
+        assert_eq!(speakers[0].aliases, ["a1", "a2"]);
+        assert_eq!(speakers[1].name, "B");
+        assert!(speakers[1].aliases.is_empty());
+        assert_eq!(speakers[0].find_in("Led by A1 today"), Some(7));
+        assert_eq!(speakers[0].find_in("Aa1"), None);
+    }
+
+    #[test]
+    fn test_parse_series_playlists() {
+        assert_eq!(
+            parse_series_playlists("PL1=Faith; PL2 = Hope ;bad;PL3="),
+            [
+                ("PL1".to_string(), "Faith".to_string()),
+                ("PL2".to_string(), "Hope".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_speaker_from_pattern_is_canonicalised() {
+        let e = extractors();
+        let desc = "Sunday service\nSpeaker: ps. kofi\n";
+        assert_eq!(
+            e.speaker("Sunday Service", desc).as_deref(),
+            Some("Pastor Kofi Mensah")
+        );
+        // Unknown speakers are kept as written
+        assert_eq!(
+            e.speaker("", "Preacher: Guest Minister").as_deref(),
+            Some("Guest Minister")
+        );
+    }
+
+    #[test]
+    fn test_speaker_from_known_alias_in_text() {
+        let e = extractors();
+        assert_eq!(
+            e.speaker("The Power of Prayer | Jane Doe", "").as_deref(),
+            Some("Rev. Jane Doe")
+        );
+        assert_eq!(
+            e.speaker("Sunday Service", "Today Kofi Mensah preaches")
+                .as_deref(),
+            Some("Pastor Kofi Mensah")
+        );
+        assert_eq!(e.speaker("Sunday Service", "Welcome"), None);
+    }
+
+    #[test]
+    fn test_series_prefers_playlist_mapping() {
+        let e = extractors();
+        let desc = "Series: Hope Rising";
+        let playlists = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            e.series("", desc, &playlists(&["PLsermons", "PLfaith"]))
+                .as_deref(),
+            Some("Walking by Faith")
+        );
+        assert_eq!(
+            e.series("", desc, &playlists(&["PLother"])).as_deref(),
+            Some("Hope Rising")
+        );
+        assert_eq!(e.series("", "", &[]), None);
+    }
+
+    #[test]
+    fn test_compile_requires_capture_group() {
+        assert!(compile(r"Speaker: \w+").is_err());
+        assert!(compile(r"Speaker: (\w+").is_err());
+        assert!(compile(r"Speaker: (\w+)").is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+//! Episode title and description templates.
+//!
+//! Templates are plain strings with `{name}` placeholders that are filled in from
+//! the video metadata (e.g. `"{title} | {scripture}"`). They are read from the
+//! `EPISODE_TITLE_TEMPLATE` and `EPISODE_DESCRIPTION_TEMPLATE` environment variables.
+
+use std::env;
+
+/// Title template used when `EPISODE_TITLE_TEMPLATE` is not set
+pub const DEFAULT_TITLE_TEMPLATE: &str = "{title}";
+
+/// Description template used when `EPISODE_DESCRIPTION_TEMPLATE` is not set
+pub const DEFAULT_DESCRIPTION_TEMPLATE: &str =
+    "Join us online for our Sunday services @ 9AM & 11AM.";
+
+/// Replaces every `{name}` placeholder in `template` with its value from `vars`.
+///
+/// Unknown placeholders are left untouched so that a typo in a template is visible
+/// in the published episode rather than silently removed.
+///
+/// # Arguments
+///
+/// * `template` - The template string
+/// * `vars` - Placeholder names and their values
+///
+/// # Returns
+///
+/// Returns the rendered string.
+///
+/// # Example
+///
+/// ```
+/// let rendered = audio_epistles::template::render(
+///     "{title} ({scripture})",
+///     &[("title", "Faith"), ("scripture", "Hebrews 11:1")],
+/// );
+/// assert_eq!(rendered, "Faith (Hebrews 11:1)");
+/// ```
+pub fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, close))
+        });
+
+        match value {
+            Some((value, close)) => {
+                rendered.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
+
+/// Reads a template from the environment, falling back to `default`.
+pub fn from_env(var: &str, default: &str) -> String {
+    env::var(var)
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| t.replace("\\n", "\n"))
+        .unwrap_or_else(|| default.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_replaces_placeholders() {
+        let vars = [("title", "Grace"), ("scripture", "Ephesians 2:8")];
+        assert_eq!(
+            render("{title} | {scripture}", &vars),
+            "Grace | Ephesians 2:8"
+        );
+        assert_eq!(render("{title}{title}", &vars), "GraceGrace");
+    }
+
+    #[test]
+    fn test_render_keeps_unknown_placeholders() {
+        let vars = [("title", "Grace")];
+        assert_eq!(render("{titel} - {title}", &vars), "{titel} - Grace");
+        assert_eq!(render("{ unclosed", &vars), "{ unclosed");
+        assert_eq!(render("no placeholders", &vars), "no placeholders");
+    }
+
+    #[test]
+    fn test_render_empty_value() {
+        assert_eq!(render("Text: {scripture}", &[("scripture", "")]), "Text: ");
+    }
+}
@@ -0,0 +1,223 @@
+//! Two-factor authentication module.
+//!
+//! Generates the time-based one-time passwords (RFC 6238) asked for by the Spotify
+//! login when two-factor authentication is enabled on the account. The secret is the
+//! base32 key shown when setting up an authenticator app (`SPOTIFY_TOTP_SECRET`).
+//!
+//! Accounts without a configured secret (e.g. codes sent by SMS or email) can supply
+//! the code by writing it to a file, which the login waits for.
+
+use anyhow::{anyhow, Context, Result};
+use hmac::{Hmac, KeyInit, Mac};
+use sha1::Sha1;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::info;
+
+/// File polled for a one-time code when `SPOTIFY_2FA_CODE_FILE` is not set
+pub const DEFAULT_CODE_FILE: &str = "spotify-2fa-code.txt";
+
+/// Seconds to wait for a code file when `SPOTIFY_2FA_TIMEOUT` is not set
+pub const DEFAULT_CODE_TIMEOUT_SECS: u64 = 300;
+
+/// Validity period of a code in seconds
+const TIME_STEP: u64 = 30;
+
+/// Number of digits in a code
+const DIGITS: u32 = 6;
+
+/// How the login obtains one-time codes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeSource {
+    /// Codes are generated from a TOTP secret
+    Totp(Vec<u8>),
+    /// The login waits for a code to be written to a file
+    File { path: PathBuf, timeout: Duration },
+}
+
+impl CodeSource {
+    /// Reads the code source from the environment.
+    ///
+    /// - `SPOTIFY_TOTP_SECRET`: base32 TOTP secret of the account
+    /// - `SPOTIFY_2FA_CODE_FILE`: file to wait for when there is no secret
+    ///   (default `spotify-2fa-code.txt`)
+    /// - `SPOTIFY_2FA_TIMEOUT`: seconds to wait for the file (default 300)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret is not valid base32 or the timeout is invalid.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        if let Some(secret) = var("SPOTIFY_TOTP_SECRET") {
+            let key = base32_decode(&secret).context("Invalid SPOTIFY_TOTP_SECRET")?;
+            return Ok(Self::Totp(key));
+        }
+
+        let timeout = match var("SPOTIFY_2FA_TIMEOUT") {
+            Some(v) => v
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid SPOTIFY_2FA_TIMEOUT: {v}"))?,
+            None => DEFAULT_CODE_TIMEOUT_SECS,
+        };
+        Ok(Self::File {
+            path: PathBuf::from(var("SPOTIFY_2FA_CODE_FILE").unwrap_or(DEFAULT_CODE_FILE.into())),
+            timeout: Duration::from_secs(timeout),
+        })
+    }
+
+    /// Returns a one-time code, generating it or waiting for the code file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no code is written to the file before the timeout.
+    pub async fn code(&self) -> Result<String> {
+        match self {
+            Self::Totp(key) => Ok(totp(key, chrono::Utc::now().timestamp() as u64)),
+            Self::File { path, timeout } => wait_for_code(path, *timeout).await,
+        }
+    }
+}
+
+/// Decodes an RFC 4648 base32 string, ignoring case, spaces, dashes and padding.
+///
+/// # Errors
+///
+/// Returns an error if the string contains a character outside the base32 alphabet.
+pub fn base32_decode(text: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in text.chars().filter(|c| !matches!(c, ' ' | '-' | '=')) {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            other => return Err(anyhow!("Invalid base32 character: {other}")),
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    if bytes.is_empty() {
+        return Err(anyhow!("Empty base32 secret"));
+    }
+    Ok(bytes)
+}
+
+/// Generates the 6-digit TOTP code (HMAC-SHA1, 30-second steps) for a Unix time.
+pub fn totp(key: &[u8], unix_time: u64) -> String {
+    hotp(key, unix_time / TIME_STEP)
+}
+
+/// Generates the HOTP code (RFC 4226) for a counter.
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // Dynamic truncation
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// Waits for a code to be written to `path`, then deletes the file so that the code
+/// is not reused.
+async fn wait_for_code(path: &Path, timeout: Duration) -> Result<String> {
+    // A code left over from an earlier login would be stale
+    let _ = fs::remove_file(path);
+    info!(
+        path = %path.display(),
+        timeout_secs = timeout.as_secs(),
+        "Spotify asked for a one-time code, waiting for it to be written to the code file"
+    );
+
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Ok(text) = fs::read_to_string(path) {
+            let code: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+            if !code.is_empty() {
+                let _ = fs::remove_file(path);
+                info!("One-time code received");
+                return Ok(code);
+            }
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    Err(anyhow!(
+        "Timed out waiting for a one-time code in {}",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Secret of the RFC 6238 SHA-1 test vectors ("12345678901234567890")
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn test_base32_decode() {
+        assert_eq!(
+            base32_decode(RFC_SECRET).unwrap(),
+            b"12345678901234567890".to_vec()
+        );
+        assert_eq!(
+            base32_decode("gezd gnbv gy3t qojq").unwrap(),
+            b"1234567890".to_vec()
+        );
+        assert!(base32_decode("GEZ1").is_err());
+        assert!(base32_decode("").is_err());
+    }
+
+    #[test]
+    fn test_totp_rfc_vectors() {
+        let key = base32_decode(RFC_SECRET).unwrap();
+        // Last six digits of the RFC 6238 appendix B values
+        assert_eq!(totp(&key, 59), "287082");
+        assert_eq!(totp(&key, 1111111109), "081804");
+        assert_eq!(totp(&key, 1234567890), "005924");
+        assert_eq!(totp(&key, 2000000000), "279037");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_code_file() {
+        let path = env::temp_dir().join(format!("audio_epistles_2fa_{}", std::process::id()));
+        let source = CodeSource::File {
+            path: path.clone(),
+            timeout: Duration::from_secs(5),
+        };
+
+        let writer = {
+            let path = path.clone();
+            tokio::spawn(async move {
+                sleep(Duration::from_millis(200)).await;
+                fs::write(path, "123 456\n").unwrap();
+            })
+        };
+        assert_eq!(source.code().await.unwrap(), "123456");
+        writer.await.unwrap();
+        assert!(!path.exists());
+    }
+}
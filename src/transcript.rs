@@ -0,0 +1,362 @@
+//! Speech-to-text transcript module.
+//!
+//! This module runs an offline, CPU-based speech-to-text engine (e.g. whisper.cpp's
+//! `whisper-cli`) configured through `TRANSCRIBE_COMMAND`, and converts its output
+//! into plain text, SRT, WebVTT and Podcasting 2.0 JSON transcripts.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+
+/// A timed piece of transcribed speech.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Start time in milliseconds
+    pub start_ms: u64,
+    /// End time in milliseconds
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// A transcript made of timed segments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub segments: Vec<Segment>,
+}
+
+/// Paths of the transcript files written next to the audio file.
+#[derive(Debug, Clone)]
+pub struct TranscriptFiles {
+    pub text: PathBuf,
+    pub srt: PathBuf,
+    pub vtt: PathBuf,
+    pub json: PathBuf,
+}
+
+impl TranscriptFiles {
+    /// Returns the transcript paths for an audio file (e.g. `assets/audio.srt`).
+    pub fn for_audio(audio: &Path) -> Self {
+        Self {
+            text: audio.with_extension("txt"),
+            srt: audio.with_extension("srt"),
+            vtt: audio.with_extension("vtt"),
+            json: audio.with_extension("json"),
+        }
+    }
+}
+
+/// Formats milliseconds as `HH:MM:SS<sep>mmm`.
+fn format_time(ms: u64, separator: char) -> String {
+    let (hours, rest) = (ms / 3_600_000, ms % 3_600_000);
+    let (minutes, rest) = (rest / 60_000, rest % 60_000);
+    let (seconds, millis) = (rest / 1000, rest % 1000);
+    format!("{hours:02}:{minutes:02}:{seconds:02}{separator}{millis:03}")
+}
+
+/// Parses `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm` into milliseconds.
+fn parse_time(ts: &str) -> Option<u64> {
+    let ts = ts.trim().replace(',', ".");
+    let (clock, millis) = ts.split_once('.').unwrap_or((&ts, "0"));
+    let millis: u64 = format!("{millis:0<3}").get(..3)?.parse().ok()?;
+    let parts: Vec<u64> = clock
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let seconds = match parts.as_slice() {
+        [h, m, s] => h * 3600 + m * 60 + s,
+        [m, s] => m * 60 + s,
+        _ => return None,
+    };
+    Some(seconds * 1000 + millis)
+}
+
+impl Transcript {
+    /// Parses SRT or WebVTT cues into a transcript.
+    ///
+    /// Cue numbers, the `WEBVTT` header, `NOTE` blocks and cue settings are ignored;
+    /// multi-line cue text is joined with spaces.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let normalized = input.replace("\r\n", "\n");
+
+        for block in normalized.split("\n\n") {
+            let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+            let Some(timing) = lines.next() else {
+                continue;
+            };
+            let (start, end) = timing
+                .split_once("-->")
+                .ok_or_else(|| anyhow!("Invalid cue timing: {timing}"))?;
+            // WebVTT cue settings follow the end time ("00:01.000 align:start")
+            let end = end.split_whitespace().next().unwrap_or_default();
+            let start_ms = parse_time(start).ok_or_else(|| anyhow!("Invalid time: {start}"))?;
+            let end_ms = parse_time(end).ok_or_else(|| anyhow!("Invalid time: {end}"))?;
+
+            let text = lines
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !text.is_empty() {
+                segments.push(Segment {
+                    start_ms,
+                    end_ms,
+                    text,
+                });
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Renders the transcript as plain text, one segment per line.
+    pub fn to_text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|s| format!("{}\n", s.text))
+            .collect()
+    }
+
+    /// Renders the transcript as SubRip (SRT).
+    pub fn to_srt(&self) -> String {
+        let mut srt = String::new();
+        for (i, s) in self.segments.iter().enumerate() {
+            let _ = write!(
+                srt,
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                format_time(s.start_ms, ','),
+                format_time(s.end_ms, ','),
+                s.text
+            );
+        }
+        srt
+    }
+
+    /// Renders the transcript as WebVTT.
+    pub fn to_vtt(&self) -> String {
+        let mut vtt = String::from("WEBVTT\n\n");
+        for s in &self.segments {
+            let _ = write!(
+                vtt,
+                "{} --> {}\n{}\n\n",
+                format_time(s.start_ms, '.'),
+                format_time(s.end_ms, '.'),
+                s.text
+            );
+        }
+        vtt
+    }
+
+    /// Renders the transcript in the Podcasting 2.0 JSON transcript format.
+    pub fn to_json(&self) -> String {
+        let segments: Vec<_> = self
+            .segments
+            .iter()
+            .map(|s| {
+                json!({
+                    "startTime": s.start_ms as f64 / 1000.0,
+                    "endTime": s.end_ms as f64 / 1000.0,
+                    "body": s.text,
+                })
+            })
+            .collect();
+        json!({ "version": "1.0.0", "segments": segments }).to_string()
+    }
+
+    /// Writes the text, SRT, WebVTT and JSON transcripts next to `audio`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any file cannot be written.
+    pub fn write_files(&self, audio: &Path) -> Result<TranscriptFiles> {
+        let files = TranscriptFiles::for_audio(audio);
+        fs::write(&files.text, self.to_text())?;
+        fs::write(&files.srt, self.to_srt())?;
+        fs::write(&files.vtt, self.to_vtt())?;
+        fs::write(&files.json, self.to_json())?;
+        Ok(files)
+    }
+}
+
+/// Transcribes an audio file with a local speech-to-text command.
+///
+/// The audio is first converted with ffmpeg to 16 kHz mono WAV (the input format
+/// whisper.cpp expects). The command is then run through `sh -c` after replacing
+/// `{input}` with the WAV path and `{output}` with an output path without extension,
+/// both shell-quoted; it must write `{output}.srt` or `{output}.vtt`. The WAV file is
+/// removed whether or not the command succeeds. For whisper.cpp:
+///
+/// ```text
+/// whisper-cli -m models/ggml-base.en.bin -f {input} -osrt -of {output}
+/// ```
+///
+/// # Arguments
+///
+/// * `audio` - Path to the audio file to transcribe
+/// * `command` - The speech-to-text command template
+///
+/// # Returns
+///
+/// Returns the parsed `Transcript` on success.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The ffmpeg conversion fails
+/// - The transcription command fails or exits with a non-zero status
+/// - Neither an SRT nor a WebVTT file is produced
+/// - The produced file cannot be parsed
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// let transcript = audio_epistles::transcript::transcribe(
+///     Path::new("assets/audio.mp3"),
+///     "whisper-cli -m models/ggml-base.en.bin -f {input} -osrt -of {output}",
+/// ).unwrap();
+/// println!("{}", transcript.to_text());
+/// ```
+pub fn transcribe(audio: &Path, command: &str) -> Result<Transcript> {
+    let wav = audio.with_extension("wav");
+    let output = audio.with_extension("stt");
+
+    let result = run_transcription(audio, &wav, &output, command);
+    let _ = fs::remove_file(&wav);
+    result?;
+
+    let produced = ["srt", "vtt"]
+        .iter()
+        .map(|ext| PathBuf::from(format!("{}.{ext}", output.display())))
+        .find(|path| path.exists())
+        .ok_or_else(|| anyhow!("Transcription produced no {}.srt or .vtt", output.display()))?;
+
+    let content = fs::read_to_string(&produced)?;
+    let _ = fs::remove_file(&produced);
+    Transcript::parse(&content)
+}
+
+/// Converts `audio` to `wav` and runs the transcription command on it.
+fn run_transcription(audio: &Path, wav: &Path, output: &Path, command: &str) -> Result<()> {
+    let status = Command::new("ffmpeg")
+        .args(["-y", "-i"])
+        .arg(audio)
+        .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(wav)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("ffmpeg conversion to WAV failed: {status}"));
+    }
+
+    let command = command
+        .replace("{input}", &shell_quote(&wav.to_string_lossy()))
+        .replace("{output}", &shell_quote(&output.to_string_lossy()));
+    info!(command = %command, "Transcribing audio");
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .status()
+        .context("Failed to run TRANSCRIBE_COMMAND")?;
+    if !status.success() {
+        return Err(anyhow!("Transcription command failed: {status}"));
+    }
+    Ok(())
+}
+
+/// Quotes a value for `sh` so that spaces and metacharacters are kept literal.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\r\n00:00:00,000 --> 00:00:02,500\r\nGood morning,\r\nchurch.\r\n\r\n2\r\n00:00:02,500 --> 00:01:05,040\r\nTurn to John 3.\r\n";
+
+    #[test]
+    fn test_parse_srt() {
+        let transcript = Transcript::parse(SRT).unwrap();
+        assert_eq!(
+            transcript.segments,
+            [
+                Segment {
+                    start_ms: 0,
+                    end_ms: 2500,
+                    text: "Good morning, church.".to_string()
+                },
+                Segment {
+                    start_ms: 2500,
+                    end_ms: 65040,
+                    text: "Turn to John 3.".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("assets/audio.wav"), "'assets/audio.wav'");
+        assert_eq!(
+            shell_quote("my audio; rm -rf ~/it's.wav"),
+            "'my audio; rm -rf ~/it'\\''s.wav'"
+        );
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf %s {}", shell_quote("a b;$(echo x)'c")))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "a b;$(echo x)'c");
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        let vtt = "WEBVTT\n\nNOTE generated\n\n00:01.000 --> 00:02.5 align:start\nHello\n\n1:00:00.000 --> 1:00:01.000\nAmen\n";
+        let transcript = Transcript::parse(vtt).unwrap();
+        assert_eq!(transcript.segments.len(), 2);
+        assert_eq!(transcript.segments[0].start_ms, 1000);
+        assert_eq!(transcript.segments[0].end_ms, 2500);
+        assert_eq!(transcript.segments[1].start_ms, 3_600_000);
+        assert_eq!(transcript.segments[1].text, "Amen");
+    }
+
+    #[test]
+    fn test_parse_invalid_timing() {
+        assert!(Transcript::parse("1\nab:cd --> 00:00:01,000\nHi\n").is_err());
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let transcript = Transcript::parse(SRT).unwrap();
+
+        assert_eq!(
+            transcript.to_text(),
+            "Good morning, church.\nTurn to John 3.\n"
+        );
+        assert!(transcript
+            .to_srt()
+            .starts_with("1\n00:00:00,000 --> 00:00:02,500\nGood morning, church.\n\n2\n"));
+        assert!(transcript
+            .to_vtt()
+            .starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:02.500\nGood morning, church.\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&transcript.to_json()).unwrap();
+        assert_eq!(json["version"], "1.0.0");
+        assert_eq!(json["segments"][1]["startTime"], 2.5);
+        assert_eq!(json["segments"][1]["endTime"], 65.04);
+        assert_eq!(json["segments"][1]["body"], "Turn to John 3.");
+    }
+
+    #[test]
+    fn test_srt_round_trip() {
+        let transcript = Transcript::parse(SRT).unwrap();
+        assert_eq!(Transcript::parse(&transcript.to_srt()).unwrap(), transcript);
+        assert_eq!(Transcript::parse(&transcript.to_vtt()).unwrap(), transcript);
+    }
+}
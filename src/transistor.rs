@@ -0,0 +1,385 @@
+//! Transistor.fm publisher module.
+//!
+//! The `transistor` publisher sends episodes to a show hosted on Transistor through
+//! its API (<https://developers.transistor.fm>), authenticated with an API key:
+//!
+//! 1. `GET /v1/episodes/authorize_upload` returns a pre-signed upload URL for the
+//!    audio file and the URL the uploaded audio will have
+//! 2. The audio is uploaded to the pre-signed URL
+//! 3. `POST /v1/episodes` creates a draft with the title, description, season and
+//!    episode numbers, type and explicit flag
+//! 4. `PATCH /v1/episodes/{id}/publish` publishes or schedules the episode, as set by
+//!    `TRANSISTOR_PUBLISH_MODE` (left as a draft by default)
+//!
+//! Episode artwork is not sent: Transistor only accepts images by public URL.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{Local, TimeZone};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::env;
+use std::path::Path;
+use tracing::{debug, info};
+
+use crate::editor;
+use crate::episode::Episode;
+use crate::publish::{
+    api_json, credential, upload_to_url, EpisodeStatus, PublishMode, Published, Publisher,
+};
+
+/// API used when `TRANSISTOR_API_URL` is not set
+pub const DEFAULT_API_URL: &str = "https://api.transistor.fm";
+
+/// Connection settings of a Transistor show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransistorConfig {
+    /// API base URL, without a trailing slash
+    pub api_url: String,
+    pub api_key: String,
+    /// ID of the show the episodes are added to
+    pub show_id: String,
+    /// Whether episodes are left as drafts, published or scheduled
+    pub mode: PublishMode,
+}
+
+impl TransistorConfig {
+    /// Reads the show settings from the environment.
+    ///
+    /// - `TRANSISTOR_API_KEY` (or `TRANSISTOR_API_KEY_FILE`): API key from the
+    ///   account settings (required)
+    /// - `TRANSISTOR_SHOW_ID`: ID of the show (required)
+    /// - `TRANSISTOR_PUBLISH_MODE`, `TRANSISTOR_PUBLISH_SCHEDULE`: as for Spotify
+    ///   (default `draft`)
+    /// - `TRANSISTOR_API_URL`: API base URL (default `https://api.transistor.fm`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a required variable is missing or the publish mode is invalid.
+    pub fn from_env() -> Result<Self> {
+        let show_id = env::var("TRANSISTOR_SHOW_ID")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .context("TRANSISTOR_SHOW_ID must be set")?;
+        Ok(Self {
+            api_url: env::var("TRANSISTOR_API_URL")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: credential("TRANSISTOR_API_KEY")?,
+            show_id,
+            mode: PublishMode::from_env_for("TRANSISTOR")?,
+        })
+    }
+}
+
+/// Publishes episodes to a Transistor show through its API.
+#[derive(Debug, Clone)]
+pub struct TransistorPublisher {
+    pub config: TransistorConfig,
+    http: Client,
+}
+
+impl TransistorPublisher {
+    pub fn new(config: TransistorConfig) -> Self {
+        Self {
+            config,
+            http: Client::new(),
+        }
+    }
+
+    /// Builds the publisher from the `TRANSISTOR_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the show settings are incomplete.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(TransistorConfig::from_env()?))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.config.api_url)
+    }
+
+    /// Uploads the audio file and returns the URL Transistor gave it.
+    async fn upload_audio(&self, path: &Path) -> Result<String> {
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("Invalid audio path: {}", path.display()))?;
+        let response = self
+            .http
+            .get(self.url("/v1/episodes/authorize_upload"))
+            .header("x-api-key", &self.config.api_key)
+            .query(&[("filename", &filename)])
+            .send()
+            .await?;
+        let body = api_json(response, "authorize the audio upload").await?;
+        let attributes = &body["data"]["attributes"];
+        let (Some(upload_url), Some(audio_url)) = (
+            attributes["upload_url"].as_str(),
+            attributes["audio_url"].as_str(),
+        ) else {
+            anyhow::bail!("Transistor returned no upload URL: {body}");
+        };
+
+        debug!(filename = %filename, "Uploading audio to Transistor");
+        upload_to_url(&self.http, upload_url, path).await?;
+        Ok(audio_url.to_string())
+    }
+
+    /// Publishes or schedules a created episode.
+    async fn set_status(&self, id: &str, status: EpisodeStatus) -> Result<()> {
+        let mut fields = json!({ "status": status.as_str() });
+        if let Some(at) = status.publish_at() {
+            let at = Local
+                .from_local_datetime(&at)
+                .earliest()
+                .with_context(|| format!("Invalid local time: {at}"))?;
+            fields["published_at"] = json!(at.to_rfc3339());
+        }
+        let response = self
+            .http
+            .patch(self.url(&format!("/v1/episodes/{id}/publish")))
+            .header("x-api-key", &self.config.api_key)
+            .json(&json!({ "episode": fields }))
+            .send()
+            .await?;
+        api_json(response, "publish the episode").await?;
+        Ok(())
+    }
+}
+
+/// Returns the attributes of a new episode, as sent to `POST /v1/episodes`.
+fn episode_fields(episode: &Episode, show_id: &str, audio_url: &str) -> Value {
+    let summary = episode
+        .description
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+    let mut fields = json!({
+        "show_id": show_id,
+        "title": episode.title,
+        "summary": summary,
+        "description": editor::to_html(&episode.description),
+        "type": episode.episode_type.as_str(),
+        "explicit": episode.explicit,
+        "audio_url": audio_url,
+    });
+    if let Some(season) = episode.season {
+        fields["season"] = json!(season);
+    }
+    if let Some(number) = episode.episode_number {
+        fields["number"] = json!(number);
+    }
+    fields
+}
+
+#[async_trait]
+impl Publisher for TransistorPublisher {
+    fn name(&self) -> &str {
+        "transistor"
+    }
+
+    async fn publish(&self, episode: &Episode) -> Result<Published> {
+        let audio_url = self.upload_audio(Path::new(&episode.audio_path)).await?;
+        if episode.artwork.is_some() {
+            debug!("Episode artwork is not sent to Transistor");
+        }
+
+        let response = self
+            .http
+            .post(self.url("/v1/episodes"))
+            .header("x-api-key", &self.config.api_key)
+            .json(&json!({ "episode": episode_fields(episode, &self.config.show_id, &audio_url) }))
+            .send()
+            .await?;
+        let body = api_json(response, "create the episode").await?;
+        let id = body["data"]["id"]
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| body["data"]["id"].as_u64().map(|id| id.to_string()))
+            .ok_or_else(|| anyhow!("Transistor returned no episode ID: {body}"))?;
+        let url = body["data"]["attributes"]["share_url"]
+            .as_str()
+            .map(str::to_string);
+
+        let status = self.config.mode.target_status(Local::now().naive_local());
+        if status != EpisodeStatus::Draft {
+            self.set_status(&id, status).await?;
+        }
+
+        info!(id = %id, status = %status, "Episode created on Transistor");
+        Ok(Published {
+            status,
+            remote_id: Some(id),
+            url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::EpisodeType;
+    use crate::mock_http::MockHttp;
+    use crate::publish::PublishSchedule;
+    use std::fs;
+
+    /// Answers like the Transistor API, with an upload URL on the mock itself.
+    async fn mock_transistor() -> MockHttp {
+        MockHttp::start(
+            |request, base| match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/v1/episodes/authorize_upload") => (
+                    200,
+                    json!({"data": {"attributes": {
+                        "upload_url": format!("{base}/upload/sermon.mp3?signature=abc"),
+                        "audio_url": "https://cdn.transistor.fm/sermon.mp3",
+                    }}}),
+                ),
+                ("PUT", "/upload/sermon.mp3") => (200, json!({})),
+                ("POST", "/v1/episodes") => (
+                    201,
+                    json!({"data": {"id": "1234", "attributes": {
+                        "share_url": "https://share.transistor.fm/s/1234",
+                    }}}),
+                ),
+                ("PATCH", "/v1/episodes/1234/publish") => (200, json!({"data": {"id": "1234"}})),
+                _ => (404, json!({"errors": [{"title": "Not found"}]})),
+            },
+        )
+        .await
+    }
+
+    fn publisher(api_url: &str, mode: PublishMode) -> TransistorPublisher {
+        TransistorPublisher::new(TransistorConfig {
+            api_url: api_url.to_string(),
+            api_key: "key-1".to_string(),
+            show_id: "42".to_string(),
+            mode,
+        })
+    }
+
+    fn episode(name: &str) -> Episode {
+        let audio = env::temp_dir().join(format!("transistor_{}_{name}", std::process::id()));
+        fs::write(&audio, b"audio").unwrap();
+        Episode {
+            audio_path: audio.to_string_lossy().into_owned(),
+            title: "Walking by Faith".to_string(),
+            description: "Join us online.\nhttps://example.com".to_string(),
+            season: Some(2),
+            episode_number: Some(7),
+            episode_type: EpisodeType::Bonus,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_draft() {
+        let mock = mock_transistor().await;
+        let episode = episode("draft.mp3");
+
+        let published = publisher(&mock.url, PublishMode::Draft)
+            .publish(&episode)
+            .await
+            .unwrap();
+        assert_eq!(
+            published,
+            Published {
+                status: EpisodeStatus::Draft,
+                remote_id: Some("1234".to_string()),
+                url: Some("https://share.transistor.fm/s/1234".to_string()),
+            }
+        );
+
+        let requests = mock.requests();
+        let calls: Vec<_> = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                ("GET", "/v1/episodes/authorize_upload"),
+                ("PUT", "/upload/sermon.mp3"),
+                ("POST", "/v1/episodes"),
+            ]
+        );
+        assert_eq!(requests[0].header("x-api-key"), Some("key-1"));
+        assert_eq!(
+            requests[0].param("filename"),
+            Path::new(&episode.audio_path).file_name().unwrap().to_str()
+        );
+        assert_eq!(requests[1].param("signature"), Some("abc"));
+        assert_eq!(requests[1].header("content-type"), Some("audio/mpeg"));
+        assert_eq!(requests[1].body, b"audio");
+        assert_eq!(requests[1].header("content-length"), Some("5"));
+        assert_eq!(
+            requests[2].json(),
+            json!({"episode": {
+                "show_id": "42",
+                "title": "Walking by Faith",
+                "summary": "Join us online.",
+                "description": "<p>Join us online.</p><p><a href=\"https://example.com\">\
+                                https://example.com</a></p>",
+                "season": 2,
+                "number": 7,
+                "type": "bonus",
+                "explicit": false,
+                "audio_url": "https://cdn.transistor.fm/sermon.mp3",
+            }})
+        );
+
+        let _ = fs::remove_file(&episode.audio_path);
+    }
+
+    #[tokio::test]
+    async fn test_publish_scheduled() {
+        let mock = mock_transistor().await;
+        let episode = episode("scheduled.mp3");
+        let mode = PublishMode::Schedule(PublishSchedule::parse("Sunday 18:00").unwrap());
+
+        let published = publisher(&mock.url, mode).publish(&episode).await.unwrap();
+        let at = published.status.publish_at().unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[3].path, "/v1/episodes/1234/publish");
+        let fields = &requests[3].json()["episode"];
+        assert_eq!(fields["status"], "scheduled");
+        assert_eq!(
+            fields["published_at"],
+            Local
+                .from_local_datetime(&at)
+                .earliest()
+                .unwrap()
+                .to_rfc3339()
+        );
+
+        let _ = fs::remove_file(&episode.audio_path);
+    }
+
+    #[tokio::test]
+    async fn test_api_error() {
+        let mock =
+            MockHttp::start(|_, _| (401, json!({"errors": [{"title": "Unauthorized"}]}))).await;
+        let episode = episode("error.mp3");
+
+        let err = publisher(&mock.url, PublishMode::Now)
+            .publish(&episode)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to authorize the audio upload: 401 Unauthorized: \
+             {\"errors\":[{\"title\":\"Unauthorized\"}]}"
+        );
+        assert_eq!(mock.requests().len(), 1);
+
+        let _ = fs::remove_file(&episode.audio_path);
+    }
+}
@@ -1,14 +1,15 @@
 //! YouTube video fetching module.
 //!
 //! This module provides functionality to fetch the latest video ID from a
-//! YouTube playlist by scraping the playlist page HTML.
+//! YouTube playlist by scraping the playlist page HTML, and to find which of a set
+//! of playlists a video belongs to.
 
 use anyhow::{Context, Result};
 use dotenvy::dotenv;
 use regex::Regex;
 use reqwest::get;
 use std::env;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Fetches the latest video ID from a YouTube playlist.
 ///
@@ -43,21 +44,71 @@ pub async fn fetch_video() -> Result<String> {
     dotenv().ok();
     let playlist_id = env::var("SERMON_PLAYLIST_ID")
         .context("SERMON_PLAYLIST_ID environment variable not set")?;
-    let playlist_url = format!("https://www.youtube.com/playlist?list={playlist_id}");
+    let video_id = fetch_playlist(&playlist_id).await?.pop();
 
+    match video_id {
+        Some(id) => {
+            info!(video_id = %id, "Latest video ID found");
+            Ok(id)
+        }
+        None => Err(anyhow::anyhow!("No video ID found in playlist")),
+    }
+}
+
+/// Returns the video IDs listed on a playlist page, oldest first.
+///
+/// The page lists the first videos of the playlist (about a hundred), which covers
+/// the recent sermons of a series.
+///
+/// # Errors
+///
+/// Returns an error if the HTTP request to YouTube fails.
+pub async fn fetch_playlist(playlist_id: &str) -> Result<Vec<String>> {
+    let playlist_url = format!("https://www.youtube.com/playlist?list={playlist_id}");
     let body = get(&playlist_url).await?.text().await?;
+    Ok(video_ids(&body))
+}
+
+/// Extracts the video IDs from a playlist page, in page order and without repeats.
+fn video_ids(body: &str) -> Vec<String> {
     // Regex pattern is a compile-time constant and will never fail to compile
     let re = Regex::new(r#""videoId":"([^"]+)""#).unwrap();
-    let video_id = re.captures_iter(&body).filter_map(|cap| cap.get(1)).last();
+    let mut ids: Vec<String> = Vec::new();
+    for id in re.captures_iter(body).filter_map(|cap| cap.get(1)) {
+        if !ids.iter().any(|seen| seen == id.as_str()) {
+            ids.push(id.as_str().to_string());
+        }
+    }
+    ids
+}
 
-    match video_id {
-        Some(id) => {
-            let id_str = id.as_str().to_string();
-            info!(video_id = %id_str, "Latest video ID found");
-            Ok(id_str)
+/// Returns the playlists among `playlist_ids` that list `video_id`.
+///
+/// A playlist that cannot be fetched is logged and treated as not containing the
+/// video, so that a YouTube hiccup does not stop the episode from being published.
+///
+/// # Example
+///
+/// ```no_run
+/// # tokio_test::block_on(async {
+/// let playlists =
+///     audio_epistles::video::playlists_containing("dQw4w9WgXcQ", &["PLfaith", "PLhope"]).await;
+/// # })
+/// ```
+pub async fn playlists_containing(video_id: &str, playlist_ids: &[&str]) -> Vec<String> {
+    let mut playlists = Vec::new();
+    for playlist_id in playlist_ids {
+        match fetch_playlist(playlist_id).await {
+            Ok(ids) if ids.iter().any(|id| id == video_id) => {
+                playlists.push(playlist_id.to_string())
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!(playlist_id = %playlist_id, error = %e, "Failed to fetch playlist")
+            }
         }
-        None => Err(anyhow::anyhow!("No video ID found in playlist")),
     }
+    playlists
 }
 
 #[cfg(test)]
@@ -123,6 +174,14 @@ mod tests {
         assert_eq!(last_video.unwrap().as_str(), "latest_video");
     }
 
+    #[test]
+    fn test_video_ids_deduplicated() {
+        // Playlist pages repeat IDs in thumbnails and navigation endpoints
+        let body = r#"{"videoId":"first"},{"videoId":"second"},{"videoId":"first"}"#;
+        assert_eq!(video_ids(body), ["first", "second"]);
+        assert!(video_ids("{}").is_empty());
+    }
+
     #[test]
     fn test_video_id_regex_no_match() {
         let re = Regex::new(r#""videoId":"([^"]+)""#).unwrap();
@@ -1,63 +1,93 @@
 //! Web automation module for Spotify for Podcasters.
 //!
-//! This module uses Selenium WebDriver (via chromedriver and fantoccini) to
+//! This module uses Selenium WebDriver (via chromedriver or geckodriver and
+//! fantoccini, see `browser.rs`) to
 //! automate the process of uploading podcast episodes to Spotify for Podcasters.
-//! It handles authentication, form filling, and saving episodes as drafts.
+//! It handles authentication (reusing a saved session when possible), form
+//! filling, and saving episodes as drafts or publishing them now or at a
+//! scheduled time. Uploaded episodes can later be edited, have their audio
+//! replaced, or be deleted (see `manage.rs`).
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Local;
 use dotenvy::dotenv;
+use fantoccini::elements::Element;
 use fantoccini::key::Key;
-use fantoccini::{Client, Locator};
-use rand::{rng, Rng};
+use fantoccini::Client;
 use std::env;
-use std::process::{Child, Command, Stdio};
+use std::path::Path;
 use tokio::time::{sleep, Duration};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-/// Guard struct that ensures chromedriver process is properly cleaned up
-/// even if the upload fails partway through
-struct ChromeDriverGuard {
-    process: Child,
-}
+use crate::browser::{BrowserConfig, BrowserKind, TextInput};
+use crate::challenge::{ChallengeError, Challenges};
+use crate::delay::Pause;
+use crate::diagnostics::Diagnostics;
+use crate::editor;
+use crate::episode::{Episode, EpisodeType};
+use crate::manage::Change;
+use crate::pages::Pages;
+use crate::publish::{credential, EpisodeStatus, PublishMode, Published, Publisher};
+use crate::session::SessionStore;
+use crate::totp::CodeSource;
 
-impl ChromeDriverGuard {
-    /// Start chromedriver process
-    fn new() -> std::io::Result<Self> {
-        let process = Command::new("chromedriver")
-            .arg("--port=64175")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()?;
+/// Episodes list of the Spotify for Podcasters dashboard
+const EPISODES_PATH: &str = "/pod/dashboard/episodes";
 
-        Ok(ChromeDriverGuard { process })
-    }
+/// Wizard that uploads a new episode
+const WIZARD_PATH: &str = "/pod/dashboard/episode/wizard";
+
+/// Publishes episodes to Spotify for Podcasters through browser automation.
+#[derive(Debug, Clone, Copy)]
+pub struct SpotifyPublisher {
+    /// Whether episodes are saved as drafts, published now or scheduled
+    pub mode: PublishMode,
 }
 
-impl Drop for ChromeDriverGuard {
-    fn drop(&mut self) {
-        // Attempt to kill the process
-        let _ = self.process.kill();
-        // CRITICAL: wait() to reap the process and avoid zombies
-        let _ = self.process.wait();
+impl SpotifyPublisher {
+    /// Builds the publisher from `SPOTIFY_PUBLISH_MODE` and `SPOTIFY_PUBLISH_SCHEDULE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the publish mode is invalid.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            mode: PublishMode::from_env()?,
+        })
     }
 }
 
-/// Add randomized human-like delay
-async fn human_delay(min_ms: u64, max_ms: u64) {
-    let mut rng = rng();
-    let delay_ms = rng.random_range(min_ms..=max_ms);
-    sleep(Duration::from_millis(delay_ms)).await;
+#[async_trait]
+impl Publisher for SpotifyPublisher {
+    fn name(&self) -> &str {
+        "spotify"
+    }
+
+    async fn publish(&self, episode: &Episode) -> Result<Published> {
+        upload(episode, self.mode).await
+    }
 }
 
 /// Uploads a podcast episode to Spotify for Podcasters.
 ///
 /// This function automates the complete workflow of uploading an audio file
 /// to Spotify for Podcasters:
-/// 1. Starts chromedriver
-/// 2. Navigates to Spotify for Podcasters and logs in
+/// 1. Starts chromedriver or geckodriver (`BROWSER`) on a free port, or uses the
+///    remote WebDriver in `WEBDRIVER_URL`, with the browser options of `BrowserConfig`
+/// 2. Restores the saved session (see `session.rs`), or navigates to Spotify for
+///    Podcasters and logs in if there is none or it has expired, then saves the session
 /// 3. Delegates to `draft_episode` to upload the audio and fill in episode details
-/// 4. Saves the episode as a draft
-/// 5. Cleans up by closing the browser and killing chromedriver
+///    (title, description, cover art, season and episode number, episode type and
+///    explicit flag)
+/// 4. Saves the episode as a draft, or publishes or schedules it depending on `mode`,
+///    then confirms it on the episodes list and records its Spotify episode ID and URL
+/// 5. Cleans up by closing the browser and killing the driver
+///
+/// If a step fails, a screenshot, the page HTML, URL and console log are saved
+/// (see `diagnostics.rs`) and the error carries `FailureDiagnostics`. A captcha or
+/// other bot challenge stops the run with a `ChallengeError` and a notification,
+/// or waits for an operator to solve it (see `challenge.rs`).
 ///
 /// The function uses randomized delays between actions to simulate human behavior
 /// and avoid bot detection. Authentication credentials are read from environment
@@ -65,157 +95,454 @@ async fn human_delay(min_ms: u64, max_ms: u64) {
 ///
 /// # Arguments
 ///
-/// * `title` - The episode title to use when creating the draft
+/// * `episode` - The episode to upload: its audio, title, description and details
+/// * `mode` - Whether to save a draft, publish now or schedule the episode
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success.
+/// Returns the status, Spotify episode ID and URL of the episode on success.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - chromedriver fails to start
+/// - The driver fails to start
 /// - Required environment variables are not set
 /// - WebDriver connection fails
 /// - Any web element cannot be found or interacted with
 /// - Login fails or authentication is rejected
+/// - Spotify shows a bot challenge that is not solved in time (`ChallengeError`)
 /// - Episode upload or save fails
+/// - The episode is not listed with the expected status, or its ID cannot be read
 ///
 /// # Example
 ///
 /// ```no_run
 /// # tokio_test::block_on(async {
-/// // Ensure SPOTIFY_EMAIL, SPOTIFY_PASSWORD, and AUDIO_FILE are set
-/// audio_epistles::webdriver::upload("Sunday Service | Jan 1, 2024").await.unwrap();
+/// // Ensure SPOTIFY_EMAIL and SPOTIFY_PASSWORD are set
+/// let episode = audio_epistles::episode::Episode {
+///     title: "Sunday Service | Jan 1, 2024".to_string(),
+///     description: "Join us online.".to_string(),
+///     audio_path: "assets/audio.mp3".to_string(),
+///     ..Default::default()
+/// };
+/// audio_epistles::webdriver::upload(&episode, audio_epistles::publish::PublishMode::Draft)
+///     .await
+///     .unwrap();
 /// # })
 /// ```
 #[allow(deprecated)]
 #[allow(unused_variables)]
-pub async fn upload(title: &str) -> Result<()> {
+pub async fn upload(episode: &Episode, mode: PublishMode) -> Result<Published> {
     dotenv().ok();
 
-    // Start chromedriver with proper cleanup guard
-    let _webdriver_guard = ChromeDriverGuard::new().context("Failed to start chromedriver")?;
+    // Start the driver (killed when `webdriver` is dropped) unless a remote one is set
+    let browser = BrowserConfig::from_env()?;
+    let challenges = Challenges::from_env(&browser)?;
+    let webdriver = browser.start().await?;
 
-    // Wait for chromedriver to be ready with a reasonable startup time
-    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    let pages = Pages::from_env()?;
+    let diagnostics = Diagnostics::from_env();
+    let client = browser.connect(&webdriver).await?;
 
-    let email = env::var("SPOTIFY_EMAIL").context("SPOTIFY_EMAIL must be set")?;
-    let password = env::var("SPOTIFY_PASSWORD").context("SPOTIFY_PASSWORD must be set")?;
+    let result = run(
+        &client,
+        &pages,
+        &diagnostics,
+        &challenges,
+        browser.kind,
+        episode,
+        mode,
+    )
+    .await;
+    pages.log_report();
+    let published = match result {
+        Ok(published) => published,
+        Err(e) => {
+            let e = challenges.explain(&client, &pages, e).await;
+            let e = diagnostics
+                .fail(&client, webdriver.console_log_url(), e)
+                .await;
+            let _ = client.close().await;
+            return Err(e);
+        }
+    };
 
-    let client = Client::new("http://localhost:64175")
-        .await
-        .context("Failed to connect to WebDriver")?;
+    client.close().await?;
+    // No need to manually kill the driver - dropping `webdriver` will handle it
+
+    Ok(published)
+}
+
+/// Signs in (restoring the saved session when possible) and creates the episode,
+/// recording each step in `diagnostics`.
+async fn run(
+    client: &Client,
+    pages: &Pages,
+    diagnostics: &Diagnostics,
+    challenges: &Challenges,
+    browser: BrowserKind,
+    episode: &Episode,
+    mode: PublishMode,
+) -> Result<Published> {
+    sign_in(client, pages, diagnostics, challenges).await?;
+
+    diagnostics.enter("create_episode");
+    draft_episode(episode, mode, client, pages, browser).await
+}
+
+/// Restores the saved session, logging in only when there is none or it has
+/// expired, and saves the (refreshed) session.
+///
+/// A bot challenge shown on the way is handed to `challenges`; it is not mistaken
+/// for an expired session.
+async fn sign_in(
+    client: &Client,
+    pages: &Pages,
+    diagnostics: &Diagnostics,
+    challenges: &Challenges,
+) -> Result<()> {
+    // Reuse the saved session if there is one, logging in only when it has expired
+    let store = SessionStore::from_env();
+    diagnostics.enter("restore_session");
+    let restored = match &store {
+        Some(store) => match restore_session(client, pages, store, challenges).await {
+            Ok(restored) => restored,
+            Err(e) if e.downcast_ref::<ChallengeError>().is_some() => return Err(e),
+            Err(e) => {
+                warn!(error = %e, "Failed to restore Spotify session");
+                false
+            }
+        },
+        None => false,
+    };
+    if !restored {
+        diagnostics.enter("login");
+        login(client, pages, challenges).await?;
+    }
+    if let Some(store) = &store {
+        // Saved on every run so that cookies refreshed by Spotify are kept
+        if let Err(e) = save_session(client, store).await {
+            warn!(error = %e, "Failed to save Spotify session");
+        }
+    }
+
+    Ok(())
+}
+
+/// An uploaded episode to change on Spotify for Podcasters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotifyEpisode {
+    /// Current title, used to find the episode on the episodes list
+    pub title: String,
+    /// Page of the episode recorded when it was uploaded, if any
+    pub url: Option<String>,
+}
+
+/// Edits, replaces the audio of, or deletes an uploaded episode on Spotify for
+/// Podcasters.
+///
+/// The browser is started and signed in the same way as for `upload`. The episode
+/// is opened from its recorded URL or, without one, from the episodes list by its
+/// title, then:
+/// - `Change::Edit` replaces the title and/or description in the edit form
+/// - `Change::ReplaceAudio` uploads the new audio file in the edit form and waits
+///   for it to be processed
+/// - `Change::Delete` deletes the episode from its options menu
+///
+/// Edits are confirmed by finding the episode on the episodes list under its
+/// (new) title, and deletions by the episode no longer being listed. If a step
+/// fails, diagnostics are captured as for `upload`.
+///
+/// # Arguments
+///
+/// * `episode` - The episode to change
+/// * `change` - The change to apply
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The driver fails to start, or login fails (including an unsolved bot challenge)
+/// - The episode cannot be found, or a form field or button is missing
+/// - The new audio file is rejected or still uploading after the upload timeout
+/// - The change cannot be confirmed on the episodes list
+///
+/// # Example
+///
+/// ```no_run
+/// # tokio_test::block_on(async {
+/// let episode = audio_epistles::webdriver::SpotifyEpisode {
+///     title: "Sunday Service | Jan 1, 2024".to_string(),
+///     url: None,
+/// };
+/// let change = audio_epistles::manage::Change::Edit {
+///     title: Some("Sunday Service | Jan 7, 2024".to_string()),
+///     description: None,
+/// };
+/// audio_epistles::webdriver::change_episode(&episode, &change)
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+pub async fn change_episode(episode: &SpotifyEpisode, change: &Change) -> Result<()> {
+    dotenv().ok();
+
+    let browser = BrowserConfig::from_env()?;
+    let challenges = Challenges::from_env(&browser)?;
+    let webdriver = browser.start().await?;
+
+    let pages = Pages::from_env()?;
+    let diagnostics = Diagnostics::from_env();
+    let client = browser.connect(&webdriver).await?;
 
-    client.goto("https://podcasters.spotify.com/").await?;
-    human_delay(6000, 9000).await;
+    let result = async {
+        sign_in(&client, &pages, &diagnostics, &challenges).await?;
+        diagnostics.enter("open_episode");
+        open_episode(&client, &pages, episode).await?;
+        diagnostics.enter(change.action());
+        match change {
+            Change::Edit { title, description } => {
+                edit_details(
+                    &client,
+                    &pages,
+                    browser.kind,
+                    title.as_deref(),
+                    description.as_deref(),
+                )
+                .await?;
+                let title = title.as_deref().unwrap_or(&episode.title);
+                confirm_listed(&client, &pages, title).await
+            }
+            Change::ReplaceAudio { audio_path } => {
+                replace_audio(&client, &pages, Path::new(audio_path)).await?;
+                confirm_listed(&client, &pages, &episode.title).await
+            }
+            Change::Delete => delete_episode(&client, &pages, &episode.title).await,
+        }
+    }
+    .await;
+    pages.log_report();
+    if let Err(e) = result {
+        let e = challenges.explain(&client, &pages, e).await;
+        let e = diagnostics
+            .fail(&client, webdriver.console_log_url(), e)
+            .await;
+        let _ = client.close().await;
+        return Err(e);
+    }
+
+    client.close().await?;
+    Ok(())
+}
+
+/// Logs in to Spotify for Podcasters with `SPOTIFY_EMAIL` and `SPOTIFY_PASSWORD`
+/// (or the files named in `SPOTIFY_EMAIL_FILE` and `SPOTIFY_PASSWORD_FILE`, see
+/// `publish::credential`).
+///
+/// If Spotify asks for a one-time code, it is generated from `SPOTIFY_TOTP_SECRET`
+/// or, without a secret, read from the code file once an operator writes it there
+/// (see `totp.rs`).
+///
+/// # Errors
+///
+/// Returns an error if the credentials are not set, any login step fails, the
+/// one-time code is missing or rejected, or Spotify shows a bot challenge that is
+/// not solved in time (`ChallengeError`).
+#[allow(deprecated)]
+async fn login(client: &Client, pages: &Pages, challenges: &Challenges) -> Result<()> {
+    let email = credential("SPOTIFY_EMAIL")?;
+    let password = credential("SPOTIFY_PASSWORD")?;
+
+    client.goto(&pages.url("/")).await?;
+    pages.pause(Pause::SlowPage).await;
 
     // Click 'Log in'
-    client
-        .find(Locator::Css("a[href='/pod/login']"))
-        .await?
-        .click()
-        .await?;
-    human_delay(3500, 4500).await;
+    pages.find(client, "login.link").await?.click().await?;
+    pages.pause(Pause::Step).await;
 
     // Click 'Continue with Spotify'
-    client
-        .find(Locator::XPath(
-            "//span[text()='Continue with Spotify']/ancestor::button",
-        ))
+    pages
+        .find(client, "login.continue_with_spotify")
         .await?
         .click()
         .await?;
-    human_delay(3500, 4500).await;
+    pages.pause(Pause::Step).await;
 
     // Input email
-    client
-        .find(Locator::Css("input#login-username"))
+    pages
+        .find(client, "login.email_input")
         .await?
         .send_keys(&email)
         .await?;
-    human_delay(1000, 2000).await;
+    pages.pause(Pause::Field).await;
 
     // Click 'Continue'
-    client
-        .find(Locator::Css("button#login-button"))
+    pages
+        .find(client, "login.continue_button")
         .await?
         .click()
         .await?;
-    human_delay(6800, 7800).await;
+    pages.pause(Pause::SlowPage).await;
+    challenges.check(client, pages).await?;
 
     // Click 'Log in with a password'
-    if let Ok(password_btn) = client
-        .find(Locator::Css(
-            "button[data-encore-id='buttonTertiary'].Button-sc-1dqy6lx-0",
-        ))
-        .await
-    {
+    if let Some(password_btn) = pages.try_find(client, "login.password_option").await {
         password_btn.click().await?;
-        human_delay(1200, 2000).await;
+        pages.pause(Pause::Click).await;
     }
-    human_delay(6200, 8200).await;
+    pages.pause(Pause::SlowPage).await;
 
     // Input password
-    client
-        .wait()
-        .for_element(Locator::Css("input[data-testid='login-password']"))
+    pages
+        .wait_for(client, "login.password_input")
         .await?
         .send_keys(&password)
         .await?;
-    human_delay(7700, 8800).await;
+    pages.pause(Pause::SlowPage).await;
 
     // Click 'Log In'
-    let login_btn = client
-    .wait()
-    .for_element(Locator::XPath(
-        "//button[@id='login-button' or @data-testid='login-button' or .//span[translate(normalize-space(text()), 'abcdefghijklmnopqrstuvwxyz', 'ABCDEFGHIJKLMNOPQRSTUVWXYZ')='LOG IN']]",
-    ))
-    .await?;
+    pages
+        .wait_for(client, "login.submit_button")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::Page).await;
+    challenges.check(client, pages).await?;
 
-    login_btn.click().await?;
-    human_delay(4000, 6000).await;
+    // Accounts with two-factor authentication are asked for a one-time code
+    if let Some(code_input) = pages.try_find(client, "login.one_time_code_input").await {
+        let code = CodeSource::from_env()?.code().await?;
+        code_input.send_keys(&code).await?;
+        pages.pause(Pause::Click).await;
 
-    info!("Spotify login successful");
+        pages
+            .find(client, "login.one_time_code_submit")
+            .await?
+            .click()
+            .await?;
+        pages.pause(Pause::Page).await;
 
-    draft_episode(title, &client).await?;
+        if pages
+            .try_find(client, "login.one_time_code_input")
+            .await
+            .is_some()
+        {
+            return Err(anyhow!("Spotify rejected the one-time code"));
+        }
+        info!("Two-factor authentication passed");
+    }
 
-    client.close().await?;
-    // No need to manually kill webdriver - ChromeDriverGuard's Drop will handle it
+    info!("Spotify login successful");
 
     Ok(())
 }
 
+/// Restores a saved session and checks that Spotify still accepts it.
+///
+/// # Returns
+///
+/// Returns `true` if the episodes dashboard opens without a login prompt, and
+/// `false` if no session is saved or it has expired (the expired session is deleted).
+///
+/// # Errors
+///
+/// Returns an error if the session file cannot be read or the browser fails, or a
+/// `ChallengeError` if Spotify shows a bot challenge that is not solved in time.
+async fn restore_session(
+    client: &Client,
+    pages: &Pages,
+    store: &SessionStore,
+    challenges: &Challenges,
+) -> Result<bool> {
+    let Some(cookies) = store.load()? else {
+        info!("No saved Spotify session, logging in");
+        return Ok(false);
+    };
+
+    // Cookies can only be added for the domain of the current page
+    client.goto(&pages.url("/")).await?;
+    pages.pause(Pause::Dialog).await;
+    let mut added = 0;
+    for cookie in cookies {
+        let name = cookie.name().to_string();
+        match client.add_cookie(cookie).await {
+            Ok(()) => added += 1,
+            Err(e) => debug!(cookie = %name, error = %e, "Skipping cookie for another domain"),
+        }
+    }
+
+    client.goto(&pages.url(EPISODES_PATH)).await?;
+    pages.pause(Pause::Page).await;
+    challenges.check(client, pages).await?;
+    if is_logged_in(client, pages).await? {
+        info!(cookies = added, "Restored saved Spotify session");
+        return Ok(true);
+    }
+
+    info!("Saved Spotify session expired, logging in");
+    client.delete_all_cookies().await?;
+    store.clear()?;
+    Ok(false)
+}
+
+/// Returns true if the current page belongs to a logged-in dashboard rather than
+/// the login flow.
+async fn is_logged_in(client: &Client, pages: &Pages) -> Result<bool> {
+    let url = client.current_url().await?;
+    if url.host_str() == Some("accounts.spotify.com") || url.path().contains("login") {
+        return Ok(false);
+    }
+    // The login link is only shown to signed-out visitors
+    let login_link = pages.try_find(client, "login.link").await;
+    Ok(url.path().starts_with("/pod/dashboard") && login_link.is_none())
+}
+
+/// Saves the cookies of the logged-in browser.
+async fn save_session(client: &Client, store: &SessionStore) -> Result<()> {
+    let cookies = client.get_all_cookies().await?;
+    store.save(&cookies)
+}
+
 /// Creates a draft episode on Spotify for Podcasters.
 ///
 /// This function handles the episode creation workflow after authentication:
 /// 1. Navigates to the episode upload wizard
-/// 2. Uploads the audio file specified in the `AUDIO_FILE` environment variable
+/// 2. Uploads the audio file at `audio_path`
 /// 3. Fills in the episode title
-/// 4. Sets a default description
-/// 5. Saves the episode as a draft
+/// 4. Fills in the episode description
+/// 5. Sets the cover art, season and episode number, episode type and explicit
+///    flag with `set_episode_details`
+/// 6. Saves the episode as a draft, or hands over to `schedule_episode` to publish
+///    it now or at the next scheduled slot
+/// 7. Confirms the episode on the episodes list with `verify_episode`
 ///
-/// The audio file path must be an absolute path and is read from the `AUDIO_FILE`
-/// environment variable. The function uses character-by-character input for the
-/// description to work around Spotify's Slate.js rich text editor.
+/// The audio path is made absolute before it is sent to the file input, as the
+/// browser requires. The description is pasted into Spotify's Slate.js rich text
+/// editor and read back; if the editor's text differs, it is typed the way
+/// `browser` handles it (see `BrowserKind::description_input`): character by
+/// character in Chrome, in one send in Firefox.
 ///
 /// # Arguments
 ///
-/// * `title` - The episode title to set
+/// * `episode` - The episode to create: its audio, title, description and details
+/// * `mode` - Whether to save a draft, publish now or schedule the episode
 /// * `client` - A reference to the authenticated WebDriver client
+/// * `pages` - The named elements of the Spotify pages
+/// * `browser` - The browser `client` drives
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success.
+/// Returns the status the episode was left in, with its Spotify episode ID and URL.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The `AUDIO_FILE` environment variable is not set
+/// - The audio or artwork file does not exist
 /// - Navigation to the episode wizard fails
-/// - Audio file upload fails or times out
+/// - Audio file upload is rejected, or still running after the upload timeout
+///   (`UploadError`)
 /// - Any form field cannot be found or filled
-/// - Saving the draft fails
+/// - Saving the draft, publishing or scheduling fails
+/// - The episode is not on the episodes list with the expected status and an ID
 ///
 /// # Example
 ///
@@ -224,148 +551,1059 @@ pub async fn upload(title: &str) -> Result<()> {
 /// # tokio_test::block_on(async {
 /// let client = Client::new("http://localhost:64175").await.unwrap();
 /// // ... authenticate first ...
-/// audio_epistles::webdriver::draft_episode("My Episode", &client).await.unwrap();
+/// let episode = audio_epistles::episode::Episode {
+///     title: "My Episode".to_string(),
+///     description: "Join us online.".to_string(),
+///     audio_path: "assets/audio.mp3".to_string(),
+///     ..Default::default()
+/// };
+/// let mode = audio_epistles::publish::PublishMode::Draft;
+/// let pages = audio_epistles::pages::Pages::from_env().unwrap();
+/// audio_epistles::webdriver::draft_episode(
+///     &episode,
+///     mode,
+///     &client,
+///     &pages,
+///     audio_epistles::browser::BrowserKind::Chrome,
+/// )
+/// .await
+/// .unwrap();
 /// # })
 /// ```
 #[allow(deprecated)]
-pub async fn draft_episode(title: &str, client: &Client) -> Result<()> {
+pub async fn draft_episode(
+    episode: &Episode,
+    mode: PublishMode,
+    client: &Client,
+    pages: &Pages,
+    browser: BrowserKind,
+) -> Result<Published> {
     dotenv().ok();
+    let title = episode.title.as_str();
+    let description = episode.description.as_str();
+    let audio_path = Path::new(&episode.audio_path);
 
     // Go to episode upload wizard (logged in already)
-    human_delay(9000, 10000).await;
-    client
-        .goto("https://podcasters.spotify.com/pod/dashboard/episode/wizard")
-        .await?;
-    human_delay(7600, 9300).await;
+    pages.pause(Pause::SlowPage).await;
+    client.goto(&pages.url(WIZARD_PATH)).await?;
+    pages.pause(Pause::SlowPage).await;
 
     // Upload audio
-    if let Ok(select_btn) = client
-        .find(Locator::XPath(
-            "//span[text()='Select a file']/ancestor::button",
-        ))
-        .await
-    {
+    if let Some(select_btn) = pages.try_find(client, "wizard.select_file_button").await {
         select_btn.click().await?;
     }
-    human_delay(2200, 3000).await;
+    pages.pause(Pause::Dialog).await;
 
-    let audio_file_path = env::var("AUDIO_FILE").context("AUDIO_FILE must be set")?;
-    client
-        .find(Locator::Css("input[type='file']"))
+    let audio_file_path = audio_path
+        .canonicalize()
+        .with_context(|| format!("Audio file not found: {}", audio_path.display()))?;
+    pages
+        .find(client, "wizard.file_input")
         .await?
-        .send_keys(&audio_file_path)
+        .send_keys(&audio_file_path.to_string_lossy())
         .await?;
 
-    human_delay(55800, 62400).await;
+    let size = std::fs::metadata(&audio_file_path)?.len();
+    let timeout = UploadTimeouts::from_env()?.for_size(size);
+    wait_for_upload(client, pages, timeout).await?;
+    pages.pause(Pause::Click).await;
     debug!("Audio uploaded");
 
     // Set title
-    client
-        .find(Locator::Css("input#title-input"))
+    pages
+        .find(client, "wizard.title_input")
         .await?
         .send_keys(title)
         .await?;
-    human_delay(1000, 2000).await;
+    pages.pause(Pause::Field).await;
     debug!("Title entered");
 
-    let description = "Join us online for our Sunday services @ 9AM & 11AM.";
-    let desc_field = client
-        .find(Locator::Css(
-            "div[role='textbox'][data-slate-editor='true']",
-        ))
+    type_description(client, pages, browser, description).await?;
+    debug!("Description entered");
+    pages.pause(Pause::Field).await;
+
+    set_episode_details(client, pages, episode).await?;
+
+    let status = mode.target_status(Local::now().naive_local());
+    if status != EpisodeStatus::Draft {
+        schedule_episode(client, pages, &status).await?;
+        return verify_episode(client, pages, title, status).await;
+    }
+
+    // Click 'Close' to trigger save-draft modal/dialog
+    if let Some(close_btn) = pages.try_find(client, "wizard.close_button").await {
+        close_btn.click().await?;
+    }
+    pages.pause(Pause::Dialog).await;
+
+    // Click 'Save draft' in the dialog
+    pages
+        .wait_for(client, "wizard.save_draft_button")
+        .await
+        .context("Save draft dialog did not open")?
+        .click()
         .await?;
+    pages.pause(Pause::Click).await;
 
-    // Focus the description field (double click for some editors)
-    desc_field.click().await?;
-    human_delay(200, 400).await;
-    desc_field.click().await?;
-    human_delay(400, 800).await;
+    // Allow Spotify UI to settle
+    pages.pause(Pause::Settle).await;
 
-    // Select all (Cmd + A on macOS, Ctrl + A otherwise)
+    let published = verify_episode(client, pages, title, EpisodeStatus::Draft).await?;
+    info!("Episode successfully saved as draft");
+
+    Ok(published)
+}
+
+/// Selects all text of a field and deletes it (Cmd + A on macOS, Ctrl + A otherwise).
+async fn clear_field(pages: &Pages, field: &Element) -> Result<()> {
     #[cfg(target_os = "macos")]
-    desc_field.send_keys(&(Key::Meta + "a")).await?;
+    field.send_keys(&(Key::Meta + "a")).await?;
     #[cfg(not(target_os = "macos"))]
-    desc_field.send_keys(&(Key::Control + "a")).await?;
+    field.send_keys(&(Key::Control + "a")).await?;
 
-    human_delay(200, 400).await;
+    pages.pause(Pause::Focus).await;
 
     // Delete selected text
-    desc_field.send_keys(&Key::Backspace).await?;
-    human_delay(400, 800).await;
+    field.send_keys(&Key::Backspace).await?;
+    pages.pause(Pause::Erase).await;
+
+    Ok(())
+}
+
+/// Replaces the text of the Slate.js description editor with `description`.
+///
+/// The description is pasted through a script (see `editor.rs`) and read back. When
+/// the editor's text differs, it is cleared and the description typed instead, the
+/// way `browser`'s driver gets it through (see `BrowserKind::description_input`).
+/// A typed description that still differs is an error, so that the episode is never
+/// saved with a corrupted description.
+async fn type_description(
+    client: &Client,
+    pages: &Pages,
+    browser: BrowserKind,
+    description: &str,
+) -> Result<()> {
+    let desc_field = pages.find(client, "wizard.description_editor").await?;
+
+    // Focus the description field (double click for some editors)
+    desc_field.click().await?;
+    pages.pause(Pause::Focus).await;
+    desc_field.click().await?;
+    pages.pause(Pause::Erase).await;
+
+    clear_field(pages, &desc_field).await?;
 
     // Click to ensure focus is reset
     desc_field.click().await?;
-    human_delay(200, 400).await;
+    pages.pause(Pause::Focus).await;
 
-    // Set description (works better for Spotify's text editor Slate.js)
-    for c in description.chars() {
-        desc_field.send_keys(&c.to_string()).await?;
-        human_delay(50, 120).await;
+    editor::paste(client, &desc_field, description).await?;
+    pages.pause(Pause::Field).await;
+
+    let pasted = editor::read_text(client, &desc_field).await?;
+    if editor::same_text(description, &pasted) {
+        debug!("Description pasted");
+        return Ok(());
     }
-    debug!("Description entered");
-    human_delay(1000, 2000).await;
+    warn!(
+        expected = description.chars().count(),
+        pasted = pasted.chars().count(),
+        "Pasted description does not match; typing it instead"
+    );
 
-    // schedule_episode(&client).await?;
+    clear_field(pages, &desc_field).await?;
+    desc_field.click().await?;
+    pages.pause(Pause::Focus).await;
 
-    // Click 'Close' to trigger save-draft modal/dialog
-    if let Ok(close_btn) = client
-        .find(Locator::Css(
-            "button[aria-label='Close'][data-encore-id='buttonTertiary']",
-        ))
-        .await
-    {
-        close_btn.click().await?;
+    match browser.description_input() {
+        TextInput::PerCharacter => {
+            for c in description.chars() {
+                desc_field.send_keys(&c.to_string()).await?;
+                pages.pause(Pause::Keystroke).await;
+            }
+        }
+        TextInput::Whole => desc_field.send_keys(description).await?,
     }
-    human_delay(2700, 3100).await;
 
-    // Click 'Save draft' in the dialog
-    if let Ok(save_btn) = client
-        .find(Locator::XPath(
-            "//span[text()='Save draft']/ancestor::button",
-        ))
-        .await
-    {
-        save_btn.click().await?;
+    let typed = editor::read_text(client, &desc_field).await?;
+    if let Some(difference) = editor::first_difference(description, &typed) {
+        return Err(anyhow!(
+            "Description does not match after pasting and typing it ({} of {} characters, {difference})",
+            typed.chars().count(),
+            description.chars().count()
+        ));
     }
+    debug!("Description typed");
 
-    human_delay(1200, 2000).await;
-    info!("Episode successfully saved as draft");
+    Ok(())
+}
 
-    // Allow Spotify UI to settle
-    human_delay(3000, 5000).await;
+/// Why waiting for an uploaded audio file failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadError {
+    /// The upload or processing had not finished when the timeout expired
+    StillRunning {
+        /// Last upload progress shown, in percent
+        progress: Option<u8>,
+        waited: Duration,
+    },
+    /// Spotify reported an error for the file
+    Rejected(String),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StillRunning { progress, waited } => {
+                write!(f, "Upload still running after {}s", waited.as_secs())?;
+                if let Some(progress) = progress {
+                    write!(f, " ({progress}% uploaded)")?;
+                }
+                Ok(())
+            }
+            Self::Rejected(message) => write!(f, "Upload rejected by Spotify: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for UploadError {}
+
+/// How long to wait for an audio upload, scaled by the size of the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UploadTimeouts {
+    /// Time allowed regardless of the file size (processing, page updates)
+    pub base: Duration,
+    /// Extra time allowed per MiB of audio
+    pub per_mib: Duration,
+}
+
+impl Default for UploadTimeouts {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(120),
+            per_mib: Duration::from_secs(10),
+        }
+    }
+}
+
+impl UploadTimeouts {
+    /// Reads `SPOTIFY_UPLOAD_TIMEOUT` (base seconds, default 120) and
+    /// `SPOTIFY_UPLOAD_SECS_PER_MB` (seconds per MiB, default 10).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not a whole number of seconds.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| -> Result<Duration> {
+            match env::var(name).ok().filter(|v| !v.trim().is_empty()) {
+                Some(v) => Ok(Duration::from_secs(
+                    v.trim()
+                        .parse()
+                        .with_context(|| format!("Invalid {name}: {v}"))?,
+                )),
+                None => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            base: secs("SPOTIFY_UPLOAD_TIMEOUT", defaults.base)?,
+            per_mib: secs("SPOTIFY_UPLOAD_SECS_PER_MB", defaults.per_mib)?,
+        })
+    }
+
+    /// Returns the timeout for a file of `size` bytes, counting partial MiBs as whole.
+    pub fn for_size(&self, size: u64) -> Duration {
+        let mib = size.div_ceil(1024 * 1024) as u32;
+        self.base + self.per_mib * mib
+    }
+}
+
+/// Extracts the percentage from an upload progress label such as "Uploading 42%"
+/// or "Uploading 42.5%".
+fn parse_progress(text: &str) -> Option<u8> {
+    let end = text.find('%')?;
+    let number: String = text[..end]
+        .trim_end()
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    parse_progress_value(&number)
+}
+
+/// Reads a progress value such as the `aria-valuenow` of a progress bar ("42" or
+/// "100.0"), rounded down so that an unfinished upload never reads as 100%.
+fn parse_progress_value(value: &str) -> Option<u8> {
+    let value: f64 = value.trim().parse().ok()?;
+    (0.0..=100.0).contains(&value).then(|| value.floor() as u8)
+}
+
+/// Waits until the audio sent to the file input has been uploaded and processed.
+///
+/// The upload is finished once the progress indicator is gone or at 100%, no
+/// processing indicator is shown, and the title field is enabled.
+///
+/// # Errors
+///
+/// Returns an `UploadError` if Spotify shows an upload error or the upload has not
+/// finished within `timeout`, or an error if the browser fails.
+async fn wait_for_upload(client: &Client, pages: &Pages, timeout: Duration) -> Result<()> {
+    info!(timeout_secs = timeout.as_secs(), "Waiting for audio upload");
+    let started = tokio::time::Instant::now();
+    let mut progress = None;
+
+    loop {
+        if let Some(error) = pages.try_find(client, "wizard.upload_error").await {
+            let message = error.text().await.unwrap_or_default();
+            return Err(UploadError::Rejected(message.trim().to_string()).into());
+        }
+
+        let mut uploading = false;
+        if let Some(bar) = pages.try_find(client, "wizard.upload_progress").await {
+            let current = match bar.attr("aria-valuenow").await? {
+                Some(value) => parse_progress_value(&value),
+                None => parse_progress(&bar.text().await.unwrap_or_default()),
+            };
+            if current.is_some() && current != progress {
+                debug!(progress = ?current, "Upload progress");
+                progress = current;
+            }
+            uploading = current != Some(100);
+        }
+        let processing = pages
+            .try_find(client, "wizard.processing_indicator")
+            .await
+            .is_some();
+        let title_enabled = match pages.try_find(client, "wizard.title_input").await {
+            Some(input) => input.is_enabled().await?,
+            None => false,
+        };
+
+        if !uploading && !processing && title_enabled {
+            info!(
+                waited_secs = started.elapsed().as_secs(),
+                "Audio upload finished"
+            );
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            return Err(UploadError::StillRunning {
+                progress,
+                waited: started.elapsed(),
+            }
+            .into());
+        }
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Sets the cover art, season and episode number, episode type and explicit flag.
+///
+/// Only details the episode has are set: numbers when numbering is enabled, the
+/// episode type when it is not `full` and the cover art when there is one. The
+/// explicit-content answer is always set when Spotify shows the question.
+///
+/// # Errors
+///
+/// Returns an error if the artwork file does not exist or a field for a detail
+/// the episode has cannot be found or filled.
+async fn set_episode_details(client: &Client, pages: &Pages, episode: &Episode) -> Result<()> {
+    if let Some(artwork) = &episode.artwork {
+        let artwork_path = Path::new(artwork)
+            .canonicalize()
+            .with_context(|| format!("Artwork file not found: {artwork}"))?;
+        pages
+            .find(client, "wizard.artwork_input")
+            .await?
+            .send_keys(&artwork_path.to_string_lossy())
+            .await?;
+        pages.pause(Pause::Click).await;
+        // Spotify may ask to confirm the crop of the image
+        if let Some(save_btn) = pages.try_find(client, "wizard.artwork_save_button").await {
+            save_btn.click().await?;
+            pages.pause(Pause::Confirm).await;
+        }
+        debug!("Artwork uploaded");
+    }
+
+    for (name, number) in [
+        ("wizard.season_input", episode.season),
+        ("wizard.episode_number_input", episode.episode_number),
+    ] {
+        if let Some(number) = number {
+            pages
+                .find(client, name)
+                .await?
+                .send_keys(&number.to_string())
+                .await?;
+            pages.pause(Pause::Choice).await;
+        }
+    }
+
+    let episode_type = [("type", episode.episode_type.as_str())];
+    if episode.episode_type != EpisodeType::Full {
+        pages
+            .find_with(client, "wizard.episode_type_option", &episode_type)
+            .await?
+            .click()
+            .await?;
+        pages.pause(Pause::Choice).await;
+    }
+
+    // "No" is the default answer, so it is only a required field for explicit episodes
+    if episode.explicit {
+        pages
+            .find(client, "wizard.explicit_yes")
+            .await?
+            .click()
+            .await?;
+        pages.pause(Pause::Choice).await;
+    } else if let Some(clean) = pages.try_find(client, "wizard.explicit_no").await {
+        clean.click().await?;
+        pages.pause(Pause::Choice).await;
+    }
+    debug!(season = ?episode.season, episode = ?episode.episode_number, episode_type = episode.episode_type.as_str(), explicit = episode.explicit, "Episode details set");
 
     Ok(())
 }
 
-/// Handles publishing episode to Spotify/Anchor.fm
-#[allow(dead_code)]
-async fn schedule_episode(client: &Client) -> Result<()> {
+/// Handles publishing episode to Spotify/Anchor.fm, either now or at the time of a
+/// `Scheduled` status
+async fn schedule_episode(client: &Client, pages: &Pages, status: &EpisodeStatus) -> Result<()> {
     // Click 'Next' button
-    if let Ok(next_button) = client
-        .find(Locator::Css("button[form='details-form'][type='submit']"))
+    pages
+        .find(client, "wizard.next_button")
+        .await?
+        .click()
+        .await?;
+    debug!("Clicked 'Next'");
+    pages.pause(Pause::Review).await; // Wait for UI to load
+
+    match status.publish_at() {
+        None => {
+            // Click 'Now' option
+            pages
+                .find(client, "review.publish_now_option")
+                .await?
+                .click()
+                .await?;
+            debug!("Selected 'Now' for publishing");
+        }
+        Some(at) => {
+            // Click 'Schedule' option and enter the local date and time
+            pages
+                .find(client, "review.schedule_option")
+                .await?
+                .click()
+                .await?;
+            pages.pause(Pause::Field).await;
+
+            let date_field = pages.find(client, "review.date_input").await?;
+            date_field.clear().await?;
+            date_field
+                .send_keys(&at.format("%m/%d/%Y").to_string())
+                .await?;
+            pages.pause(Pause::Confirm).await;
+
+            let time_field = pages.find(client, "review.time_input").await?;
+            time_field.clear().await?;
+            time_field
+                .send_keys(&at.format("%I:%M %p").to_string())
+                .await?;
+            debug!(publish_at = %at, "Selected publish date and time");
+        }
+    }
+    pages.pause(Pause::Settle).await;
+
+    // Click 'Publish'/'Schedule' button
+    pages
+        .find(client, "review.submit_button")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::SlowPage).await;
+    info!(status = %status, "Episode successfully submitted");
+
+    Ok(())
+}
+
+/// Checks on the episodes list that the episode titled `title` exists with the
+/// expected status, and reads its Spotify episode ID and URL from its link.
+///
+/// # Errors
+///
+/// Returns an error if the episode is not listed, has another status, or has no
+/// episode link, so that an unconfirmed episode is never recorded as uploaded.
+async fn verify_episode(
+    client: &Client,
+    pages: &Pages,
+    title: &str,
+    expected: EpisodeStatus,
+) -> Result<Published> {
+    client.goto(&pages.url(EPISODES_PATH)).await?;
+    pages.pause(Pause::Page).await;
+
+    let title_literal = xpath_literal(title.trim());
+    pages
+        .wait_for_with(client, "episodes.row", &[("title", &title_literal)])
         .await
-    {
-        next_button.click().await?;
-        debug!("Clicked 'Next'");
+        .with_context(|| format!("Episode not found in episodes list: {title}"))?;
+    let label = pages
+        .find_with(client, "episodes.status", &[("title", &title_literal)])
+        .await
+        .with_context(|| format!("No status for \"{title}\" in episodes list"))?
+        .text()
+        .await?;
+
+    if !expected.matches_label(&label) {
+        return Err(anyhow!(
+            "Episode \"{title}\" is not {}: listed as \"{}\"",
+            expected.as_str(),
+            label.trim()
+        ));
+    }
+
+    let link = pages
+        .find_with(client, "episodes.link", &[("title", &title_literal)])
+        .await
+        .with_context(|| format!("No episode link for \"{title}\" in episodes list"))?;
+    let href = link.attr("href").await?.unwrap_or_default();
+    let base = client.current_url().await?;
+    let (remote_id, url) = parse_episode_link(&base, &href)
+        .ok_or_else(|| anyhow!("No Spotify episode ID in link of \"{title}\": {href}"))?;
+    info!(status = %expected, episode_id = %remote_id, "Episode verified on episodes list");
+
+    Ok(Published {
+        status: expected,
+        remote_id: Some(remote_id),
+        url: Some(url),
+    })
+}
+
+/// Opens the page of an uploaded episode, from its recorded URL or, without one,
+/// by following its link on the episodes list.
+///
+/// # Errors
+///
+/// Returns an error if the episode is not listed or its page does not open.
+async fn open_episode(client: &Client, pages: &Pages, episode: &SpotifyEpisode) -> Result<()> {
+    match &episode.url {
+        Some(url) => client.goto(url).await?,
+        None => {
+            client.goto(&pages.url(EPISODES_PATH)).await?;
+            pages.pause(Pause::Page).await;
+            let title_literal = xpath_literal(episode.title.trim());
+            pages
+                .wait_for_with(client, "episodes.link", &[("title", &title_literal)])
+                .await
+                .with_context(|| format!("Episode not found in episodes list: {}", episode.title))?
+                .click()
+                .await?;
+        }
+    }
+    pages.pause(Pause::Page).await;
+    debug!(title = %episode.title, "Episode page opened");
+
+    Ok(())
+}
+
+/// Opens the edit form from the episode page.
+async fn open_edit_form(client: &Client, pages: &Pages) -> Result<()> {
+    pages
+        .wait_for(client, "episode.edit_button")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::Page).await;
+    pages.wait_for(client, "wizard.title_input").await?;
+
+    Ok(())
+}
+
+/// Saves the edit form.
+async fn save_edit_form(client: &Client, pages: &Pages) -> Result<()> {
+    pages
+        .find(client, "edit.save_button")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::Page).await;
+    debug!("Episode changes saved");
+
+    Ok(())
+}
+
+/// Replaces the title and/or description of the open episode and saves it.
+async fn edit_details(
+    client: &Client,
+    pages: &Pages,
+    browser: BrowserKind,
+    title: Option<&str>,
+    description: Option<&str>,
+) -> Result<()> {
+    open_edit_form(client, pages).await?;
+
+    if let Some(title) = title {
+        let title_field = pages.find(client, "wizard.title_input").await?;
+        title_field.click().await?;
+        clear_field(pages, &title_field).await?;
+        title_field.send_keys(title).await?;
+        pages.pause(Pause::Field).await;
+        debug!("Title replaced");
+    }
+    if let Some(description) = description {
+        type_description(client, pages, browser, description).await?;
+        pages.pause(Pause::Field).await;
+        debug!("Description replaced");
     }
-    human_delay(19000, 22000).await; // Wait for UI to load
 
-    // Click 'Now' option
-    if let Ok(now_option) = client.find(Locator::Css("input#publish-date-now")).await {
-        now_option.click().await?;
-        debug!("Selected 'Now' for publishing");
+    save_edit_form(client, pages).await
+}
+
+/// Uploads `audio_path` in place of the audio of the open episode and saves it.
+async fn replace_audio(client: &Client, pages: &Pages, audio_path: &Path) -> Result<()> {
+    let audio_file_path = audio_path
+        .canonicalize()
+        .with_context(|| format!("Audio file not found: {}", audio_path.display()))?;
+    open_edit_form(client, pages).await?;
+
+    if let Some(replace_btn) = pages.try_find(client, "edit.replace_audio_button").await {
+        replace_btn.click().await?;
+        pages.pause(Pause::Dialog).await;
     }
-    human_delay(3000, 5000).await;
+    pages
+        .find(client, "edit.audio_input")
+        .await?
+        .send_keys(&audio_file_path.to_string_lossy())
+        .await?;
 
-    // Click 'Schedule' button
-    if let Ok(schedule_button) = client
-        .find(Locator::Css("button[form='review-form'][type='submit']"))
+    let size = std::fs::metadata(&audio_file_path)?.len();
+    let timeout = UploadTimeouts::from_env()?.for_size(size);
+    wait_for_upload(client, pages, timeout).await?;
+    pages.pause(Pause::Click).await;
+    debug!("Audio replaced");
+
+    save_edit_form(client, pages).await
+}
+
+/// Deletes the open episode and checks that it is gone from the episodes list.
+async fn delete_episode(client: &Client, pages: &Pages, title: &str) -> Result<()> {
+    if let Some(more_btn) = pages.try_find(client, "episode.more_button").await {
+        more_btn.click().await?;
+        pages.pause(Pause::Field).await;
+    }
+    pages
+        .wait_for(client, "episode.delete_option")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::Click).await;
+    pages
+        .wait_for(client, "episode.confirm_delete_button")
+        .await?
+        .click()
+        .await?;
+    pages.pause(Pause::Page).await;
+
+    client.goto(&pages.url(EPISODES_PATH)).await?;
+    pages.pause(Pause::Page).await;
+    let title_literal = xpath_literal(title.trim());
+    if pages
+        .try_find_with(client, "episodes.row", &[("title", &title_literal)])
         .await
+        .is_some()
     {
-        schedule_button.click().await?;
+        return Err(anyhow!(
+            "Episode \"{title}\" is still listed after deleting it"
+        ));
     }
-    human_delay(6000, 9000).await;
-    info!("Episode successfully published");
+    info!(title = %title, "Episode deleted");
+
+    Ok(())
+}
+
+/// Checks that an episode titled `title` is on the episodes list.
+async fn confirm_listed(client: &Client, pages: &Pages, title: &str) -> Result<()> {
+    client.goto(&pages.url(EPISODES_PATH)).await?;
+    pages.pause(Pause::Page).await;
+
+    let title_literal = xpath_literal(title.trim());
+    pages
+        .wait_for_with(client, "episodes.row", &[("title", &title_literal)])
+        .await
+        .with_context(|| format!("Episode not found in episodes list: {title}"))?;
+    info!(title = %title, "Episode change confirmed on episodes list");
 
     Ok(())
 }
+
+/// Returns the episode ID and absolute URL of an episode link such as
+/// `/pod/show/<show>/episode/<id>`, resolved against the page URL `base`.
+fn parse_episode_link(base: &reqwest::Url, href: &str) -> Option<(String, String)> {
+    let url = base.join(href).ok()?;
+    let mut segments = url.path_segments()?;
+    segments.find(|s| *s == "episode")?;
+    let id = segments.next().filter(|id| !id.is_empty())?.to_string();
+
+    Some((id, url.to_string()))
+}
+
+/// Quotes `text` as an XPath string literal, using `concat()` if it contains both
+/// quote characters
+fn xpath_literal(text: &str) -> String {
+    if !text.contains('\'') {
+        format!("'{text}'")
+    } else if !text.contains('"') {
+        format!("\"{text}\"")
+    } else {
+        let parts: Vec<String> = text.split('\'').map(|p| format!("'{p}'")).collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::ChallengeKind;
+    use crate::diagnostics::FailureDiagnostics;
+    use crate::mock_spotify::{MockOptions, MockSpotify, MOCK_EMAIL, MOCK_PASSWORD};
+    use crate::publish::PublishSchedule;
+    use std::path::PathBuf;
+    use tokio::sync::{Mutex, MutexGuard};
+
+    /// Serialises the end-to-end tests, which configure the automation through the
+    /// environment
+    static E2E: Mutex<()> = Mutex::const_new(());
+
+    /// Starts the mock Spotify pages and points the automation at them.
+    ///
+    /// Variables that would otherwise be loaded from `.env` are set to empty values,
+    /// so that a saved session, remote WebDriver or selector overrides are not used.
+    /// Pauses use the seeded `fast` profile, so runs are quick and repeatable.
+    async fn e2e_setup(options: MockOptions) -> (MutexGuard<'static, ()>, MockSpotify, PathBuf) {
+        let guard = E2E.lock().await;
+        let mock = MockSpotify::start(options).await;
+        let dir = env::temp_dir().join(format!("audio_epistles_e2e_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, value) in [
+            ("SPOTIFY_BASE_URL", mock.url.as_str()),
+            ("SPOTIFY_EMAIL", MOCK_EMAIL),
+            ("SPOTIFY_PASSWORD", MOCK_PASSWORD),
+            ("SPOTIFY_SESSION_KEY", ""),
+            ("SPOTIFY_SELECTORS_FILE", ""),
+            ("SPOTIFY_UPLOAD_TIMEOUT", "60"),
+            ("SPOTIFY_UPLOAD_SECS_PER_MB", "0"),
+            ("SPOTIFY_DELAY_PROFILE", "fast"),
+            ("SPOTIFY_DELAY_SEED", "1"),
+            ("SPOTIFY_CHALLENGE_WAIT", ""),
+            ("NOTIFY_WEBHOOK_URL", ""),
+            ("BROWSER_DEBUG_ADDRESS", ""),
+            ("WEBDRIVER_URL", ""),
+            ("BROWSER_HEADLESS", "true"),
+            (
+                "DIAGNOSTICS_DIR",
+                &dir.join("diagnostics").to_string_lossy(),
+            ),
+        ] {
+            env::set_var(name, value);
+        }
+
+        let audio = dir.join("episode.mp3");
+        std::fs::write(&audio, [0u8; 1024]).unwrap();
+        (guard, mock, audio)
+    }
+
+    fn e2e_episode(audio: &Path) -> Episode {
+        Episode {
+            title: "Walking by Faith | Oct 18, 2026".to_string(),
+            description: "Join us online.".to_string(),
+            audio_path: audio.to_string_lossy().to_string(),
+            season: Some(2),
+            episode_number: Some(14),
+            episode_type: EpisodeType::Bonus,
+            ..Default::default()
+        }
+    }
+
+    /// End-to-end tests drive headless Chrome against `mock_spotify.rs`; they need
+    /// Chrome and chromedriver and run with `cargo test e2e -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_upload_draft() {
+        let (_guard, mock, audio) = e2e_setup(MockOptions::default()).await;
+
+        let published = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap();
+        assert_eq!(published.status, EpisodeStatus::Draft);
+        assert_eq!(published.remote_id.as_deref(), Some("mock1"));
+        assert_eq!(
+            published.url,
+            Some(format!("{}/pod/show/mock/episode/mock1", mock.url))
+        );
+
+        let episodes = mock.episodes();
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(episodes[0].title, "Walking by Faith | Oct 18, 2026");
+        assert_eq!(episodes[0].description, "Join us online.");
+        assert_eq!(episodes[0].status, "draft");
+        assert_eq!(episodes[0].season, Some(2));
+        assert_eq!(episodes[0].episode_number, Some(14));
+        assert_eq!(episodes[0].episode_type, "bonus");
+        assert!(!episodes[0].explicit);
+        assert_eq!(episodes[0].audio.as_deref(), Some("episode.mp3"));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_publish_now_and_schedule() {
+        let (_guard, mock, audio) = e2e_setup(MockOptions::default()).await;
+
+        let published = upload(&e2e_episode(&audio), PublishMode::Now)
+            .await
+            .unwrap();
+        assert_eq!(published.status, EpisodeStatus::Published);
+
+        let schedule = PublishSchedule::parse("Sunday 18:00").unwrap();
+        let mut episode = e2e_episode(&audio);
+        episode.title = "Walking by Faith | Oct 25, 2026".to_string();
+        let published = upload(&episode, PublishMode::Schedule(schedule))
+            .await
+            .unwrap();
+        let at = published.status.publish_at().unwrap();
+        assert_eq!(at.format("%H:%M").to_string(), "18:00");
+
+        let episodes = mock.episodes();
+        assert_eq!(episodes[0].status, "published");
+        assert_eq!(episodes[1].status, "scheduled");
+        assert_eq!(
+            episodes[1].publish_at,
+            Some(at.format("%m/%d/%Y %I:%M %p").to_string())
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_missing_button_captures_diagnostics() {
+        let (_guard, mock, audio) = e2e_setup(MockOptions {
+            missing: vec!["wizard.save_draft_button"],
+            ..Default::default()
+        })
+        .await;
+
+        let err = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("Save draft dialog did not open"));
+        let failure = err.downcast_ref::<FailureDiagnostics>().unwrap();
+        assert_eq!(failure.step, "create_episode");
+        assert!(failure.dir.join("screenshot.png").is_file());
+        assert!(failure.dir.join("page.html").is_file());
+        assert!(mock.episodes().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_slow_and_rejected_upload() {
+        let (_guard, _mock, audio) = e2e_setup(MockOptions {
+            upload_secs: 120,
+            ..Default::default()
+        })
+        .await;
+        env::set_var("SPOTIFY_UPLOAD_TIMEOUT", "5");
+        let err = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<UploadError>() {
+            Some(UploadError::StillRunning { progress, .. }) => {
+                assert!(progress.is_some_and(|p| p < 100), "{progress:?}")
+            }
+            other => panic!("Expected a running upload, got {other:?}"),
+        }
+        drop(_guard);
+
+        let (_guard, _mock, audio) = e2e_setup(MockOptions {
+            reject_upload: true,
+            ..Default::default()
+        })
+        .await;
+        let err = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<UploadError>(),
+            Some(&UploadError::Rejected(
+                "Upload failed: this file type is not supported".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_pasted_and_typed_description() {
+        let description =
+            "Join us online.\n\nNotes: https://example.com/notes?a=1&b=2\n00:00 Welcome";
+        let mut episode = e2e_episode(Path::new(""));
+
+        for ignore_paste in [false, true] {
+            let (_guard, mock, audio) = e2e_setup(MockOptions {
+                ignore_paste,
+                ..Default::default()
+            })
+            .await;
+            episode.audio_path = audio.to_string_lossy().to_string();
+            episode.description = description.to_string();
+
+            upload(&episode, PublishMode::Draft).await.unwrap();
+            let saved = &mock.episodes()[0].description;
+            assert!(editor::same_text(description, saved), "{saved:?}");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_captcha_stops_or_waits_for_operator() {
+        let captcha = || MockOptions {
+            captcha: true,
+            ..Default::default()
+        };
+
+        let (_guard, mock, audio) = e2e_setup(captcha()).await;
+        let err = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap_err();
+        let challenge = err.downcast_ref::<ChallengeError>().unwrap();
+        assert_eq!(challenge.kind, ChallengeKind::Captcha);
+        assert_eq!(challenge.url, format!("{}/challenge", mock.url));
+        assert!(mock.episodes().is_empty());
+        drop(_guard);
+
+        let (_guard, mock, audio) = e2e_setup(captcha()).await;
+        env::set_var("SPOTIFY_CHALLENGE_WAIT", "60");
+        let operator = mock.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(3)).await;
+            operator.solve_captcha();
+        });
+        let published = upload(&e2e_episode(&audio), PublishMode::Draft)
+            .await
+            .unwrap();
+        assert_eq!(published.status, EpisodeStatus::Draft);
+        assert_eq!(mock.episodes().len(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_e2e_edit_and_delete_episode() {
+        let (_guard, mock, _audio) = e2e_setup(MockOptions::default()).await;
+        mock.add_episode("Walking by Faith", "draft");
+
+        let episode = SpotifyEpisode {
+            title: "Walking by Faith".to_string(),
+            url: None,
+        };
+        let change = Change::Edit {
+            title: Some("Walking by Faith, Part 2".to_string()),
+            description: Some("Join us online.".to_string()),
+        };
+        change_episode(&episode, &change).await.unwrap();
+        assert_eq!(mock.episodes()[0].title, "Walking by Faith, Part 2");
+        assert_eq!(mock.episodes()[0].description, "Join us online.");
+
+        let episode = SpotifyEpisode {
+            title: "Walking by Faith, Part 2".to_string(),
+            url: Some(format!("{}/pod/show/mock/episode/mock1", mock.url)),
+        };
+        change_episode(&episode, &Change::Delete).await.unwrap();
+        assert!(mock.episodes().is_empty());
+    }
+
+    #[test]
+    fn test_upload_timeout_for_size() {
+        let timeouts = UploadTimeouts::default();
+        assert_eq!(timeouts.for_size(0), Duration::from_secs(120));
+        assert_eq!(timeouts.for_size(1), Duration::from_secs(130));
+        // A 90-minute sermon at 128 kbps is about 82 MiB
+        assert_eq!(
+            timeouts.for_size(82 * 1024 * 1024),
+            Duration::from_secs(940)
+        );
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(parse_progress("Uploading 42%"), Some(42));
+        assert_eq!(parse_progress("100 %"), Some(100));
+        assert_eq!(parse_progress("Processing"), None);
+        assert_eq!(parse_progress("250%"), None);
+        assert_eq!(parse_progress("Uploading 42.5%"), Some(42));
+        assert_eq!(parse_progress("100.0%"), Some(100));
+    }
+
+    #[test]
+    fn test_parse_progress_value() {
+        assert_eq!(parse_progress_value("100.0"), Some(100));
+        assert_eq!(parse_progress_value("100"), Some(100));
+        assert_eq!(parse_progress_value(" 42.7 "), Some(42));
+        assert_eq!(parse_progress_value("99.99"), Some(99));
+        assert_eq!(parse_progress_value("0"), Some(0));
+        assert_eq!(parse_progress_value("100.5"), None);
+        assert_eq!(parse_progress_value("-1"), None);
+        assert_eq!(parse_progress_value(""), None);
+    }
+
+    #[test]
+    fn test_upload_error_messages() {
+        let running = UploadError::StillRunning {
+            progress: Some(87),
+            waited: Duration::from_secs(300),
+        };
+        assert_eq!(
+            running.to_string(),
+            "Upload still running after 300s (87% uploaded)"
+        );
+        let rejected = UploadError::Rejected("File type not supported".to_string());
+        assert_eq!(
+            rejected.to_string(),
+            "Upload rejected by Spotify: File type not supported"
+        );
+    }
+
+    #[test]
+    fn test_parse_episode_link() {
+        let base = reqwest::Url::parse(&Pages::default().url(EPISODES_PATH)).unwrap();
+        assert_eq!(
+            parse_episode_link(&base, "/pod/show/audio-epistles/episode/e2f4a9b"),
+            Some((
+                "e2f4a9b".to_string(),
+                "https://podcasters.spotify.com/pod/show/audio-epistles/episode/e2f4a9b"
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            parse_episode_link(
+                &base,
+                "https://podcasters.spotify.com/pod/show/x/episode/abc/details?tab=1"
+            )
+            .map(|(id, _)| id),
+            Some("abc".to_string())
+        );
+        assert_eq!(parse_episode_link(&base, "/pod/dashboard/episodes"), None);
+        assert_eq!(parse_episode_link(&base, "/pod/show/x/episode/"), None);
+    }
+
+    #[test]
+    fn test_xpath_literal() {
+        assert_eq!(xpath_literal("Faith"), "'Faith'");
+        assert_eq!(xpath_literal("God's Love"), "\"God's Love\"");
+        assert_eq!(
+            xpath_literal(r#"God's "Love""#),
+            r#"concat('God', "'", 's "Love"')"#
+        );
+    }
+}